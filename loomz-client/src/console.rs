@@ -0,0 +1,607 @@
+//! Developer console. A drop-down gui with a scrollback log and a command line, toggled with the backquote key.
+//!
+//! The client modules register their commands with `Console::register`. The first word of a command line is the
+//! name of the command and the other words are its arguments. Arguments with spaces can be quoted: `say "hello world"`.
+//! Errors returned by the commands are printed in the log.
+use std::collections::VecDeque;
+use std::str::FromStr;
+use loomz_shared::store::{StoreAndLoad, SaveFileReaderBase, SaveFileWriterBase};
+use loomz_shared::{client_err, CommonError, RectF32, rect};
+use crate::LoomzClient;
+
+/// Layer of the console. Drawn over every other gui.
+const CONSOLE_GUI_LAYER: i32 = 110;
+
+/// Submit callback of the command line
+const CONSOLE_SUBMIT: u64 = 1100;
+
+/// Lines kept in the scrollback log
+const CONSOLE_LOG_SIZE: usize = 200;
+/// Longer log lines are truncated. Every character of the visible lines is a gui sprite.
+const CONSOLE_LINE_MAX_CHARS: usize = 200;
+/// Log lines scrolled by one step of the mouse wheel
+const CONSOLE_WHEEL_LINES: f32 = 3.0;
+/// Command lines kept in the history
+const CONSOLE_HISTORY_SIZE: usize = 50;
+
+/// Part of the screen height covered by the console
+const CONSOLE_SCREEN_HEIGHT: f32 = 0.4;
+const CONSOLE_LINE_HEIGHT: f32 = 20.0;
+const CONSOLE_INPUT_HEIGHT: f32 = 30.0;
+const CONSOLE_PADDING: f32 = 6.0;
+
+pub(crate) type ConsoleCallback = fn(&mut LoomzClient, &ConsoleArgs) -> Result<(), CommonError>;
+
+#[derive(Copy, Clone)]
+pub(crate) struct ConsoleCommand {
+    pub name: &'static str,
+    /// Arguments of the command, as shown by `help`. Ex: `<width> <height>`
+    pub usage: &'static str,
+    pub help: &'static str,
+    pub callback: ConsoleCallback,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum ConsoleLineType {
+    Input,
+    Output,
+    Error,
+}
+
+struct ConsoleLine {
+    ty: ConsoleLineType,
+    text: String,
+}
+
+/// Arguments of a console command. The name of the command is not included.
+pub(crate) struct ConsoleArgs {
+    command: ConsoleCommand,
+    values: Vec<String>,
+}
+
+impl ConsoleArgs {
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.values.get(index).map(|value| value.as_str() )
+    }
+
+    /// Returns an error if the command did not receive exactly `count` arguments
+    pub fn expect_count(&self, count: usize) -> Result<(), CommonError> {
+        match self.values.len() == count {
+            true => Ok(()),
+            false => Err(self.usage_err(&format!("expected {} argument(s), got {}", count, self.values.len()))),
+        }
+    }
+
+    /// Returns the argument at `index`, or an error if it is missing
+    pub fn str(&self, index: usize) -> Result<&str, CommonError> {
+        self.get(index)
+            .ok_or_else(|| self.usage_err(&format!("missing argument {}", index + 1)) )
+    }
+
+    pub fn parse<T: FromStr>(&self, index: usize) -> Result<T, CommonError> {
+        let value = self.str(index)?;
+        value.parse()
+            .map_err(|_| self.usage_err(&format!("invalid argument {:?}", value)) )
+    }
+
+    /// Parses `on` / `off`, `true` / `false` or `1` / `0`
+    pub fn bool(&self, index: usize) -> Result<bool, CommonError> {
+        match self.str(index)? {
+            "on" | "true" | "1" => Ok(true),
+            "off" | "false" | "0" => Ok(false),
+            value => Err(self.usage_err(&format!("invalid argument {:?}, expected on or off", value)))
+        }
+    }
+
+    pub fn usage_err(&self, message: &str) -> CommonError {
+        client_err!("{}: {}. Usage: {} {}", self.command.name, message, self.command.name, self.command.usage)
+    }
+
+}
+
+/// Commands, scrollback log and history of the console.
+/// The commands are not stored with the session. They are registered again when the client is reloaded.
+#[derive(Default)]
+pub(crate) struct Console {
+    commands: Vec<ConsoleCommand>,
+    log: VecDeque<ConsoleLine>,
+    history: Vec<String>,
+    /// Entry of the history shown in the command line. `history.len()` is the empty command line.
+    history_index: usize,
+    /// Number of log lines scrolled back from the end of the log. Not stored with the session.
+    scroll: usize,
+}
+
+impl Console {
+
+    /// Registers a command. A command registered with the same name replaces the old one.
+    pub fn register(&mut self, name: &'static str, usage: &'static str, help: &'static str, callback: ConsoleCallback) {
+        let command = ConsoleCommand { name, usage, help, callback };
+        match self.commands.iter_mut().find(|command| command.name == name ) {
+            Some(old) => { *old = command; },
+            None => { self.commands.push(command); }
+        }
+    }
+
+    pub fn print(&mut self, text: &str) {
+        self.push_lines(ConsoleLineType::Output, text);
+    }
+
+    /// Prints an error with the errors it was chained from
+    pub fn print_error(&mut self, err: &CommonError) {
+        self.push_lines(ConsoleLineType::Error, &err.to_string());
+    }
+
+    pub fn clear(&mut self) {
+        self.log.clear();
+        self.scroll = 0;
+    }
+
+    /// Selects the previous entry of the history. Returns `None` if there is no older entry.
+    fn history_prev(&mut self) -> Option<&str> {
+        if self.history_index == 0 {
+            return None;
+        }
+
+        self.history_index -= 1;
+        self.history.get(self.history_index).map(|line| line.as_str() )
+    }
+
+    /// Selects the next entry of the history. Past the last entry, returns an empty command line.
+    fn history_next(&mut self) -> Option<&str> {
+        if self.history_index >= self.history.len() {
+            return None;
+        }
+
+        self.history_index += 1;
+        match self.history.get(self.history_index) {
+            Some(line) => Some(line.as_str()),
+            None => Some("")
+        }
+    }
+
+    fn push_history(&mut self, line: &str) {
+        if self.history.last().map(|last| last != line ).unwrap_or(true) {
+            if self.history.len() >= CONSOLE_HISTORY_SIZE {
+                self.history.remove(0);
+            }
+
+            self.history.push(line.to_string());
+        }
+
+        self.history_index = self.history.len();
+    }
+
+    /// Adds lines at the end of the log and scrolls to the end of the log
+    fn push_lines(&mut self, ty: ConsoleLineType, text: &str) {
+        for line in text.lines() {
+            if self.log.len() >= CONSOLE_LOG_SIZE {
+                self.log.pop_front();
+            }
+
+            let text = match line.char_indices().nth(CONSOLE_LINE_MAX_CHARS) {
+                Some((end, _)) => format!("{}...", &line[..end]),
+                None => line.to_string(),
+            };
+
+            self.log.push_back(ConsoleLine { ty, text });
+        }
+
+        self.scroll = 0;
+    }
+
+    /// Scrolls the log by `lines` (positive values scroll back). Returns `true` if the visible lines changed.
+    fn scroll_by(&mut self, lines: isize, visible_lines: usize) -> bool {
+        let max_scroll = self.log.len().saturating_sub(visible_lines);
+        let scroll = self.scroll.saturating_add_signed(lines).min(max_scroll);
+        let changed = scroll != self.scroll;
+        self.scroll = scroll;
+        changed
+    }
+
+    /// Range of the log lines shown in a log view of `visible_lines` lines
+    fn visible_range(&self, visible_lines: usize) -> std::ops::Range<usize> {
+        let end = self.log.len() - self.scroll.min(self.log.len());
+        end.saturating_sub(visible_lines)..end
+    }
+
+    fn find(&self, name: &str) -> Option<ConsoleCommand> {
+        self.commands.iter().find(|command| command.name == name ).copied()
+    }
+
+    /// Splits a command line in words. Quoted words can contain spaces.
+    fn split(line: &str) -> Result<Vec<String>, CommonError> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        let mut in_word = false;
+        let mut quoted = false;
+
+        for c in line.chars() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                    in_word = true;
+                },
+                c if c.is_whitespace() && !quoted => {
+                    if in_word {
+                        words.push(::std::mem::take(&mut word));
+                        in_word = false;
+                    }
+                },
+                c => {
+                    word.push(c);
+                    in_word = true;
+                }
+            }
+        }
+
+        if quoted {
+            return Err(client_err!("Unterminated quote in command line"));
+        }
+
+        if in_word {
+            words.push(word);
+        }
+
+        Ok(words)
+    }
+
+}
+
+impl LoomzClient {
+
+    /// Registers the commands of the client modules. Called again when the client is reloaded.
+    pub(crate) fn register_console_commands(&mut self) {
+        let console = &mut self.console;
+        console.register("help", "[command]", "Lists the commands, or shows the help of a command", Self::console_help);
+        console.register("clear", "", "Clears the console log", |client, _| { client.console.clear(); Ok(()) });
+        console.register("history", "", "Prints the command history", Self::console_history);
+
+        Self::register_debug_commands(console);
+        Self::register_gameplay_commands(console);
+        Self::register_editor_commands(console);
+    }
+
+    /// Runs a command line and prints its errors in the log
+    pub(crate) fn run_console_command(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+
+        self.console.push_lines(ConsoleLineType::Input, &format!("> {line}"));
+        self.console.push_history(line);
+
+        if let Err(err) = self.run_console_command_inner(line) {
+            self.console.print_error(&err);
+        }
+    }
+
+    fn run_console_command_inner(&mut self, line: &str) -> Result<(), CommonError> {
+        let mut values = Console::split(line)?;
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        let name = values.remove(0);
+        let command = self.console.find(&name)
+            .ok_or_else(|| client_err!("Unknown command {name:?}. Type \"help\" to list the commands") )?;
+
+        let args = ConsoleArgs { command, values };
+        (command.callback)(self, &args)
+    }
+
+    fn console_help(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        let lines: Vec<String> = match args.get(0) {
+            Some(name) => {
+                let command = self.console.find(name)
+                    .ok_or_else(|| client_err!("Unknown command {name:?}") )?;
+                vec![format!("{} {}", command.name, command.usage), format!("    {}", command.help)]
+            },
+            None => {
+                self.console.commands.iter()
+                    .map(|command| format!("{} {} - {}", command.name, command.usage, command.help) )
+                    .collect()
+            }
+        };
+
+        for line in lines {
+            self.console.print(&line);
+        }
+
+        Ok(())
+    }
+
+    fn console_history(&mut self, _args: &ConsoleArgs) -> Result<(), CommonError> {
+        let history = self.console.history.join("\n");
+        self.console.print(&history);
+        Ok(())
+    }
+
+    /// Toggles the console with the backquote key, and runs the submitted command lines.
+    /// The up and down arrows browse the history of the command line. Page up, page down and the mouse wheel scroll the log.
+    pub(crate) fn update_console(&mut self) -> Result<(), CommonError> {
+        use loomz_shared::inputs::keys;
+
+        let [toggle, up, down, page_up, page_down] = match self.api.keys_ref().read_updates() {
+            Some(keys) => [
+                keys.just_pressed(keys::BACKQUOTE),
+                keys.just_pressed(keys::ARROW_UP),
+                keys.just_pressed(keys::ARROW_DOWN),
+                keys.just_pressed(keys::PAGE_UP),
+                keys.just_pressed(keys::PAGE_DOWN),
+            ],
+            None => [false; 5]
+        };
+
+        // The backquote key can be typed in the text inputs of the other guis
+        let other_focus = self.gui.has_text_focus() || self.context_gui.has_text_focus() || self.debug_gui.has_text_focus();
+        if toggle && !other_focus {
+            return self.set_console_visible(!self.console_gui.visible());
+        }
+
+        if !self.console_gui.visible() {
+            return Ok(());
+        }
+
+        while let Some(event) = self.console_gui.next_event::<u64>() {
+            if event == CONSOLE_SUBMIT {
+                let line = self.console_gui.text_input_value(CONSOLE_SUBMIT).unwrap_or("").to_string();
+                self.run_console_command(&line);
+
+                // The events of the previous console gui are dropped with it
+                return self.console_gui("");
+            }
+        }
+
+        // Only the visible lines of the log are built, so the log is scrolled by rebuilding the console
+        let visible_lines = self.console_visible_lines();
+        let page = visible_lines.saturating_sub(1).max(1) as isize;
+        let mut scroll = 0;
+        if page_up { scroll += page; }
+        if page_down { scroll -= page; }
+
+        let inputs = self.api.inputs();
+        if let Some(wheel) = inputs.mouse_wheel() {
+            if self.console_area().is_point_inside(inputs.cursor_position_value().as_f32()) {
+                scroll += (wheel.y * CONSOLE_WHEEL_LINES).round() as isize;
+            }
+        }
+
+        if scroll != 0 && self.console.scroll_by(scroll, visible_lines) {
+            let input = self.console_gui.text_input_value(CONSOLE_SUBMIT).unwrap_or("").to_string();
+            return self.console_gui(&input);
+        }
+
+        if self.console_gui.has_text_focus() && (up || down) {
+            let entry = match up {
+                true => self.console.history_prev(),
+                false => self.console.history_next(),
+            };
+
+            if let Some(entry) = entry.map(|entry| entry.to_string() ) {
+                self.console_gui(&entry)?;
+            }
+        } else if self.api.inputs().screen_size().is_some() {
+            let input = self.console_gui.text_input_value(CONSOLE_SUBMIT).unwrap_or("").to_string();
+            self.console_gui(&input)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_console_visible(&mut self, visible: bool) -> Result<(), CommonError> {
+        self.console.history_index = self.console.history.len();
+        match visible {
+            true => self.console_gui(""),
+            false => {
+                self.console_gui.toggle(&self.api, false);
+                Ok(())
+            }
+        }
+    }
+
+    /// Builds and shows the console with `input` in the command line. The command line receives the keyboard focus.
+    fn console_gui(&mut self, input: &str) -> Result<(), CommonError> {
+        use crate::gui::{GuiLayoutType, GuiLayoutPosition, GuiSize, GuiStyleState, GuiTextInputCallback, GuiEasing};
+        use loomz_shared::{rgb, rgba, pos};
+
+        let view = self.console_area();
+        let scale = self.console_gui.scale();
+        let log_height = self.console_log_height();
+
+        self.console_gui.set_layer(&self.api, CONSOLE_GUI_LAYER);
+        self.console_gui.build_style(&self.api, |style| {
+            style.root_layout(GuiLayoutType::VBox, GuiLayoutPosition::TopLeft);
+            style.frame("console_panel", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgba(20, 20, 24, 235));
+            style.frame("console_log_frame", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgba(0, 0, 0, 0));
+            style.frame("console_input_frame", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(40, 40, 46));
+            style.scroll_view("console_log", GuiStyleState::Base, "console_log_frame", rgb(40, 40, 46), rgb(140, 140, 150));
+            style.text_input("console_input", GuiStyleState::Base, "roboto", 18.0, rgb(230, 230, 230), "console_input_frame");
+            style.label("console_line_input", GuiStyleState::Base, "roboto", 16.0, rgb(150, 190, 250));
+            style.label("console_line_output", GuiStyleState::Base, "roboto", 16.0, rgb(210, 210, 210));
+            style.label("console_line_error", GuiStyleState::Base, "roboto", 16.0, rgb(240, 110, 100));
+            style.toggle_transition(0.15, GuiEasing::EaseOut, false, pos(0.0, -view.height() / scale));
+        })?;
+
+        let console = &self.console;
+        let visible_range = console.visible_range(self.console_visible_lines());
+        self.console_gui.build(&self.api, &self.strings, &view, |gui| {
+            gui.layout(GuiLayoutType::VBox, GuiLayoutPosition::TopLeft);
+            gui.layout_spacing(CONSOLE_PADDING);
+            gui.layout_item_sized(GuiSize::Percent(1.0), GuiSize::Percent(1.0));
+            gui.layout_item_padding(rect(CONSOLE_PADDING, CONSOLE_PADDING, CONSOLE_PADDING, CONSOLE_PADDING));
+            gui.frame("console_panel", |gui| {
+                gui.layout(GuiLayoutType::VBox, GuiLayoutPosition::TopLeft);
+                gui.layout_item_sized(GuiSize::Percent(1.0), GuiSize::Fixed(log_height));
+                gui.scroll_view("console_log", |gui| {
+                    for line in console.log.range(visible_range) {
+                        let style_key = match line.ty {
+                            ConsoleLineType::Input => "console_line_input",
+                            ConsoleLineType::Output => "console_line_output",
+                            ConsoleLineType::Error => "console_line_error",
+                        };
+
+                        gui.layout_item_sized(GuiSize::Percent(1.0), GuiSize::Fixed(CONSOLE_LINE_HEIGHT));
+                        gui.label(&line.text, style_key);
+                    }
+                });
+
+                gui.layout_item_sized(GuiSize::Percent(1.0), GuiSize::Fixed(CONSOLE_INPUT_HEIGHT));
+                gui.text_input_callback(GuiTextInputCallback::Submit, CONSOLE_SUBMIT);
+                gui.text_input(input, "", 256, "console_input");
+            });
+        })?;
+
+        if !self.console_gui.visible() {
+            self.console_gui.toggle(&self.api, true);
+        }
+
+        self.console_gui.focus_text_input(&self.api, CONSOLE_SUBMIT);
+        self.console_gui.scroll_to_end(&self.api);
+
+        Ok(())
+    }
+
+    /// Height of the log view, in unscaled gui pixels
+    fn console_log_height(&self) -> f32 {
+        let scale = self.console_gui.scale();
+        self.console_area().height() / scale - CONSOLE_INPUT_HEIGHT - CONSOLE_PADDING * 3.0
+    }
+
+    /// Number of log lines that fit in the log view
+    fn console_visible_lines(&self) -> usize {
+        usize::max((self.console_log_height() / CONSOLE_LINE_HEIGHT) as usize, 1)
+    }
+
+    /// Returns the screen area of the console, at the top of the screen
+    fn console_area(&self) -> RectF32 {
+        let screen_size = self.api.inputs().screen_size_value();
+        rect(0.0, 0.0, screen_size.width, screen_size.height * CONSOLE_SCREEN_HEIGHT)
+    }
+
+    /// Rebuilds the console after the scale or the language of the guis changed. Does nothing if the console is hidden.
+    pub(crate) fn rebuild_console_gui(&mut self) -> Result<(), CommonError> {
+        if !self.console_gui.visible() {
+            return Ok(());
+        }
+
+        let input = self.console_gui.text_input_value(CONSOLE_SUBMIT).unwrap_or("").to_string();
+        self.console_gui(&input)
+    }
+
+}
+
+impl StoreAndLoad for Console {
+    fn load(reader: &mut SaveFileReaderBase) -> Self {
+        let mut console = Console::default();
+
+        let log_count = reader.read_len(3);
+        for _ in 0..log_count {
+            let ty = match reader.read_u32() {
+                0 => ConsoleLineType::Input,
+                1 => ConsoleLineType::Output,
+                _ => ConsoleLineType::Error,
+            };
+
+            let text = reader.read_str().to_string();
+            console.log.push_back(ConsoleLine { ty, text });
+        }
+
+        let history_count = reader.read_len(2);
+        for _ in 0..history_count {
+            console.history.push(reader.read_str().to_string());
+        }
+
+        console.history_index = console.history.len();
+
+        console
+    }
+
+    fn store(&self, writer: &mut SaveFileWriterBase) {
+        writer.write_u32(self.log.len() as u32);
+        for line in self.log.iter() {
+            let ty = match line.ty {
+                ConsoleLineType::Input => 0,
+                ConsoleLineType::Output => 1,
+                ConsoleLineType::Error => 2,
+            };
+
+            writer.write_u32(ty);
+            writer.write_str(&line.text);
+        }
+
+        writer.write_u32(self.history.len() as u32);
+        for line in self.history.iter() {
+            writer.write_str(line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod console_tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> ConsoleArgs {
+        let command = ConsoleCommand { name: "test", usage: "<a> <b>", help: "", callback: |_, _| Ok(()) };
+        ConsoleArgs { command, values: values.iter().map(|value| value.to_string() ).collect() }
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(Console::split("  map.new   64 32 ").unwrap(), ["map.new", "64", "32"]);
+        assert_eq!(Console::split("say \"hello world\" x\"y z\"").unwrap(), ["say", "hello world", "xy z"]);
+        assert_eq!(Console::split("author \"\"").unwrap(), ["author", ""]);
+        assert!(Console::split("").unwrap().is_empty());
+        assert!(Console::split("say \"hello").is_err());
+    }
+
+    #[test]
+    fn history() {
+        let mut console = Console::default();
+        assert!(console.history_prev().is_none());
+
+        console.push_history("a");
+        console.push_history("b");
+        console.push_history("b");
+        assert_eq!(console.history, ["a", "b"]);
+
+        assert_eq!(console.history_prev(), Some("b"));
+        assert_eq!(console.history_prev(), Some("a"));
+        assert_eq!(console.history_prev(), None);
+        assert_eq!(console.history_next(), Some("b"));
+        assert_eq!(console.history_next(), Some(""));
+        assert_eq!(console.history_next(), None);
+
+        for index in 0..CONSOLE_HISTORY_SIZE {
+            console.push_history(&index.to_string());
+        }
+        assert!(console.history.len() == CONSOLE_HISTORY_SIZE);
+        assert_eq!(console.history[0], "0");
+        assert!(console.history_index == CONSOLE_HISTORY_SIZE);
+    }
+
+    #[test]
+    fn parse_args() {
+        let args = args(&["12", "-3", "on", "word"]);
+        assert!(args.parse::<u32>(0).unwrap() == 12);
+        assert!(args.parse::<i32>(1).unwrap() == -3);
+        assert!(args.parse::<u32>(1).is_err());
+        assert!(args.parse::<u32>(4).is_err());
+        assert!(args.bool(2).unwrap());
+        assert!(args.bool(3).is_err());
+        assert!(args.str(3).unwrap() == "word");
+    }
+
+    #[test]
+    fn expect_count() {
+        let args = args(&["1", "2"]);
+        assert!(args.expect_count(2).is_ok());
+        assert!(args.expect_count(1).is_err());
+        assert!(args.expect_count(3).is_err());
+
+        let message = args.expect_count(3).unwrap_err().inner.message;
+        assert!(message.contains("Usage: test <a> <b>"));
+    }
+}
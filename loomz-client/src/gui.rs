@@ -4,32 +4,98 @@ pub use style::{GuiStyleBuilder, GuiStyleState};
 
 mod callbacks;
 use callbacks::{IntoGuiCallback, GuiComponentCallbacksValue, RawCallbackValue};
+pub use callbacks::GuiPointerCallback;
 
 mod components;
-use components::{GuiLabel, GuiComponentBase, GuiComponentData};
-pub use components::GuiLabelCallback;
+use components::{GuiLabel, GuiTextInput, GuiDropdown, GuiMenu, GuiMenuItem, GuiMenuItemType, GuiMenuAccelerator,
+    GuiScrollDrag, GuiComponentBase, GuiComponentData};
+pub use components::{GuiLabelCallback, GuiTextInputCallback, GuiCheckboxCallback, GuiSliderCallback, GuiDropdownCallback,
+    GuiImageCallback, GuiImageFit};
 
 mod layout;
 use layout::{GuiLayout, GuiLayoutItem};
-pub use layout::{GuiLayoutType, GuiLayoutPosition};
+pub use layout::{GuiLayoutType, GuiLayoutPosition, GuiSize};
+
+mod atlas;
+
+mod transitions;
+use transitions::{GuiToggleTransition, GuiStyleTransition, GuiComponentTween, GuiAnimations};
+pub use transitions::GuiEasing;
+
+mod tooltip;
+use tooltip::{GuiTooltip, GuiTooltipStyle, GuiTooltipState};
+
+mod json;
+
+mod markup;
 
 mod builder;
 use builder::GuiBuilder;
 
 use loomz_shared::base_types::{RectF32, PositionF32};
+use loomz_shared::assets::JsonId;
 use loomz_shared::api::{LoomzApi, GuiId, GuiSprite, GuiSpriteType};
 use loomz_shared::store::*;
-use loomz_shared::{CommonError, client_err};
+use loomz_shared::{CommonError, client_err, assets_err};
+use crate::localization::Localization;
+
+/// Distance in pixels the cursor must move after a press to start dragging a label or an image
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// Maximum time in seconds between the two clicks of a double click
+const DOUBLE_CLICK_DELAY: f32 = 0.4;
+
+/// Past this number of dirty components, all the sprites of the gui are sent to the engine
+const MAX_PATCHED_COMPONENTS: usize = 8;
+
+/// Inputs captured by a gui from the guis on the layers below it
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum GuiInputCapture {
+    None,
+    /// The cursor is over the gui. Mouse inputs are not sent to the guis below.
+    Cursor,
+    /// The gui is modal. No inputs are sent to the guis below.
+    All,
+}
 
 #[derive(Copy, Clone)]
 enum GuiInnerEvent {
-    Click
+    Click,
+    Change,
+    Submit,
+    RightClick,
+    DoubleClick,
+    HoverEnter,
+    HoverLeave,
+    Press,
+    Release,
+    DragStart,
+    DragMove,
+    DragEnd,
+}
+
+/// Mouse state used to detect the drags, the right clicks and the double clicks
+#[derive(Copy, Clone)]
+struct GuiPointerState {
+    /// Cursor position when the left button was pressed
+    press_position: PositionF32,
+    dragging: bool,
+    /// Scroll view whose content is dragged when the drag started on one of its children
+    scroll_drag_index: u32,
+    right_button_down: bool,
+    /// Component under the cursor when the right button was pressed
+    right_pressed_index: u32,
+    last_click_index: u32,
+    /// Seconds since the last click
+    last_click_elapsed: f32,
 }
 
 #[derive(Copy, Clone)]
 struct GuiComponentState {
     hovered_index: u32,
     selected_index: u32,
+    focused_index: u32,
+    expanded_index: u32,
 }
 
 struct GuiBuilderData {
@@ -39,6 +105,10 @@ struct GuiBuilderData {
     last_callbacks: GuiComponentCallbacksValue,
     root_layout_type: GuiLayoutType,
     root_layout_pos: GuiLayoutPosition,
+    /// Style keys loaded from json assets. Style keys are static strings, so each unique key is leaked once.
+    interned_keys: fnv::FnvHashSet<&'static str>,
+    /// Json asset the gui components were built from and its version
+    json_source: Option<(JsonId, u32)>,
 }
 
 pub struct Gui {
@@ -46,8 +116,13 @@ pub struct Gui {
 
     state: GuiComponentState,
     styles: Vec<GuiComponentStyle>,
+    style_transitions: Vec<GuiStyleTransition>,
+    toggle_transition: GuiToggleTransition,
+    tooltip_style: Option<GuiTooltipStyle>,
     callbacks: Vec<GuiComponentCallbacksValue>,
     callbacks_output: Vec<RawCallbackValue>,
+    /// Cursor position when each callback in `callbacks_output` was sent
+    callbacks_positions: Vec<PositionF32>,
     callbacks_iter: usize,
 
     base_view: RectF32,
@@ -55,12 +130,28 @@ pub struct Gui {
     layout_items: Vec<GuiLayoutItem>,
     component_base: Vec<GuiComponentBase>,
     component_data: Vec<GuiComponentData>,
+    component_clips: Vec<RectF32>,
+    /// Tooltips sorted by component index
+    tooltips: Vec<GuiTooltip>,
 
     sprites: Vec<GuiSprite>,
+    /// First sprite and sprite count of each component in `sprites`
+    component_sprites: Vec<(u32, u32)>,
+    /// Components whose sprites changed since the last sync. See `sync_dirty_components`.
+    dirty_components: Vec<u32>,
+    patch_sprites: Vec<GuiSprite>,
+    /// Keys handled by the gui during the last `read_inputs`. See `consumed_key`.
+    consumed_keys: Vec<u32>,
 
     builder_data: Box<GuiBuilderData>,
+    animations: GuiAnimations,
 
+    cursor_position: PositionF32,
+    pointer: GuiPointerState,
     visible: bool,
+    layer: i32,
+    modal: bool,
+    scale: f32,
 }
 
 impl Gui {
@@ -74,37 +165,143 @@ impl Gui {
         Ok(())
     }
 
-    pub fn build<F: FnOnce(&mut GuiBuilder)>(&mut self, api: &LoomzApi, view: &RectF32, cb: F) -> Result<(), CommonError> {
-        let mut builder = GuiBuilder::new(api, view, self);
+    /// Builds the gui components. `strings` are the localized strings used by the components built by key.
+    pub fn build<F: FnOnce(&mut GuiBuilder)>(&mut self, api: &LoomzApi, strings: &Localization, view: &RectF32, cb: F) -> Result<(), CommonError> {
+        let mut builder = GuiBuilder::new(api, strings, view, self);
         cb(&mut builder);
+        self.builder_data.json_source = None;
 
         self.check_errors()?;
 
+        self.finish_animations(api);
+
         self.layouts[0] = self.get_root_layout();
 
-        layout::compute(self);
+        self.compute_layout();
 
         self.sync_with_engine(api);
 
         Ok(())
     }
 
-    pub fn read_inputs(&mut self, api: &LoomzApi) {
+    /// Builds the styles defined in the json asset `json_key`. See the `json` module for the format.
+    pub fn build_style_from_json(&mut self, api: &LoomzApi, json_key: &str) -> Result<(), CommonError> {
+        let (_, json) = Self::parse_json(api, json_key)?;
+        self.build_style(api, |style| json::build_style(style, &json) )
+    }
+
+    /// Builds the components defined in the json asset `json_key`. `callbacks` maps the callback names used in the json to callback ids.
+    pub fn build_from_json(&mut self, api: &LoomzApi, strings: &Localization, view: &RectF32, json_key: &str, callbacks: &[(&str, u64)]) -> Result<(), CommonError> {
+        let (json_id, json) = Self::parse_json(api, json_key)?;
+        self.build(api, strings, view, |gui| json::build(gui, &json, callbacks) )?;
+        self.builder_data.json_source = Some((json_id, api.assets_ref().json_version(json_id)));
+        Ok(())
+    }
+
+    /// Returns `true` if the gui was built from a json asset and the asset was reloaded since. Always `false` without `reload-assets`.
+    pub fn json_changed(&self, api: &LoomzApi) -> bool {
+        match self.builder_data.json_source {
+            Some((id, version)) => api.assets_ref().json_version(id) != version,
+            None => false
+        }
+    }
+
+    fn parse_json(api: &LoomzApi, json_key: &str) -> Result<(JsonId, serde_json::Value), CommonError> {
+        let assets = api.assets_ref();
+        let json_id = assets.json_id_by_name(json_key)
+            .ok_or_else(|| assets_err!("Failed to find json {json_key:?}") )?;
+        let json_source = assets.json_by_name(json_key)
+            .ok_or_else(|| assets_err!("Failed to find json {json_key:?}") )?;
+        let json = serde_json::from_str(json_source.as_str())
+            .map_err(|err| assets_err!("Failed to parse json {json_key:?}: {err:?}") )?;
+
+        Ok((json_id, json))
+    }
+
+    /// Reads the inputs of a visible gui. `capture` is the inputs captured by the guis on the layers over this one.
+    /// Returns the inputs captured by this gui and the guis over it.
+    pub fn read_inputs(&mut self, api: &LoomzApi, capture: GuiInputCapture) -> GuiInputCapture {
+        self.consumed_keys.clear();
+        if !self.visible {
+            return capture;
+        }
+
         let inputs = api.inputs();
 
         let mut need_sync = false;
 
-        if let Some(cursor_position) = inputs.cursor_position() {
-            self.update_cursor_position(cursor_position.as_f32(), &mut need_sync);
+        // A component being dragged keeps receiving the mouse inputs
+        let mouse_captured = match capture {
+            GuiInputCapture::None => false,
+            GuiInputCapture::Cursor => self.state.selected_index == u32::MAX,
+            GuiInputCapture::All => true,
+        };
+
+        if mouse_captured {
+            self.clear_hover(&mut need_sync);
+        } else {
+            if let Some(cursor_position) = inputs.cursor_position() {
+                self.update_cursor_position(cursor_position.as_f32(), &mut need_sync);
+            }
+
+            if let Some(buttons) = inputs.mouse_buttons() {
+                self.update_mouse_button(api, buttons.left_button_down(), &mut need_sync);
+                self.update_right_button(buttons.right_button_down());
+            }
+
+            if let Some(wheel) = inputs.mouse_wheel() {
+                self.update_mouse_wheel(wheel.y, &mut need_sync);
+            }
         }
 
-        if let Some(buttons) = inputs.mouse_buttons() {
-            self.update_mouse_button(buttons.left_button_down(), &mut need_sync);
+        if capture != GuiInputCapture::All {
+            self.update_keyboard_navigation(api, &mut need_sync);
+
+            if self.has_text_focus() {
+                self.update_text_input(api);
+            }
         }
 
         if need_sync {
             self.sync_with_engine(api);
+        } else {
+            self.sync_dirty_components(api);
         }
+
+        let own_capture = if self.modal {
+            GuiInputCapture::All
+        } else if self.state.hovered_index != u32::MAX || self.state.selected_index != u32::MAX || self.state.expanded_index != u32::MAX {
+            GuiInputCapture::Cursor
+        } else {
+            GuiInputCapture::None
+        };
+
+        GuiInputCapture::max(capture, own_capture)
+    }
+
+    /// Guis with a higher layer are drawn over the guis with a lower layer and capture their inputs
+    pub fn set_layer(&mut self, api: &LoomzApi, layer: i32) {
+        self.layer = layer;
+        api.gui().set_gui_layer(&self.id, layer);
+    }
+
+    pub fn layer(&self) -> i32 {
+        self.layer
+    }
+
+    /// A visible modal gui blocks every input to the guis on the layers below it. Building the gui resets this flag.
+    pub fn set_modal(&mut self, modal: bool) {
+        self.modal = modal;
+    }
+
+    /// Multiplies the pixel sizes of the gui: fixed layout item sizes, margins, paddings, spacings, font sizes and frame borders.
+    /// Only applies to the styles and the components built after this call.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = f32::max(scale, 0.1);
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
     }
 
     pub fn resize(&mut self, api: &LoomzApi, view: &RectF32) {
@@ -121,68 +318,537 @@ impl Gui {
             None => {
                 self.callbacks_iter = 0;
                 self.callbacks_output.clear();
+                self.callbacks_positions.clear();
                 None
             }
         }
     }
 
+    /// Returns the cursor position when the last event returned by `next_event` was sent
+    pub fn event_position(&self) -> PositionF32 {
+        self.callbacks_positions.get(self.callbacks_iter.wrapping_sub(1))
+            .copied()
+            .unwrap_or(self.cursor_position)
+    }
+
+    /// Returns the value of the text input that was built with `callback_id` as its change or submit callback
+    pub fn text_input_value<ID: IntoGuiCallback>(&self, callback_id: ID) -> Option<&str> {
+        match self.find_component(callback_id.into_u64()) {
+            Some(GuiComponentData::TextInput(input)) => Some(&input.value),
+            _ => None
+        }
+    }
+
+    /// Returns the value of the checkbox that was built with `callback_id` as its change callback
+    pub fn checkbox_value<ID: IntoGuiCallback>(&self, callback_id: ID) -> Option<bool> {
+        match self.find_component(callback_id.into_u64()) {
+            Some(GuiComponentData::Checkbox(checkbox)) => Some(checkbox.checked),
+            _ => None
+        }
+    }
+
+    /// Returns the value of the slider that was built with `callback_id` as its change callback
+    pub fn slider_value<ID: IntoGuiCallback>(&self, callback_id: ID) -> Option<f32> {
+        match self.find_component(callback_id.into_u64()) {
+            Some(GuiComponentData::Slider(slider)) => Some(slider.value),
+            _ => None
+        }
+    }
+
+    /// Returns the index of the selected option of the dropdown that was built with `callback_id` as its change callback
+    pub fn dropdown_value<ID: IntoGuiCallback>(&self, callback_id: ID) -> Option<u32> {
+        match self.find_component(callback_id.into_u64()) {
+            Some(GuiComponentData::Dropdown(dropdown)) => Some(dropdown.selected),
+            _ => None
+        }
+    }
+
+    /// Returns the check state of the checkable menu item that was built with `callback_id`
+    pub fn menu_item_checked<ID: IntoGuiCallback>(&self, callback_id: ID) -> Option<bool> {
+        let callback = callback_id.into_u64();
+        self.component_data.iter()
+            .flat_map(|data| match data {
+                GuiComponentData::Menu(menu) => menu.items.iter(),
+                _ => Default::default(),
+            })
+            .find(|item| item.ty == GuiMenuItemType::Checkable && item.callback == callback )
+            .map(|item| item.checked )
+    }
+
+    /// Sets the check state of the checkable menu item that was built with `callback_id`
+    pub fn set_menu_item_checked<ID: IntoGuiCallback>(&mut self, api: &LoomzApi, callback_id: ID, checked: bool) {
+        let callback = callback_id.into_u64();
+        let item = self.component_data.iter_mut()
+            .flat_map(|data| match data {
+                GuiComponentData::Menu(menu) => menu.items.iter_mut(),
+                _ => Default::default(),
+            })
+            .find(|item| item.ty == GuiMenuItemType::Checkable && item.callback == callback );
+
+        if let Some(item) = item {
+            if item.checked != checked {
+                item.checked = checked;
+                self.sync_with_engine(api);
+            }
+        }
+    }
+
+    /// Replaces the text of a label without rebuilding the gui. `label` is the index of the label in the order the labels were built.
+    /// The layout is not computed again, so the new text should fit in the layout item of the label.
+    pub fn set_label_text(&mut self, api: &LoomzApi, label: usize, text: &str) {
+        let index = self.component_data.iter()
+            .enumerate()
+            .filter(|(_, data)| matches!(data, GuiComponentData::Label(_)) )
+            .nth(label)
+            .map(|(index, _)| index );
+
+        let index = match index {
+            Some(index) => index,
+            None => { return; }
+        };
+
+        let style = match self.styles.get(self.component_base[index].style_index as usize) {
+            Some(GuiComponentStyle::Label(style)) => style.base,
+            _ => unreachable!("Label styles are validated by the builder")
+        };
+
+        if let GuiComponentData::Label(label) = &mut self.component_data[index] {
+            let color = label.color;
+            *label = builder::build_label_component(api, text, &style);
+            label.color = color;
+            self.mark_dirty(index as u32);
+        }
+    }
+
+    /// Activates the menu items whose accelerator was just pressed, even if their menu is closed.
+    /// Like other keyboard shortcuts, this should not be called while a text input has the keyboard focus.
+    pub fn update_accelerators(&mut self, api: &LoomzApi) {
+        if !self.visible {
+            return;
+        }
+
+        let mut need_sync = false;
+        if let Some(keys) = api.keys_ref().read_updates() {
+            let ctrl = keys.ctrl();
+            for data in self.component_data.iter_mut() {
+                let menu = match data {
+                    GuiComponentData::Menu(menu) => menu,
+                    _ => { continue; }
+                };
+
+                for item in menu.items.iter_mut() {
+                    match item.accelerator {
+                        Some(accelerator) if accelerator.ctrl == ctrl && keys.just_pressed(accelerator.key) => {
+                            self.callbacks_output.push(item.activate());
+                            self.callbacks_positions.push(self.cursor_position);
+                            need_sync |= item.ty == GuiMenuItemType::Checkable;
+                        },
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if need_sync {
+            self.sync_with_engine(api);
+        }
+    }
+
+    /// Gives the keyboard focus to the text input that was built with `callback_id` as its change or submit callback.
+    /// The caret is moved to the end of the value.
+    pub fn focus_text_input<ID: IntoGuiCallback>(&mut self, api: &LoomzApi, callback_id: ID) {
+        let index = match self.find_component_index(callback_id.into_u64()) {
+            Some(index) => index,
+            None => { return; }
+        };
+
+        let mut need_sync = false;
+        if let Some(GuiComponentData::TextInput(input)) = self.component_data.get_mut(index) {
+            input.move_caret(input.graphemes_count(), false);
+            self.set_focus(index as u32, &mut need_sync);
+            self.sync_with_engine(api);
+        }
+    }
+
+    /// Scrolls the scroll views of the gui to the end of their content
+    pub fn scroll_to_end(&mut self, api: &LoomzApi) {
+        let mut moved = false;
+        for index in 0..self.component_data.len() {
+            let delta = match &self.component_data[index] {
+                GuiComponentData::ScrollView(scroll_view) => scroll_view.max_scroll() - scroll_view.scroll,
+                _ => { continue; }
+            };
+
+            moved |= self.scroll_by(index, delta);
+        }
+
+        if moved {
+            self.sync_with_engine(api);
+        }
+    }
+
+    /// Returns `true` if a text input has the keyboard focus. Keyboard shortcuts should be ignored while this is true.
+    pub fn has_text_focus(&self) -> bool {
+        let index = self.state.focused_index as usize;
+        self.visible && matches!(self.component_data.get(index), Some(GuiComponentData::TextInput(_)))
+    }
+
+    /// Returns `true` if the gui handled `key` during the last `read_inputs`. Handled keys should not trigger the game shortcuts.
+    pub fn consumed_key(&self, key: u32) -> bool {
+        self.consumed_keys.contains(&key)
+    }
+
+    /// Shows or hides the gui. If the gui style has a toggle transition, the gui is animated by `update_animations`.
+    /// The gui stops reading inputs as soon as it starts hiding.
     pub fn toggle(&mut self, api: &LoomzApi, visible: bool) {
+        let animations = &mut self.animations;
+        if self.toggle_transition.duration <= 0.0 {
+            animations.toggle_target = None;
+            animations.shown = visible as u8 as f32;
+            self.visible = visible;
+            api.gui().toggle_gui(&self.id, visible);
+            return;
+        }
+
+        if animations.toggle_target.is_none() {
+            animations.shown = self.visible as u8 as f32;
+        }
+
+        animations.toggle_target = Some(visible);
         self.visible = visible;
-        api.gui().toggle_gui(&self.id, visible);
+
+        if visible {
+            api.gui().toggle_gui(&self.id, true);
+        }
+
+        self.sync_with_engine(api);
+    }
+
+    /// Hides the gui without playing the toggle transition
+    pub fn hide_instantly(&mut self, api: &LoomzApi) {
+        self.animations.toggle_target = None;
+        self.animations.shown = 0.0;
+        self.visible = false;
+        api.gui().toggle_gui(&self.id, false);
+    }
+
+    /// Advances the toggle transition and the component tweens by `delta` seconds.
+    /// Sprites are only synced with the engine while an animation is running, or if the engine asked for them.
+    pub fn update_animations(&mut self, api: &LoomzApi, delta: f32) {
+        // The engine could not apply a patch of the sprites
+        let mut need_sync = api.gui().take_resend_request(&self.id);
+        let animations = &mut self.animations;
+        self.pointer.last_click_elapsed += delta;
+
+        if let Some(visible) = animations.toggle_target {
+            let step = delta / f32::max(self.toggle_transition.duration, f32::EPSILON);
+            animations.shown = match visible {
+                true => f32::min(animations.shown + step, 1.0),
+                false => f32::max(animations.shown - step, 0.0),
+            };
+
+            if animations.shown == visible as u8 as f32 {
+                animations.toggle_target = None;
+                if !visible {
+                    api.gui().toggle_gui(&self.id, false);
+                }
+            }
+
+            need_sync = true;
+        }
+
+        for i in 0..animations.tweens.len() {
+            let tween = &mut self.animations.tweens[i];
+            if tween.running() {
+                tween.elapsed += delta;
+                let index = tween.index;
+                self.mark_dirty(index);
+            }
+        }
+
+        self.animations.tweens.retain(|tween| tween.running() || tween.to_scale != 1.0 );
+
+        need_sync |= self.update_tooltip(delta);
+
+        if need_sync {
+            self.sync_with_engine(api);
+        } else {
+            self.sync_dirty_components(api);
+        }
     }
 
     pub fn visible(&self) -> bool {
         self.visible
     }
 
+    /// Returns `true` if the cursor is over a component of the gui
+    pub fn hovered(&self) -> bool {
+        self.visible && self.state.hovered_index != u32::MAX
+    }
+
+    /// Sends the layer, the visibility and the sprites of the gui to the engine.
+    /// Used when a gui is loaded from a session that was not saved by the running engine.
+    pub fn resync(&mut self, api: &LoomzApi) {
+        api.gui().set_gui_layer(&self.id, self.layer);
+        api.gui().toggle_gui(&self.id, self.visible);
+        self.sync_with_engine(api);
+    }
+
     fn sync_with_engine(&mut self, api: &LoomzApi) {
         self.generate_sprites();
+        self.dirty_components.clear();
         api.gui().update_gui(&self.id, &self.sprites);
     }
 
+    /// Flags the sprites of a component for the next sync. Use `need_sync` instead if the change affects other components.
+    fn mark_dirty(&mut self, index: u32) {
+        if !self.dirty_components.contains(&index) {
+            self.dirty_components.push(index);
+        }
+    }
+
+    /// Only sends the sprites of the dirty components to the engine. Does a full sync if the number of sprites
+    /// of a component changed, if too many components changed, if an expanded component changed, or if the sprites are being faded by the toggle transition.
+    fn sync_dirty_components(&mut self, api: &LoomzApi) {
+        if self.dirty_components.is_empty() {
+            return;
+        }
+
+        // The popup of an expanded component is generated after every other component
+        let expanded_dirty = self.dirty_components.contains(&self.state.expanded_index);
+        if expanded_dirty || self.dirty_components.len() > MAX_PATCHED_COMPONENTS || self.animations.toggle_target.is_some() {
+            self.sync_with_engine(api);
+            return;
+        }
+
+        let mut patch_sprites = std::mem::take(&mut self.patch_sprites);
+        for i in 0..self.dirty_components.len() {
+            let index = self.dirty_components[i] as usize;
+            let (first, count) = match self.component_sprites.get(index) {
+                Some(&range) => range,
+                None => { continue; }
+            };
+
+            patch_sprites.clear();
+            self.generate_component_sprites(index, &mut patch_sprites);
+
+            if patch_sprites.len() != count as usize {
+                self.patch_sprites = patch_sprites;
+                self.sync_with_engine(api);
+                return;
+            }
+
+            if count > 0 {
+                let first = first as usize;
+                self.sprites[first..(first + patch_sprites.len())].copy_from_slice(&patch_sprites);
+                api.gui().patch_gui(&self.id, first as u32, &patch_sprites);
+            }
+        }
+
+        self.patch_sprites = patch_sprites;
+        self.dirty_components.clear();
+    }
+
+    /// Shows the tooltip of the hovered component once it has been hovered long enough. Returns `true` if the tooltip was shown or hidden.
+    fn update_tooltip(&mut self, delta: f32) -> bool {
+        let hovered_index = match self.visible {
+            true => self.state.hovered_index,
+            false => u32::MAX,
+        };
+
+        let tooltip = self.tooltips.binary_search_by_key(&hovered_index, |tooltip| tooltip.index )
+            .map(|index| index as u32 )
+            .unwrap_or(u32::MAX);
+
+        let state = &mut self.animations.tooltip;
+        if state.tooltip != tooltip {
+            let was_shown = state.shown;
+            *state = GuiTooltipState { tooltip, ..Default::default() };
+            return was_shown;
+        }
+
+        let delay = match self.tooltip_style {
+            Some(style) => style.delay,
+            None => { return false; }
+        };
+
+        if tooltip == u32::MAX || state.shown {
+            return false;
+        }
+
+        state.hover_time += delta;
+        if state.hover_time >= delay {
+            state.shown = true;
+            state.position = self.cursor_position;
+            return true;
+        }
+
+        false
+    }
+
+    /// Jumps to the end of the toggle transition, drops the component tweens and hides the tooltip
+    fn finish_animations(&mut self, api: &LoomzApi) {
+        let animations = &mut self.animations;
+        animations.tweens.clear();
+        animations.tooltip = GuiTooltipState::default();
+
+        if let Some(visible) = animations.toggle_target.take() {
+            animations.shown = visible as u8 as f32;
+            if !visible {
+                api.gui().toggle_gui(&self.id, false);
+            }
+        }
+    }
+
+    /// Starts a color and scale tween from the current look of the component at `index`
+    fn start_tween(&self, index: u32, transition: GuiStyleTransition, new_state: GuiStyleState) -> GuiComponentTween {
+        let component_index = index as usize;
+        let current = self.animations.tweens.iter().find(|tween| tween.index == index );
+
+        let mut sprites = Vec::with_capacity(9);
+        self.component_data[component_index].generate_sprites(&self.layout_items[component_index], &mut sprites);
+        if let Some(tween) = current {
+            tween.apply_colors(&mut sprites);
+        }
+
+        let to_scale = match new_state {
+            GuiStyleState::Base => 1.0,
+            _ => transition.scale,
+        };
+
+        GuiComponentTween {
+            index,
+            elapsed: 0.0,
+            transition,
+            from_colors: sprites.iter().map(|sprite| sprite.color ).collect(),
+            from_scale: current.map(|tween| tween.scale() ).unwrap_or(1.0),
+            to_scale,
+        }
+    }
+
+    fn find_component(&self, callback_id: RawCallbackValue) -> Option<&GuiComponentData> {
+        self.find_component_index(callback_id)
+            .map(|index| &self.component_data[index] )
+    }
+
+    fn find_component_index(&self, callback_id: RawCallbackValue) -> Option<usize> {
+        self.component_base.iter()
+            .position(|base| {
+                self.callbacks.get(base.callbacks_index as usize)
+                    .map(|callbacks| callbacks.contains(callback_id) )
+                    .unwrap_or(false)
+            })
+    }
+
     fn inner_resize(&mut self, view: &RectF32) {
         self.base_view = *view;
+        self.compute_layout();
+    }
+
+    fn compute_layout(&mut self) {
         layout::compute(self);
+
+        // Layout computing is done without the scroll offsets. The size of scroll views may change if they have a flexible size.
+        for index in 0..self.component_data.len() {
+            if let GuiComponentData::ScrollView(scroll_view) = &mut self.component_data[index] {
+                scroll_view.size = self.layout_items[index].size;
+                scroll_view.set_scroll(scroll_view.scroll);
+                let (descendants, scroll) = (scroll_view.descendants, scroll_view.scroll);
+                self.translate_descendants(index, descendants, -scroll);
+            }
+        }
+
+        self.compute_clips();
     }
 
-    fn on_style_update(&mut self, old_state: GuiComponentState) {
-        let styles = &self.styles;
-        let base = &self.component_base;
-        let data = &mut self.component_data;
-        let state = self.state;
-        let component_count = data.len() as u32;
+    fn translate_descendants(&mut self, index: usize, descendants: u32, offset_y: f32) {
+        let start = index + 1;
+        let end = start + descendants as usize;
+        for item in self.layout_items[start..end].iter_mut() {
+            item.position.y += offset_y;
+        }
+    }
 
-        let mut update_style = |index: u32, new_state: GuiStyleState| {
-            let component_index = index as usize;
-            let style_index = base[component_index].style_index as usize;
-            let style = &styles[style_index];
-            data[component_index].update_style(style, new_state);
-        };
+    /// Computes the clipping rect of every component. Components outside of any scroll view are clipped to the gui view.
+    fn compute_clips(&mut self) {
+        let mut clips_stack: Vec<(usize, RectF32)> = Vec::with_capacity(4);
 
-        if old_state.selected_index != state.selected_index {
-            if state.selected_index < component_count {
-                update_style(state.selected_index, GuiStyleState::Selected);
-            } else {
-                if old_state.selected_index < component_count {
-                    update_style(old_state.selected_index, GuiStyleState::Base);
-                }
+        self.component_clips.clear();
 
-                if state.hovered_index < component_count {
-                    update_style(state.hovered_index, GuiStyleState::Hovered);
-                }
+        for (index, item) in self.layout_items.iter().enumerate() {
+            while clips_stack.last().map(|(end, _)| *end <= index ).unwrap_or(false) {
+                clips_stack.pop();
+            }
+
+            let clip = clips_stack.last().map(|(_, clip)| *clip ).unwrap_or(self.base_view);
+            self.component_clips.push(clip);
+
+            if let Some(GuiComponentData::ScrollView(scroll_view)) = self.component_data.get(index) {
+                let end = index + 1 + scroll_view.descendants as usize;
+                let view = RectF32::from_position_and_size(item.position, scroll_view.size);
+                clips_stack.push((end, clip.intersection(&view)));
             }
         }
+    }
+
+    fn is_visible(item: &GuiLayoutItem, clip: &RectF32) -> bool {
+        clip.has_area() && clip.intersects(&RectF32::from_position_and_size(item.position, item.size))
+    }
+
+    /// Scrolls the scroll view at `index` by `delta` pixels
+    fn scroll_by(&mut self, index: usize, delta: f32) -> bool {
+        let (descendants, moved) = match &mut self.component_data[index] {
+            GuiComponentData::ScrollView(scroll_view) => (scroll_view.descendants, scroll_view.set_scroll(scroll_view.scroll + delta)),
+            _ => { return false; }
+        };
+
+        self.apply_scroll(index, descendants, moved)
+    }
 
-        if state.selected_index == u32::MAX && old_state.hovered_index != state.hovered_index {
-            if old_state.hovered_index < component_count {
-                update_style(old_state.hovered_index, GuiStyleState::Base);
+    fn apply_scroll(&mut self, index: usize, descendants: u32, moved: f32) -> bool {
+        if moved == 0.0 {
+            return false;
+        }
+
+        self.translate_descendants(index, descendants, -moved);
+        self.compute_clips();
+        true
+    }
+
+    fn on_style_update(&mut self, old_state: GuiComponentState) {
+        let state = self.state;
+        let component_count = self.component_data.len() as u32;
+        let indices = [
+            old_state.hovered_index, old_state.selected_index, old_state.focused_index,
+            state.hovered_index, state.selected_index, state.focused_index,
+        ];
+
+        for (i, &index) in indices.iter().enumerate() {
+            if index >= component_count || indices[..i].contains(&index) {
+                continue;
             }
 
-            if state.hovered_index < component_count {
-                update_style(state.hovered_index, GuiStyleState::Hovered);
+            let new_state = state.style_state(index);
+            if new_state == old_state.style_state(index) {
+                continue;
             }
-        }
 
+            let component_index = index as usize;
+            let style_index = self.component_base[component_index].style_index as usize;
+            let transition = self.style_transitions.get(style_index).copied().unwrap_or_default();
+            let tween = match transition.duration > 0.0 {
+                true => Some(self.start_tween(index, transition, new_state)),
+                false => None,
+            };
+
+            self.component_data[component_index].update_style(&self.styles[style_index], new_state);
+            self.mark_dirty(index);
+
+            if let Some(tween) = tween {
+                self.animations.tweens.retain(|tween| tween.index != index );
+                self.animations.tweens.push(tween);
+            }
+        }
     }
 
     fn on_events(&mut self, component_index: usize, inner_event: GuiInnerEvent) {
@@ -191,39 +857,247 @@ impl Gui {
             let callbacks = self.callbacks[base.callbacks_index as usize];
             let callback_output = &mut self.callbacks_output;
             self.component_data[component_index].on_events(&callbacks, callback_output, inner_event);
+            self.callbacks_positions.resize(self.callbacks_output.len(), self.cursor_position);
+        }
+    }
+
+    fn on_hover_events(&mut self, old_hovered_index: u32, new_hovered_index: u32) {
+        let component_count = self.component_data.len() as u32;
+        if old_hovered_index < component_count {
+            self.on_events(old_hovered_index as usize, GuiInnerEvent::HoverLeave);
+        }
+
+        if new_hovered_index < component_count {
+            self.on_events(new_hovered_index as usize, GuiInnerEvent::HoverEnter);
+        }
+    }
+
+    /// Only labels and images send drag events. Other components handle the cursor movements themselves.
+    fn is_draggable(&self, index: usize) -> bool {
+        matches!(self.component_data.get(index), Some(GuiComponentData::Label(_) | GuiComponentData::Image(_)))
+    }
+
+    /// Returns `true` if the component uses the cursor movements while it is pressed. Dragging the other
+    /// components of a scroll view drags the content of the scroll view.
+    fn handles_drag(&self, index: usize) -> bool {
+        match self.component_data.get(index) {
+            Some(GuiComponentData::Slider(_) | GuiComponentData::TextInput(_) | GuiComponentData::ScrollView(_)) => true,
+            Some(GuiComponentData::Label(_) | GuiComponentData::Image(_)) => {
+                let callbacks_index = self.component_base[index].callbacks_index as usize;
+                match self.callbacks.get(callbacks_index) {
+                    Some(GuiComponentCallbacksValue::Label(values) | GuiComponentCallbacksValue::Image(values)) => values.get(GuiInnerEvent::DragStart) != u64::MAX,
+                    _ => false,
+                }
+            },
+            _ => false,
         }
     }
 
+    /// Returns the innermost scroll view with a scrollable content containing the component at `index`, or `u32::MAX`
+    fn scroll_view_of(&self, index: usize) -> u32 {
+        let mut scroll_view_index = u32::MAX;
+        for scroll_index in 0..usize::min(index, self.component_data.len()) {
+            if let GuiComponentData::ScrollView(scroll_view) = &self.component_data[scroll_index] {
+                if index <= scroll_index + scroll_view.descendants as usize && scroll_view.max_scroll() > 0.0 {
+                    scroll_view_index = scroll_index as u32;
+                }
+            }
+        }
+
+        scroll_view_index
+    }
+
     fn update_cursor_position(&mut self, position: PositionF32, need_sync: &mut bool) {
         let old_state = self.state;
         let mut new_hovered_index = u32::MAX;
 
+        let cursor_delta = position.y - self.cursor_position.y;
+        self.cursor_position = position;
+
+        // Scroll view dragging
+        if old_state.selected_index != u32::MAX {
+            let index = old_state.selected_index as usize;
+            if let GuiComponentData::ScrollView(scroll_view) = &mut self.component_data[index] {
+                let (descendants, moved) = match scroll_view.drag {
+                    GuiScrollDrag::Content => (scroll_view.descendants, scroll_view.set_scroll(scroll_view.scroll - cursor_delta)),
+                    GuiScrollDrag::Scrollbar => (scroll_view.descendants, scroll_view.scroll_from_scrollbar(&self.layout_items[index], position.y)),
+                    GuiScrollDrag::None => (0, 0.0),
+                };
+
+                *need_sync |= self.apply_scroll(index, descendants, moved);
+            }
+        }
+
+        // Scroll view dragging from one of its children
+        if old_state.selected_index != u32::MAX && self.pointer.scroll_drag_index != u32::MAX {
+            let index = self.pointer.scroll_drag_index as usize;
+            if !self.pointer.dragging && position.out_of_range(self.pointer.press_position, DRAG_THRESHOLD) {
+                self.pointer.dragging = true;
+            }
+
+            if self.pointer.dragging {
+                if let GuiComponentData::ScrollView(scroll_view) = &mut self.component_data[index] {
+                    let (descendants, moved) = (scroll_view.descendants, scroll_view.set_scroll(scroll_view.scroll - cursor_delta));
+                    *need_sync |= self.apply_scroll(index, descendants, moved);
+                }
+            }
+        }
+
+        // Mouse selection in the focused text input
+        if old_state.selected_index != u32::MAX && old_state.selected_index == old_state.focused_index {
+            let index = old_state.focused_index as usize;
+            if let GuiComponentData::TextInput(input) = &mut self.component_data[index] {
+                let caret = input.caret_from_position(&self.layout_items[index], position.x);
+                if caret != input.caret {
+                    input.move_caret(caret, true);
+                    self.mark_dirty(index as u32);
+                }
+            }
+        }
+
+        // Slider dragging
+        if old_state.selected_index != u32::MAX {
+            let index = old_state.selected_index as usize;
+            if let GuiComponentData::Slider(slider) = &mut self.component_data[index] {
+                if slider.set_value_from_position(&self.layout_items[index], position.x) {
+                    self.on_events(index, GuiInnerEvent::Change);
+                    self.mark_dirty(index as u32);
+                }
+            }
+        }
+
+        // Label and image dragging
+        if old_state.selected_index != u32::MAX && self.pointer.scroll_drag_index == u32::MAX && self.is_draggable(old_state.selected_index as usize) {
+            let index = old_state.selected_index as usize;
+            if self.pointer.dragging {
+                self.on_events(index, GuiInnerEvent::DragMove);
+            } else if position.out_of_range(self.pointer.press_position, DRAG_THRESHOLD) {
+                self.pointer.dragging = true;
+                self.on_events(index, GuiInnerEvent::DragStart);
+            }
+        }
+
         let mut index = 0;
         let max_components = self.layout_items.len();
         while index < max_components {
             let item = self.layout_items[index];
             let view = RectF32::from_position_and_size(item.position, item.size);
-            if view.is_point_inside(position)  {
+            if view.is_point_inside(position) && self.component_clips[index].is_point_inside(position) {
                 new_hovered_index = index as u32;
             }
 
             index += 1;
         }
 
+        // The options of an expanded dropdown are drawn over the other components
+        if let Some(GuiComponentData::Dropdown(dropdown)) = self.component_data.get_mut(old_state.expanded_index as usize) {
+            let item = &self.layout_items[old_state.expanded_index as usize];
+            let hovered_option = dropdown.option_at(item, position);
+            if hovered_option != u32::MAX {
+                new_hovered_index = old_state.expanded_index;
+            }
+
+            if hovered_option != dropdown.hovered_option {
+                dropdown.hovered_option = hovered_option;
+                *need_sync = true;
+            }
+        }
+
+        // Same for the popup of an expanded menu
+        if let Some(GuiComponentData::Menu(menu)) = self.component_data.get_mut(old_state.expanded_index as usize) {
+            let item = &self.layout_items[old_state.expanded_index as usize];
+            let hovered_item = menu.item_at(item, position);
+            if menu.popup_view(item).is_point_inside(position) {
+                new_hovered_index = old_state.expanded_index;
+            }
+
+            if hovered_item != menu.hovered_item {
+                menu.hovered_item = hovered_item;
+                *need_sync = true;
+            }
+
+            // Moving the cursor over another menu of the menu bar opens it
+            let other_menu = matches!(self.component_data.get(new_hovered_index as usize), Some(GuiComponentData::Menu(_)));
+            if other_menu && new_hovered_index != old_state.expanded_index {
+                self.close_expanded(need_sync);
+                self.expand_menu(new_hovered_index, need_sync);
+            }
+        }
+
         if old_state.hovered_index != new_hovered_index {
             self.state.hovered_index = new_hovered_index;
             self.on_style_update(old_state);
+            self.on_hover_events(old_state.hovered_index, new_hovered_index);
+        }
+    }
+
+    /// Closes the expanded dropdown or menu
+    fn close_expanded(&mut self, need_sync: &mut bool) {
+        match self.component_data.get_mut(self.state.expanded_index as usize) {
+            Some(GuiComponentData::Dropdown(dropdown)) => {
+                dropdown.expanded = false;
+                dropdown.hovered_option = u32::MAX;
+            },
+            Some(GuiComponentData::Menu(menu)) => {
+                menu.close();
+            },
+            _ => { return; }
+        }
+
+        self.state.expanded_index = u32::MAX;
+        *need_sync = true;
+    }
+
+    fn expand_menu(&mut self, index: u32, need_sync: &mut bool) {
+        if let Some(GuiComponentData::Menu(menu)) = self.component_data.get_mut(index as usize) {
+            menu.expanded = true;
+            self.state.expanded_index = index;
             *need_sync = true;
         }
     }
 
-    fn update_mouse_button(&mut self, left_button_pressed: bool, need_sync: &mut bool) {
+    /// Closes the expanded menu and opens the previous (`direction` = -1) or next (`direction` = 1) menu of the gui
+    fn expand_adjacent_menu(&mut self, direction: i32, need_sync: &mut bool) {
+        let count = self.component_data.len() as i32;
+        let mut index = self.state.expanded_index as i32;
+        for _ in 1..count {
+            index = (index + direction).rem_euclid(count);
+            if let GuiComponentData::Menu(_) = self.component_data[index as usize] {
+                self.close_expanded(need_sync);
+                self.expand_menu(index as u32, need_sync);
+                if self.state.focused_index != u32::MAX {
+                    self.set_focus(index as u32, need_sync);
+                }
+                return;
+            }
+        }
+    }
+
+    /// Clears the hovered component when the cursor is captured by another gui
+    fn clear_hover(&mut self, need_sync: &mut bool) {
+        let old_state = self.state;
+        if old_state.hovered_index == u32::MAX {
+            return;
+        }
+
+        self.state.hovered_index = u32::MAX;
+        self.on_style_update(old_state);
+        self.on_hover_events(old_state.hovered_index, u32::MAX);
+        *need_sync = true;
+    }
+
+    fn update_mouse_button(&mut self, api: &LoomzApi, left_button_pressed: bool, need_sync: &mut bool) {
         let old_state = self.state;
         let mut new_selected_index = u32::MAX;
         if left_button_pressed {
             new_selected_index = old_state.hovered_index;
         }
 
+        if left_button_pressed && old_state.selected_index == u32::MAX {
+            self.update_focus(api, old_state.hovered_index, need_sync);
+            self.on_press(old_state.hovered_index, need_sync);
+        }
+
         if old_state.selected_index != new_selected_index {
             self.state.selected_index = new_selected_index;
             self.on_style_update(old_state);
@@ -231,22 +1105,484 @@ impl Gui {
         }
 
         if !left_button_pressed && old_state.selected_index != u32::MAX {
-            if old_state.selected_index == old_state.hovered_index {
-                self.on_events(old_state.selected_index as usize, GuiInnerEvent::Click);
+            let index = old_state.selected_index as usize;
+            if let GuiComponentData::ScrollView(scroll_view) = &mut self.component_data[index] {
+                scroll_view.drag = GuiScrollDrag::None;
+            }
+
+            self.pointer.scroll_drag_index = u32::MAX;
+
+            self.on_events(index, GuiInnerEvent::Release);
+
+            if self.pointer.dragging {
+                self.pointer.dragging = false;
+                self.on_events(index, GuiInnerEvent::DragEnd);
+            } else if old_state.selected_index == old_state.hovered_index {
+                self.on_click(index, need_sync);
+                self.update_double_click(index);
             }
         }
     }
 
+    /// Sends a right click to the component under the cursor if the right button was pressed and released over it
+    fn update_right_button(&mut self, right_button_pressed: bool) {
+        let pointer = &mut self.pointer;
+        if pointer.right_button_down == right_button_pressed {
+            return;
+        }
+
+        pointer.right_button_down = right_button_pressed;
+
+        let hovered_index = self.state.hovered_index;
+        if right_button_pressed {
+            pointer.right_pressed_index = hovered_index;
+        } else {
+            let index = pointer.right_pressed_index;
+            pointer.right_pressed_index = u32::MAX;
+            if index != u32::MAX && index == hovered_index {
+                self.on_events(index as usize, GuiInnerEvent::RightClick);
+            }
+        }
+    }
+
+    fn update_double_click(&mut self, index: usize) {
+        let pointer = &mut self.pointer;
+        if pointer.last_click_index == index as u32 && pointer.last_click_elapsed <= DOUBLE_CLICK_DELAY {
+            pointer.last_click_index = u32::MAX;
+            self.on_events(index, GuiInnerEvent::DoubleClick);
+        } else {
+            pointer.last_click_index = index as u32;
+            pointer.last_click_elapsed = 0.0;
+        }
+    }
+
+    fn on_press(&mut self, index: u32, need_sync: &mut bool) {
+        // Pressing anywhere outside of an expanded dropdown or menu closes it
+        if self.state.expanded_index != index {
+            self.close_expanded(need_sync);
+        }
+
+        let index = index as usize;
+        let position = self.cursor_position;
+        self.pointer.press_position = position;
+        self.pointer.dragging = false;
+        self.pointer.scroll_drag_index = match self.handles_drag(index) {
+            true => u32::MAX,
+            false => self.scroll_view_of(index),
+        };
+
+        if index < self.component_data.len() {
+            self.on_events(index, GuiInnerEvent::Press);
+        }
+        match self.component_data.get_mut(index) {
+            Some(GuiComponentData::Slider(slider)) => {
+                if slider.set_value_from_position(&self.layout_items[index], position.x) {
+                    self.on_events(index, GuiInnerEvent::Change);
+                }
+                *need_sync = true;
+            },
+            Some(GuiComponentData::ScrollView(scroll_view)) => {
+                let item = &self.layout_items[index];
+                let on_scrollbar = scroll_view.scrollbar_view(item).map(|view| view.is_point_inside(position) ).unwrap_or(false);
+                match on_scrollbar {
+                    true => {
+                        scroll_view.drag = GuiScrollDrag::Scrollbar;
+                        let (descendants, moved) = (scroll_view.descendants, scroll_view.scroll_from_scrollbar(item, position.y));
+                        *need_sync |= self.apply_scroll(index, descendants, moved);
+                    },
+                    false => {
+                        scroll_view.drag = GuiScrollDrag::Content;
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /// Scrolls the innermost scroll view under the cursor
+    fn update_mouse_wheel(&mut self, wheel_y: f32, need_sync: &mut bool) {
+        let position = self.cursor_position;
+        let mut target = None;
+        for (index, data) in self.component_data.iter().enumerate() {
+            if let GuiComponentData::ScrollView(scroll_view) = data {
+                let item = &self.layout_items[index];
+                let view = RectF32::from_position_and_size(item.position, scroll_view.size);
+                if scroll_view.max_scroll() > 0.0 && view.is_point_inside(position) && self.component_clips[index].is_point_inside(position) {
+                    target = Some(index);
+                }
+            }
+        }
+
+        if let Some(index) = target {
+            *need_sync |= self.scroll_by(index, -wheel_y);
+        }
+    }
+
+    fn on_click(&mut self, index: usize, need_sync: &mut bool) {
+        let mut changed = false;
+        match &mut self.component_data[index] {
+            GuiComponentData::Checkbox(checkbox) => {
+                checkbox.toggle();
+                changed = true;
+            },
+            GuiComponentData::Dropdown(dropdown) => match dropdown.expanded {
+                true => {
+                    changed = dropdown.select_hovered();
+                    self.state.expanded_index = u32::MAX;
+                },
+                false => {
+                    dropdown.expanded = true;
+                    self.state.expanded_index = index as u32;
+                }
+            },
+            GuiComponentData::Menu(menu) => match menu.expanded {
+                true => {
+                    if let Some(callback) = menu.activate_hovered() {
+                        self.callbacks_output.push(callback);
+                        self.callbacks_positions.push(self.cursor_position);
+                    }
+                    self.state.expanded_index = u32::MAX;
+                },
+                false => {
+                    menu.expanded = true;
+                    self.state.expanded_index = index as u32;
+                }
+            },
+            _ => {}
+        }
+
+        match changed {
+            true => {
+                self.on_events(index, GuiInnerEvent::Change);
+                *need_sync = true;
+            },
+            false => {
+                self.on_events(index, GuiInnerEvent::Click);
+            }
+        }
+
+        *need_sync |= matches!(self.component_data[index], GuiComponentData::Dropdown(_) | GuiComponentData::Menu(_));
+    }
+
+    /// Updates the keyboard focus after a mouse press on the component at `index`. Only text inputs receive the focus from the mouse.
+    fn update_focus(&mut self, api: &LoomzApi, index: u32, need_sync: &mut bool) {
+        let old_focus = self.state.focused_index;
+        let position = self.cursor_position;
+
+        let focus = match self.component_data.get(index as usize) {
+            Some(GuiComponentData::TextInput(_)) => index,
+            _ => u32::MAX
+        };
+
+        self.set_focus(focus, need_sync);
+
+        if let Some(GuiComponentData::TextInput(input)) = self.component_data.get_mut(index as usize) {
+            let shift = api.keys_ref().read().shift();
+            let caret = input.caret_from_position(&self.layout_items[index as usize], position.x);
+            input.move_caret(caret, shift && old_focus == index);
+            *need_sync = true;
+        }
+    }
+
+    /// Moves the keyboard focus to the component at `index`. `u32::MAX` clears the focus.
+    fn set_focus(&mut self, index: u32, need_sync: &mut bool) {
+        let old_state = self.state;
+        if old_state.focused_index == index {
+            return;
+        }
+
+        if let Some(GuiComponentData::TextInput(input)) = self.component_data.get_mut(old_state.focused_index as usize) {
+            input.focused = false;
+            input.move_caret(input.caret, false);
+        }
+
+        if let Some(GuiComponentData::TextInput(input)) = self.component_data.get_mut(index as usize) {
+            input.focused = true;
+        }
+
+        self.state.focused_index = index;
+        self.on_style_update(old_state);
+        self.scroll_into_view(index as usize);
+        *need_sync = true;
+    }
+
+    /// Returns `true` if the component at `index` can receive the keyboard focus
+    fn is_focusable(&self, index: usize) -> bool {
+        match self.component_data[index] {
+            GuiComponentData::TextInput(_) | GuiComponentData::Checkbox(_) |
+            GuiComponentData::Slider(_) | GuiComponentData::Dropdown(_) | GuiComponentData::Menu(_) => true,
+            GuiComponentData::Label(_) | GuiComponentData::Image(_) => self.component_base[index].callbacks_index != u32::MAX,
+            GuiComponentData::Frame(_) | GuiComponentData::ScrollView(_) => false,
+        }
+    }
+
+    /// Moves the keyboard focus to the next (`direction` = 1) or previous (`direction` = -1) focusable component.
+    /// Components are ordered like the layout tree and the focus wraps around.
+    fn move_focus(&mut self, direction: i32, need_sync: &mut bool) {
+        let count = self.component_data.len() as i32;
+        if count == 0 {
+            return;
+        }
+
+        let mut index = match self.state.focused_index {
+            u32::MAX if direction > 0 => -1,
+            u32::MAX => count,
+            focused => focused as i32,
+        };
+
+        for _ in 0..count {
+            index = (index + direction).rem_euclid(count);
+            if self.is_focusable(index as usize) {
+                self.set_focus(index as u32, need_sync);
+                if let Some(GuiComponentData::TextInput(input)) = self.component_data.get_mut(index as usize) {
+                    input.move_caret(input.graphemes_count(), false);
+                }
+                return;
+            }
+        }
+    }
+
+    /// Scrolls the scroll views containing the component at `index` until the component is fully visible
+    fn scroll_into_view(&mut self, index: usize) {
+        for scroll_index in 0..usize::min(index, self.component_data.len()) {
+            let (descendants, size) = match &self.component_data[scroll_index] {
+                GuiComponentData::ScrollView(scroll_view) => (scroll_view.descendants as usize, scroll_view.size),
+                _ => { continue; }
+            };
+
+            if index > scroll_index + descendants {
+                continue;
+            }
+
+            let view = RectF32::from_position_and_size(self.layout_items[scroll_index].position, size);
+            let item = self.layout_items[index];
+            let bottom = item.position.y + item.size.height;
+            if item.position.y < view.top {
+                self.scroll_by(scroll_index, item.position.y - view.top);
+            } else if bottom > view.bottom {
+                self.scroll_by(scroll_index, bottom - view.bottom);
+            }
+        }
+    }
+
+    /// Menus navigation with the keyboard. Tab / Shift+Tab and the arrows move the focus, Enter and Space
+    /// activate the focused component and Escape closes the expanded dropdown or menu or clears the focus.
+    /// The left / right arrows move between the menus of a menu bar while a menu is expanded.
+    fn update_keyboard_navigation(&mut self, api: &LoomzApi, need_sync: &mut bool) {
+        use loomz_shared::inputs::keys;
+
+        // Keys are copied because `set_focus` may read the keys state again
+        let [tab, shift, up, down, left, right, enter, space, escape] = match api.keys_ref().read_updates() {
+            Some(keys) => [
+                keys.just_pressed(keys::TAB), keys.shift(),
+                keys.just_pressed(keys::ARROW_UP), keys.just_pressed(keys::ARROW_DOWN),
+                keys.just_pressed(keys::ARROW_LEFT), keys.just_pressed(keys::ARROW_RIGHT),
+                keys.just_pressed(keys::ENTER), keys.just_pressed(keys::SPACE), keys.just_pressed(keys::ESC),
+            ],
+            None => { return; }
+        };
+
+        // An expanded dropdown or menu captures the keyboard until it is closed
+        let expanded_index = self.state.expanded_index as usize;
+        if expanded_index < self.component_data.len() {
+            let direction = if up { -1 } else { 1 };
+            match &mut self.component_data[expanded_index] {
+                GuiComponentData::Dropdown(dropdown) if up || down => {
+                    dropdown.hover_next(direction);
+                    *need_sync = true;
+                },
+                GuiComponentData::Menu(menu) if up || down => {
+                    menu.hover_next(direction);
+                    *need_sync = true;
+                },
+                GuiComponentData::Menu(_) if left || right => {
+                    self.expand_adjacent_menu(if left { -1 } else { 1 }, need_sync);
+                    if let Some(GuiComponentData::Menu(menu)) = self.component_data.get_mut(self.state.expanded_index as usize) {
+                        menu.hover_next(0);
+                    }
+                    return;
+                },
+                _ => {}
+            }
+
+            if enter || space {
+                self.on_click(expanded_index, need_sync);
+            } else if escape {
+                self.close_expanded(need_sync);
+                self.consumed_keys.push(keys::ESC);
+            }
+
+            return;
+        }
+
+        if escape {
+            // Esc only clears the focus if a component has it. Otherwise it is left to the game.
+            if self.state.focused_index != u32::MAX {
+                self.set_focus(u32::MAX, need_sync);
+                self.consumed_keys.push(keys::ESC);
+            }
+            return;
+        }
+
+        // Text inputs use the left / right arrows, Enter and Space
+        let focused_index = self.state.focused_index as usize;
+        let text_focus = self.has_text_focus();
+
+        let mut direction = 0;
+        if tab { direction = if shift { -1 } else { 1 }; }
+        if up { direction = -1; }
+        if down { direction = 1; }
+
+        if !text_focus && (left || right) {
+            let step = if left { -1 } else { 1 };
+            match self.component_data.get_mut(focused_index) {
+                Some(GuiComponentData::Slider(slider)) => {
+                    if slider.step_by(step) {
+                        self.on_events(focused_index, GuiInnerEvent::Change);
+                        *need_sync = true;
+                    }
+                },
+                _ => { direction = step; }
+            }
+        }
+
+        if direction != 0 {
+            self.move_focus(direction, need_sync);
+            return;
+        }
+
+        if !text_focus && (enter || space) && focused_index < self.component_data.len() {
+            self.on_click(focused_index, need_sync);
+            match &mut self.component_data[focused_index] {
+                GuiComponentData::Dropdown(dropdown) if dropdown.expanded => dropdown.hover_next(0),
+                GuiComponentData::Menu(menu) if menu.expanded => menu.hover_next(0),
+                _ => {}
+            }
+        }
+    }
+
+    fn update_text_input(&mut self, api: &LoomzApi) {
+        use loomz_shared::inputs::keys;
+
+        let index = self.state.focused_index as usize;
+        let input = match self.component_data.get_mut(index) {
+            Some(GuiComponentData::TextInput(input)) => input,
+            _ => { return; }
+        };
+
+        let keys_state = api.keys_ref();
+        let mut changed = false;
+        let mut caret_moved = false;
+        let mut submit = false;
+
+        if let Some(keys) = keys_state.read_updates() {
+            let shift = keys.shift();
+            let old_caret = (input.caret, input.anchor);
+
+            // Held editing keys repeat
+            let pressed = |key: u32| keys.just_pressed(key) || keys_state.repeated(key);
+
+            if pressed(keys::ARROW_LEFT) { input.move_left(shift); }
+            if pressed(keys::ARROW_RIGHT) { input.move_right(shift); }
+            if pressed(keys::HOME) { input.move_caret(0, shift); }
+            if pressed(keys::END) { input.move_caret(input.graphemes_count(), shift); }
+            if keys.ctrl() && keys.just_pressed(keys::A) { input.select_all(); }
+            if pressed(keys::BACKSPACE) { changed |= input.backspace(api); }
+            if pressed(keys::DELETE) { changed |= input.delete(api); }
+            if keys.just_pressed(keys::ENTER) { submit = true; }
+
+            caret_moved = old_caret != (input.caret, input.anchor);
+        }
+
+        if let Some(text) = keys_state.text() {
+            changed |= input.insert(api, &text);
+        }
+
+        if changed || caret_moved {
+            self.mark_dirty(index as u32);
+        }
+
+        if changed {
+            self.on_events(index, GuiInnerEvent::Change);
+        }
+
+        if submit {
+            self.on_events(index, GuiInnerEvent::Submit);
+        }
+    }
+
+    /// Generates the sprites of the component at `index`, with its tween and clip applied. Hidden components have no sprites.
+    fn generate_component_sprites(&self, index: usize, sprites: &mut Vec<GuiSprite>) {
+        let view = &self.layout_items[index];
+        if !Self::is_visible(view, &self.component_clips[index]) {
+            return;
+        }
+
+        let first_sprite = sprites.len();
+        self.component_data[index].generate_sprites(view, sprites);
+
+        if let Some(tween) = self.animations.tweens.iter().find(|tween| tween.index == index as u32 ) {
+            tween.apply_colors(&mut sprites[first_sprite..]);
+            tween.apply_scale(view, &mut sprites[first_sprite..]);
+        }
+
+        let clip = self.component_clips[index];
+        for sprite in sprites[first_sprite..].iter_mut() {
+            sprite.clip = clip;
+        }
+    }
+
     fn generate_sprites(&mut self) {
-        let sprites = &mut self.sprites;
+        let mut sprites = std::mem::take(&mut self.sprites);
         sprites.clear();
+        self.component_sprites.clear();
 
         let component_count = self.layout_items.len();
         for i in 0..component_count {
-            let view = &self.layout_items[i];
-            let component_type = &self.component_data[i];
-            component_type.generate_sprites(view, sprites);
+            let first_sprite = sprites.len();
+            self.generate_component_sprites(i, &mut sprites);
+            self.component_sprites.push((first_sprite as u32, (sprites.len() - first_sprite) as u32));
+        }
+
+
+        let expanded_index = self.state.expanded_index as usize;
+        if let Some(view) = self.layout_items.get(expanded_index) {
+            let first_sprite = sprites.len();
+            match &self.component_data[expanded_index] {
+                GuiComponentData::Dropdown(dropdown) => dropdown.generate_options_sprites(view, &mut sprites),
+                GuiComponentData::Menu(menu) => menu.generate_popup_sprites(view, &mut sprites),
+                _ => {}
+            }
+
+            for sprite in sprites[first_sprite..].iter_mut() {
+                sprite.clip = self.base_view;
+            }
+        }
+
+        let tooltip_state = &self.animations.tooltip;
+        if let (true, Some(style)) = (tooltip_state.shown, self.tooltip_style.as_ref()) {
+            if let Some(tooltip) = self.tooltips.get(tooltip_state.tooltip as usize) {
+                let first_sprite = sprites.len();
+                tooltip.generate_sprites(style, tooltip_state.position, &self.base_view, &mut sprites);
+
+                for sprite in sprites[first_sprite..].iter_mut() {
+                    sprite.clip = self.base_view;
+                }
+            }
         }
+
+        if self.animations.toggle_target.is_some() {
+            let transition = &self.toggle_transition;
+            let opacity = transition.opacity(self.animations.shown);
+            let offset = transition.offset(self.animations.shown);
+            for sprite in sprites.iter_mut() {
+                sprite.color.a = (sprite.color.a as f32 * opacity).round() as u8;
+                sprite.position = sprite.position.translate_into(offset.x, offset.y);
+                sprite.clip = sprite.clip.translate_into(offset.x, offset.y);
+            }
+        }
+
+        self.sprites = sprites;
     }
 
     fn get_root_layout(&self) -> GuiLayout {
@@ -288,13 +1624,73 @@ impl Gui {
                     writer.write(&text.font);
                     writer.write_into_u32(text.color);
                     writer.write_slice(&text.glyphs);
+                    writer.write_slice(&text.runs);
+                },
+                GuiComponentData::TextInput(input) => {
+                    writer.write_u32(2);
+                    writer.write(&input.style);
+                    writer.write_str(&input.value);
+                    writer.write_str(&input.placeholder);
+                    writer.write_u32(input.max_length);
+                    writer.write_u32(input.caret);
+                    writer.write_u32(input.anchor);
+                    writer.write_into_u32(input.focused);
+                    writer.write_slice(&input.glyphs);
+                    writer.write_slice(&input.offsets);
+                    writer.write_slice(&input.bytes);
+                },
+                GuiComponentData::Checkbox(checkbox) => {
+                    writer.write_u32(3);
+                    writer.write(checkbox);
+                },
+                GuiComponentData::Slider(slider) => {
+                    writer.write_u32(4);
+                    writer.write(slider);
+                },
+                GuiComponentData::Dropdown(dropdown) => {
+                    writer.write_u32(5);
+                    writer.write(&dropdown.style);
+                    writer.write_u32(dropdown.selected);
+                    writer.write_u32(dropdown.hovered_option);
+                    writer.write_into_u32(dropdown.expanded);
+                    writer.write_u32(dropdown.options.len() as u32);
+                    for option in dropdown.options.iter() {
+                        writer.write_slice(&option.glyphs);
+                    }
+                },
+                GuiComponentData::ScrollView(scroll_view) => {
+                    writer.write_u32(6);
+                    writer.write(scroll_view);
+                },
+                GuiComponentData::Image(image) => {
+                    writer.write_u32(7);
+                    writer.write(image);
+                },
+                GuiComponentData::Menu(menu) => {
+                    writer.write_u32(8);
+                    writer.write(&menu.style);
+                    writer.write_u32(menu.hovered_item);
+                    writer.write_into_u32(menu.expanded);
+                    writer.write_slice(&menu.title.glyphs);
+                    writer.write_u32(menu.items.len() as u32);
+                    for item in menu.items.iter() {
+                        let accelerator = item.accelerator.unwrap_or(GuiMenuAccelerator { key: u32::MAX, ctrl: false });
+                        writer.write_u32(item.ty as u32);
+                        writer.write_into_u32(item.checked);
+                        writer.write_u32(item.callback as u32);
+                        writer.write_u32((item.callback >> 32) as u32);
+                        writer.write_u32(accelerator.key);
+                        writer.write_into_u32(accelerator.ctrl);
+                        writer.write_slice(&item.label.glyphs);
+                        writer.write_slice(&item.accelerator_label.glyphs);
+                    }
                 }
             }
         }
     }
 
     fn load_components_data(&mut self, reader: &mut SaveFileReaderBase) {
-        let component_types_count = reader.read_u32() as usize;
+        let component_types_count = reader.read_len(1);
         self.component_data = Vec::with_capacity(component_types_count);
         for _ in 0..component_types_count {
             let enum_identifier = reader.read_u32();
@@ -306,10 +1702,106 @@ impl Gui {
                     let font = reader.read();
                     let color = reader.read_from_u32();
                     let glyphs = reader.read_slice().to_vec().into_boxed_slice();
+                    let runs = reader.read_slice().to_vec().into_boxed_slice();
                     self.component_data.push(GuiComponentData::Label(GuiLabel {
                         font,
                         color,
                         glyphs,
+                        runs,
+                    }));
+                },
+                2 => {
+                    let style = reader.read();
+                    let value = reader.read_str().to_string();
+                    let placeholder = reader.read_str().to_string();
+                    self.component_data.push(GuiComponentData::TextInput(GuiTextInput {
+                        style,
+                        value,
+                        placeholder,
+                        max_length: reader.read_u32(),
+                        caret: reader.read_u32(),
+                        anchor: reader.read_u32(),
+                        focused: reader.read_bool(),
+                        glyphs: reader.read_slice().to_vec(),
+                        offsets: reader.read_slice().to_vec(),
+                        bytes: reader.read_slice().to_vec(),
+                    }));
+                },
+                3 => {
+                    self.component_data.push(GuiComponentData::Checkbox(reader.read()));
+                },
+                4 => {
+                    self.component_data.push(GuiComponentData::Slider(reader.read()));
+                },
+                5 => {
+                    let style: components::GuiDropdownStyle = reader.read();
+                    let selected = reader.read_u32();
+                    let hovered_option = reader.read_u32();
+                    let expanded = reader.read_bool();
+                    let options_count = reader.read_len(1);
+                    let mut options = Vec::with_capacity(options_count);
+                    for _ in 0..options_count {
+                        options.push(GuiLabel {
+                            font: style.font,
+                            color: style.color,
+                            glyphs: reader.read_slice().to_vec().into_boxed_slice(),
+                            runs: Box::default(),
+                        });
+                    }
+
+                    self.component_data.push(GuiComponentData::Dropdown(GuiDropdown {
+                        style,
+                        options,
+                        selected,
+                        hovered_option,
+                        expanded,
+                    }));
+                },
+                6 => {
+                    self.component_data.push(GuiComponentData::ScrollView(reader.read()));
+                },
+                7 => {
+                    self.component_data.push(GuiComponentData::Image(reader.read()));
+                },
+                8 => {
+                    let style: components::GuiMenuStyle = reader.read();
+                    let hovered_item = reader.read_u32();
+                    let expanded = reader.read_bool();
+                    let label = |glyphs: &[_]| GuiLabel { font: style.font, color: style.color, glyphs: glyphs.to_vec().into_boxed_slice(), runs: Box::default() };
+                    let title = label(reader.read_slice());
+                    let items_count = reader.read_len(1);
+                    let mut items = Vec::with_capacity(items_count);
+                    for _ in 0..items_count {
+                        let ty = match reader.read_u32() {
+                            0 => GuiMenuItemType::Item,
+                            1 => GuiMenuItemType::Checkable,
+                            _ => GuiMenuItemType::Separator,
+                        };
+                        let checked = reader.read_bool();
+                        let callback = (reader.read_u32() as u64) | ((reader.read_u32() as u64) << 32);
+                        let key = reader.read_u32();
+                        let ctrl = reader.read_bool();
+                        let accelerator = match key {
+                            u32::MAX => None,
+                            key => Some(GuiMenuAccelerator { key, ctrl }),
+                        };
+
+                        items.push(GuiMenuItem {
+                            ty,
+                            checked,
+                            callback,
+                            accelerator,
+                            label: label(reader.read_slice()),
+                            accelerator_label: label(reader.read_slice()),
+                        });
+                    }
+
+                    self.component_data.push(GuiComponentData::Menu(GuiMenu {
+                        style,
+                        title,
+                        items,
+                        hovered_item,
+                        expanded,
                     }));
                 },
                 i => {
@@ -329,12 +1821,24 @@ impl StoreAndLoad for Gui {
         writer.write(&self.base_view);
         writer.write(&self.state);
         writer.write_into_u32(self.visible);
+        writer.write_u32(self.layer as u32);
+        writer.write_into_u32(self.modal);
+        writer.write_f32(self.scale);
         writer.write_slice(&self.layouts);
         writer.write_slice(&self.styles);
+        writer.write_slice(&self.style_transitions);
+        writer.write(&self.toggle_transition);
+        writer.write(&self.tooltip_style);
         writer.write_slice(&self.callbacks);
         writer.write_slice(&self.layout_items);
         writer.write_slice(&self.component_base);
         self.store_components_data(writer);
+
+        writer.write_u32(self.tooltips.len() as u32);
+        for tooltip in self.tooltips.iter() {
+            writer.write_u32(tooltip.index);
+            writer.write_slice(&tooltip.glyphs);
+        }
     }
 
     fn load(reader: &mut SaveFileReaderBase) -> Self {
@@ -343,13 +1847,28 @@ impl StoreAndLoad for Gui {
         gui.base_view = reader.read();
         gui.state = reader.read();
         gui.visible = reader.read_u32() != 0;
+        gui.layer = reader.read_u32() as i32;
+        gui.modal = reader.read_u32() != 0;
+        gui.scale = reader.read_f32();
         gui.layouts = reader.read_slice().to_vec();
         gui.styles = reader.read_slice().to_vec();
+        gui.style_transitions = reader.read_slice().to_vec();
+        gui.toggle_transition = reader.read();
+        gui.tooltip_style = reader.read();
         gui.callbacks = reader.read_slice().to_vec();
         gui.layout_items = reader.read_slice().to_vec();
         gui.component_base = reader.read_slice().to_vec();
         gui.load_components_data(reader);
 
+        let tooltips_count = reader.read_len(2);
+        for _ in 0..tooltips_count {
+            let index = reader.read_u32();
+            let glyphs = reader.read_slice().to_vec().into_boxed_slice();
+            gui.tooltips.push(GuiTooltip { index, glyphs });
+        }
+
+        gui.compute_clips();
+
         gui
     }
 
@@ -363,20 +1882,36 @@ impl Default for Gui {
             state: GuiComponentState::default(),
             
             styles: Vec::with_capacity(8),
+            style_transitions: Vec::new(),
+            toggle_transition: GuiToggleTransition::default(),
+            tooltip_style: None,
             callbacks: Vec::with_capacity(8),
             callbacks_output: Vec::with_capacity(8),
+            callbacks_positions: Vec::with_capacity(8),
             callbacks_iter: 0,
 
             layouts: Vec::with_capacity(8),
             layout_items: Vec::with_capacity(16),
             component_base: Vec::with_capacity(16),
             component_data: Vec::with_capacity(16),
+            component_clips: Vec::with_capacity(16),
+            tooltips: Vec::new(),
 
             sprites: Vec::with_capacity(64),
+            component_sprites: Vec::with_capacity(16),
+            dirty_components: Vec::with_capacity(8),
+            patch_sprites: Vec::with_capacity(32),
+            consumed_keys: Vec::new(),
 
             builder_data: Box::default(),
+            animations: GuiAnimations::default(),
 
+            cursor_position: PositionF32::default(),
+            pointer: GuiPointerState::default(),
             visible: true,
+            layer: 0,
+            modal: false,
+            scale: 1.0,
         }
     }
 }
@@ -391,6 +1926,39 @@ impl Default for GuiBuilderData {
             layouts_stack: Vec::with_capacity(4),
             root_layout_type: GuiLayoutType::VBox,
             root_layout_pos: GuiLayoutPosition::Center,
+            interned_keys: fnv::FnvHashSet::default(),
+            json_source: None,
+        }
+    }
+
+}
+
+impl GuiBuilderData {
+
+    fn intern(&mut self, key: &str) -> &'static str {
+        match self.interned_keys.get(key) {
+            Some(interned) => interned,
+            None => {
+                let interned: &'static str = Box::leak(key.to_string().into_boxed_str());
+                self.interned_keys.insert(interned);
+                interned
+            }
+        }
+    }
+
+}
+
+impl GuiComponentState {
+
+    fn style_state(&self, index: u32) -> GuiStyleState {
+        if index == self.selected_index {
+            GuiStyleState::Selected
+        } else if index == self.hovered_index && self.selected_index == u32::MAX {
+            GuiStyleState::Hovered
+        } else if index == self.focused_index {
+            GuiStyleState::Focused
+        } else {
+            GuiStyleState::Base
         }
     }
 
@@ -401,6 +1969,22 @@ impl Default for GuiComponentState {
         GuiComponentState {
             hovered_index: u32::MAX,
             selected_index: u32::MAX,
+            focused_index: u32::MAX,
+            expanded_index: u32::MAX,
+        }
+    }
+}
+
+impl Default for GuiPointerState {
+    fn default() -> Self {
+        GuiPointerState {
+            press_position: PositionF32::default(),
+            dragging: false,
+            scroll_drag_index: u32::MAX,
+            right_button_down: false,
+            right_pressed_index: u32::MAX,
+            last_click_index: u32::MAX,
+            last_click_elapsed: 0.0,
         }
     }
 }
@@ -0,0 +1,85 @@
+use loomz_shared::base_types::RectF32;
+use loomz_shared::assets::TextureId;
+use loomz_shared::api::TERRAIN_CELL_SIZE_PX;
+use loomz_shared::{LoomzApi, CommonError, assets_err};
+
+/// A sprite atlas loaded from a json asset. Two formats are supported:
+///
+/// * Animation atlases (ex: `pawn_sprites`) with an `animations` array. Sprites are named `{animation}` or `{animation}:{frame}`
+/// * Tile atlases (ex: `terrain_sprites`) where each key is a sprite with a `uv_x` / `uv_y` offset and an optional `width` / `height`
+///
+/// The texture is the `asset` key of the json. If it's missing, the texture has the name of the atlas without the `_sprites` suffix.
+pub(super) struct GuiAtlas {
+    pub name: String,
+    pub texture: TextureId,
+    json: serde_json::Value,
+}
+
+impl GuiAtlas {
+
+    pub fn load(api: &LoomzApi, atlas_key: &str) -> Result<Self, CommonError> {
+        let assets = api.assets_ref();
+
+        let json_source = assets.json_by_name(atlas_key)
+            .ok_or_else(|| assets_err!("Failed to find json {atlas_key:?}") )?;
+
+        let json: serde_json::Value = serde_json::from_str(json_source.as_str())
+            .map_err(|err| assets_err!("Failed to parse json: {err:?}") )?;
+
+        let texture_key = json["asset"].as_str()
+            .unwrap_or_else(|| atlas_key.strip_suffix("_sprites").unwrap_or(atlas_key) );
+
+        let texture = assets.texture_id_by_name(texture_key)
+            .ok_or_else(|| assets_err!("Failed to find texture asset {texture_key:?} for atlas {atlas_key:?}") )?;
+
+        Ok(GuiAtlas {
+            name: atlas_key.to_string(),
+            texture,
+            json,
+        })
+    }
+
+    /// Returns the region of the sprite named `sprite_name` in texels
+    pub fn sprite(&self, sprite_name: &str) -> Option<RectF32> {
+        match self.json["animations"].as_array() {
+            Some(animations) => Self::animation_sprite(animations, sprite_name),
+            None => self.tile_sprite(sprite_name),
+        }
+    }
+
+    fn animation_sprite(animations: &[serde_json::Value], sprite_name: &str) -> Option<RectF32> {
+        let (name, frame) = match sprite_name.split_once(':') {
+            Some((name, frame)) => (name, frame.parse::<u32>().ok()?),
+            None => (sprite_name, 0),
+        };
+
+        let animation = animations.iter().find(|animation| animation["name"].as_str() == Some(name) )?;
+        if frame >= parse_f32(&animation["count"]) as u32 {
+            return None;
+        }
+
+        let frame = frame as f32;
+        let padding = parse_f32(&animation["padding"]);
+        let width = parse_f32(&animation["width"]);
+        let height = parse_f32(&animation["height"]);
+        let left = parse_f32(&animation["x"]) + ((width + padding) * frame);
+        let top = parse_f32(&animation["y"]);
+
+        Some(RectF32 { left, top, right: left + width, bottom: top + height })
+    }
+
+    fn tile_sprite(&self, sprite_name: &str) -> Option<RectF32> {
+        let tile = self.json.get(sprite_name)?;
+        let left = tile["uv_x"].as_f64()? as f32;
+        let top = tile["uv_y"].as_f64()? as f32;
+        let width = tile["width"].as_f64().map(|v| v as f32).unwrap_or(TERRAIN_CELL_SIZE_PX as f32);
+        let height = tile["height"].as_f64().map(|v| v as f32).unwrap_or(TERRAIN_CELL_SIZE_PX as f32);
+
+        Some(RectF32 { left, top, right: left + width, bottom: top + height })
+    }
+
+}
+
+fn parse_f32(item: &serde_json::Value) -> f32 {
+    item.as_f64().map(|v| v as f32).unwrap_or(0.0)
+}
@@ -1,45 +1,61 @@
-use loomz_shared::base_types::{PositionF32, SizeF32, RectF32};
+use loomz_shared::base_types::{SizeF32, RectF32, RgbaU8};
+use loomz_shared::assets::{MsdfFontId, TextureId};
 use loomz_shared::assets::msdf_font::ComputedGlyph;
-use loomz_shared::{LoomzApi, assets_err};
+use loomz_shared::{LoomzApi, CommonError, assets_err};
+use crate::localization::Localization;
 use super::{
     components::*,
     layout::*,
     style::*,
-    callbacks::{IntoGuiCallback, GuiComponentCallbacksValue},
+    atlas::GuiAtlas,
+    tooltip::GuiTooltip,
+    markup,
+    callbacks::{IntoGuiCallback, GuiComponentCallbacksValue, GuiPointerCallbackValues},
     Gui
 };
 
 pub struct GuiBuilder<'a> {
     api: &'a LoomzApi,
+    strings: &'a Localization,
     gui: &'a mut Gui,
     layout_item: GuiLayoutItem,
-    next_layout: (GuiLayoutType, GuiLayoutPosition),
+    next_layout: GuiLayout,
     item_index: u32,
+    atlases: Vec<GuiAtlas>,
+    next_tooltip: Option<Box<[ComputedGlyph]>>,
 }
 
 impl<'a> GuiBuilder<'a> {
 
-    pub fn new(api: &'a LoomzApi, view: &RectF32, gui: &'a mut Gui) -> Self {
+    pub fn new(api: &'a LoomzApi, strings: &'a Localization, view: &RectF32, gui: &'a mut Gui) -> Self {
         Self::clear_gui_components(view, gui);
 
         GuiBuilder {
             api,
+            strings,
             gui,
             layout_item: GuiLayoutItem::default(),
-            next_layout: (GuiLayoutType::VBox, GuiLayoutPosition::Center),
+            next_layout: GuiLayout::default(),
             item_index: 0,
+            atlases: Vec::new(),
+            next_tooltip: None,
         }
     }
 
     fn clear_gui_components(view: &RectF32, gui: &mut Gui) {
         gui.base_view = *view;
         gui.state = Default::default();
+        gui.pointer = Default::default();
         gui.callbacks.clear();
         gui.callbacks_output.clear();
+        gui.callbacks_positions.clear();
         gui.layouts.clear();
         gui.layout_items.clear();
         gui.component_base.clear();
         gui.component_data.clear();
+        gui.component_clips.clear();
+        gui.tooltips.clear();
+        gui.modal = false;
         gui.sprites.clear();
 
         let mut root = GuiLayout::default();
@@ -54,27 +70,372 @@ impl<'a> GuiBuilder<'a> {
         gui.layouts.push(root);
     }
 
-    /// Sets the layout used to position the child items of the next component
+    /// Sets the layout used to position the child items of the next component. This resets the spacing and the columns of the layout.
     pub fn layout(&mut self, layout_type: GuiLayoutType, layout_pos: GuiLayoutPosition) {
-        self.next_layout = (layout_type, layout_pos);
+        self.next_layout = GuiLayout {
+            ty: layout_type,
+            position: layout_pos,
+            ..Default::default()
+        };
+    }
+
+    /// Sets the space between the child items of the next layout
+    pub fn layout_spacing(&mut self, spacing: f32) {
+        self.next_layout.spacing = spacing * self.gui.scale;
+    }
+
+    /// Sets the number of columns of the next layout. Only used by `Grid` layouts.
+    pub fn layout_columns(&mut self, columns: u32) {
+        self.next_layout.columns = u32::max(1, columns);
     }
 
-    /// Sets the layout item of the next components
+    /// Sets the layout item of the next components. This resets the margin and the padding of the item.
     pub fn layout_item(&mut self, width: f32, height: f32) {
+        let scale = self.gui.scale;
+        self.layout_item = GuiLayoutItem::fixed(width * scale, height * scale);
+    }
+
+    /// Sets the layout item of the next components using flexible sizes. This resets the margin and the padding of the item.
+    pub fn layout_item_sized(&mut self, width: GuiSize, height: GuiSize) {
+        let width = width.scaled(self.gui.scale);
+        let height = height.scaled(self.gui.scale);
         self.layout_item = GuiLayoutItem {
-            has_layout: false,
-            position: PositionF32::default(),
-            size: SizeF32 { width, height }
+            size: SizeF32 { width: width.fixed(), height: height.fixed() },
+            width,
+            height,
+            ..Default::default()
         };
     }
 
-    pub fn label_callback<ID: IntoGuiCallback>(&mut self, _callback: GuiLabelCallback, callback_id: ID) {
-        let click = callback_id.into_u64();
-        self.gui.builder_data.last_callbacks = GuiComponentCallbacksValue::Label(GuiLabelCallbackValues { click });
+    /// Sets the space around the layout item of the next components
+    pub fn layout_item_margin(&mut self, margin: RectF32) {
+        self.layout_item.margin = margin * self.gui.scale;
     }
 
-    /// Adds a simple text component to the gui
+    /// Sets the space between the layout item of the next components and their children
+    pub fn layout_item_padding(&mut self, padding: RectF32) {
+        self.layout_item.padding = padding * self.gui.scale;
+    }
+
+    /// Sets the tooltip of the next component to the localized string of `key`
+    pub fn tooltip_key(&mut self, key: &str) {
+        let strings = self.strings;
+        self.tooltip(strings.get(key));
+    }
+
+    /// Sets the tooltip of the next component. The tooltip is shown when the component is hovered for the delay of the tooltip style.
+    pub fn tooltip(&mut self, text: &str) {
+        let style = match self.gui.tooltip_style {
+            Some(style) => style,
+            None => {
+                self.gui.builder_data.errors.push(assets_err!("No tooltip style in builder"));
+                return;
+            }
+        };
+
+        self.next_tooltip = Some(compute_glyphs(self.api, text, &style.label));
+    }
+
+    /// Sets a callback of the next label. Multiple events can be set before calling `label`
+    pub fn label_callback<ID: IntoGuiCallback>(&mut self, callback: GuiLabelCallback, callback_id: ID) {
+        let mut values = match self.gui.builder_data.last_callbacks {
+            GuiComponentCallbacksValue::Label(values) => values,
+            _ => GuiPointerCallbackValues::default()
+        };
+
+        values.set(callback, callback_id.into_u64());
+        self.gui.builder_data.last_callbacks = GuiComponentCallbacksValue::Label(values);
+    }
+
+    /// Sets a callback of the next text input. `Change` and `Submit` can both be set before calling `text_input`
+    pub fn text_input_callback<ID: IntoGuiCallback>(&mut self, callback: GuiTextInputCallback, callback_id: ID) {
+        let callback_id = callback_id.into_u64();
+        let mut values = match self.gui.builder_data.last_callbacks {
+            GuiComponentCallbacksValue::TextInput(values) => values,
+            _ => GuiTextInputCallbackValues { change: u64::MAX, submit: u64::MAX }
+        };
+
+        match callback {
+            GuiTextInputCallback::Change => { values.change = callback_id; },
+            GuiTextInputCallback::Submit => { values.submit = callback_id; },
+        }
+
+        self.gui.builder_data.last_callbacks = GuiComponentCallbacksValue::TextInput(values);
+    }
+
+    /// Adds an editable text field to the gui. `max_length` is expressed in characters.
+    pub fn text_input(&mut self, value: &str, placeholder: &str, max_length: u32, style_key: &str) {
+        let gui = &mut self.gui;
+        let builder_data = &mut gui.builder_data;
+
+        // Layout item
+        gui.layout_items.push(self.layout_item);
+
+        // Component base
+        let callbacks_index = match builder_data.last_callbacks.take() {
+            cb @ GuiComponentCallbacksValue::TextInput(_) => {
+                gui.callbacks.push(cb);
+                (gui.callbacks.len() - 1) as u32
+            },
+            _ => u32::MAX,
+        };
+
+        let style_key = (style_key, GuiComponentTag::TextInput);
+        let style_index = match builder_data.styles.get(&style_key) {
+            Some(style_index) => *style_index,
+            None => {
+                builder_data.errors.push(assets_err!("No text input style with key {:?} in builder", style_key.0));
+                return;
+            }
+        };
+
+        gui.component_base.push(GuiComponentBase {
+            callbacks_index,
+            style_index,
+        });
+
+        // Component data
+        let style = match gui.styles.get(style_index as usize) {
+            Some(GuiComponentStyle::TextInput(text_input_style)) => text_input_style.base,
+            _ => unreachable!("GuiComponentStyle cannot be something else than TextInput")
+        };
+        let input = GuiTextInput::new(self.api, style, value, placeholder, max_length);
+        gui.component_data.push(GuiComponentData::TextInput(input));
+
+        self.update_layout(self.layout_item);
+        self.item_index += 1;
+    }
+
+    pub fn checkbox_callback<ID: IntoGuiCallback>(&mut self, _callback: GuiCheckboxCallback, callback_id: ID) {
+        let change = callback_id.into_u64();
+        self.gui.builder_data.last_callbacks = GuiComponentCallbacksValue::Checkbox(GuiCheckboxCallbackValues { change });
+    }
+
+    /// Adds a checkbox to the gui. The box is a square on the left side of the layout item
+    pub fn checkbox(&mut self, checked: bool, style_key: &str) {
+        let accept_callbacks = |cb: &GuiComponentCallbacksValue| matches!(cb, GuiComponentCallbacksValue::Checkbox(_));
+        let style_index = match self.push_component_base(GuiComponentTag::Checkbox, style_key, accept_callbacks) {
+            Some(index) => index,
+            None => { return; }
+        };
+
+        let gui = &mut self.gui;
+        let style = match gui.styles.get(style_index as usize) {
+            Some(GuiComponentStyle::Checkbox(checkbox_style)) => checkbox_style.base,
+            _ => unreachable!("GuiComponentStyle cannot be something else than Checkbox")
+        };
+        gui.component_data.push(GuiComponentData::Checkbox(GuiCheckbox { style, checked }));
+
+        self.update_layout(self.layout_item);
+        self.item_index += 1;
+    }
+
+    pub fn slider_callback<ID: IntoGuiCallback>(&mut self, _callback: GuiSliderCallback, callback_id: ID) {
+        let change = callback_id.into_u64();
+        self.gui.builder_data.last_callbacks = GuiComponentCallbacksValue::Slider(GuiSliderCallbackValues { change });
+    }
+
+    /// Adds a horizontal slider to the gui. If `step` is 0, the value is continuous.
+    pub fn slider(&mut self, value: f32, min: f32, max: f32, step: f32, style_key: &str) {
+        let accept_callbacks = |cb: &GuiComponentCallbacksValue| matches!(cb, GuiComponentCallbacksValue::Slider(_));
+        let style_index = match self.push_component_base(GuiComponentTag::Slider, style_key, accept_callbacks) {
+            Some(index) => index,
+            None => { return; }
+        };
+
+        let gui = &mut self.gui;
+        let style = match gui.styles.get(style_index as usize) {
+            Some(GuiComponentStyle::Slider(slider_style)) => slider_style.base,
+            _ => unreachable!("GuiComponentStyle cannot be something else than Slider")
+        };
+        gui.component_data.push(GuiComponentData::Slider(GuiSlider::new(style, value, min, max, step)));
+
+        self.update_layout(self.layout_item);
+        self.item_index += 1;
+    }
+
+    pub fn dropdown_callback<ID: IntoGuiCallback>(&mut self, _callback: GuiDropdownCallback, callback_id: ID) {
+        let change = callback_id.into_u64();
+        self.gui.builder_data.last_callbacks = GuiComponentCallbacksValue::Dropdown(GuiDropdownCallbackValues { change });
+    }
+
+    /// Adds a dropdown to the gui. `selected` is the index of the selected value in `options`
+    pub fn dropdown(&mut self, options: &[&str], selected: u32, style_key: &str) {
+        if options.is_empty() {
+            self.gui.builder_data.errors.push(assets_err!("Dropdown with style {:?} must have at least one option", style_key));
+            return;
+        }
+
+        let accept_callbacks = |cb: &GuiComponentCallbacksValue| matches!(cb, GuiComponentCallbacksValue::Dropdown(_));
+        let style_index = match self.push_component_base(GuiComponentTag::Dropdown, style_key, accept_callbacks) {
+            Some(index) => index,
+            None => { return; }
+        };
+
+        let gui = &mut self.gui;
+        let style = match gui.styles.get(style_index as usize) {
+            Some(GuiComponentStyle::Dropdown(dropdown_style)) => dropdown_style.base,
+            _ => unreachable!("GuiComponentStyle cannot be something else than Dropdown")
+        };
+
+        let label_style = GuiLabelStyle { font: style.font, font_size: style.font_size, color: style.color };
+        let options = options.iter()
+            .map(|option| build_label_component(self.api, option, &label_style) )
+            .collect::<Vec<_>>();
+
+        gui.component_data.push(GuiComponentData::Dropdown(GuiDropdown {
+            style,
+            selected: u32::min(selected, options.len() as u32 - 1),
+            options,
+            hovered_option: u32::MAX,
+            expanded: false,
+        }));
+
+        self.update_layout(self.layout_item);
+        self.item_index += 1;
+    }
+
+    /// Adds an entry of a menu bar titled with the localized string of `key`. See `menu`.
+    pub fn menu_key<F: FnOnce(&mut GuiMenuBuilder)>(&mut self, key: &str, style_key: &str, items: F) {
+        let strings = self.strings;
+        self.menu(strings.get(key), style_key, items);
+    }
+
+    /// Adds an entry of a menu bar to the gui. Clicking the entry opens a popup with the items added by `items`.
+    /// Menus have no callbacks of their own, each item has its own callback.
+    pub fn menu<F: FnOnce(&mut GuiMenuBuilder)>(&mut self, title: &str, style_key: &str, items: F) {
+        let style_index = match self.push_component_base(GuiComponentTag::Menu, style_key, |_| false) {
+            Some(index) => index,
+            None => { return; }
+        };
+
+        let style = match self.gui.styles.get(style_index as usize) {
+            Some(GuiComponentStyle::Menu(menu_style)) => menu_style.base,
+            _ => unreachable!("GuiComponentStyle cannot be something else than Menu")
+        };
+
+        let label_style = GuiLabelStyle { font: style.font, font_size: style.font_size, color: style.color };
+        let mut menu_builder = GuiMenuBuilder {
+            api: self.api,
+            strings: self.strings,
+            style: label_style,
+            items: Vec::with_capacity(8),
+            next_accelerator: None,
+        };
+
+        items(&mut menu_builder);
+
+        self.gui.component_data.push(GuiComponentData::Menu(GuiMenu {
+            style,
+            title: build_label_component(self.api, title, &label_style),
+            items: menu_builder.items,
+            hovered_item: u32::MAX,
+            expanded: false,
+        }));
+
+        self.update_layout(self.layout_item);
+        self.item_index += 1;
+    }
+
+    /// Sets a callback of the next image. Multiple events can be set before calling `image`
+    pub fn image_callback<ID: IntoGuiCallback>(&mut self, callback: GuiImageCallback, callback_id: ID) {
+        let mut values = match self.gui.builder_data.last_callbacks {
+            GuiComponentCallbacksValue::Image(values) => values,
+            _ => GuiPointerCallbackValues::default()
+        };
+
+        values.set(callback, callback_id.into_u64());
+        self.gui.builder_data.last_callbacks = GuiComponentCallbacksValue::Image(values);
+    }
+
+    /// Adds an image to the gui. `region` is the area of the image in the texture, in texels.
+    pub fn image(&mut self, texture_key: &str, region: RectF32, fit: GuiImageFit, style_key: &str) {
+        let texture = match self.api.assets_ref().texture_id_by_name(texture_key) {
+            Some(texture) => texture,
+            None => {
+                self.gui.builder_data.errors.push(assets_err!("No texture named {:?} in app", texture_key));
+                return;
+            }
+        };
+
+        self.push_image(texture, region, fit, style_key);
+    }
+
+    /// Adds an image to the gui using the sprite named `sprite_name` in the json atlas `atlas_key`. See [GuiAtlas] for the supported formats.
+    pub fn atlas_image(&mut self, atlas_key: &str, sprite_name: &str, fit: GuiImageFit, style_key: &str) {
+        let atlas_index = match self.atlases.iter().position(|atlas| atlas.name == atlas_key ) {
+            Some(index) => index,
+            None => match GuiAtlas::load(self.api, atlas_key) {
+                Ok(atlas) => {
+                    self.atlases.push(atlas);
+                    self.atlases.len() - 1
+                },
+                Err(error) => {
+                    self.gui.builder_data.errors.push(error);
+                    return;
+                }
+            }
+        };
+
+        let atlas = &self.atlases[atlas_index];
+        let texture = atlas.texture;
+        let region = match atlas.sprite(sprite_name) {
+            Some(region) => region,
+            None => {
+                self.gui.builder_data.errors.push(assets_err!("No sprite named {:?} in atlas {:?}", sprite_name, atlas_key));
+                return;
+            }
+        };
+
+        self.push_image(texture, region, fit, style_key);
+    }
+
+    fn push_image(&mut self, texture: TextureId, region: RectF32, fit: GuiImageFit, style_key: &str) {
+        let accept_callbacks = |cb: &GuiComponentCallbacksValue| matches!(cb, GuiComponentCallbacksValue::Image(_));
+        let style_index = match self.push_component_base(GuiComponentTag::Image, style_key, accept_callbacks) {
+            Some(index) => index,
+            None => { return; }
+        };
+
+        let gui = &mut self.gui;
+        let tint = match gui.styles.get(style_index as usize) {
+            Some(GuiComponentStyle::Image(image_style)) => image_style.base.tint,
+            _ => unreachable!("GuiComponentStyle cannot be something else than Image")
+        };
+        gui.component_data.push(GuiComponentData::Image(GuiImage { texture, region, fit, tint }));
+
+        self.update_layout(self.layout_item);
+        self.item_index += 1;
+    }
+
+    /// Adds a label showing the localized string of `key`
+    pub fn label_key(&mut self, key: &str, style_key: &str) {
+        let strings = self.strings;
+        self.label(strings.get(key), style_key);
+    }
+
+    /// Adds a label showing the localized string of `key`. The string can use inline markup, see `rich_label`.
+    pub fn rich_label_key(&mut self, key: &str, style_key: &str) {
+        let strings = self.strings;
+        self.rich_label(strings.get(key), style_key);
+    }
+
+    /// Adds a simple text component to the gui. The text is shown as is.
     pub fn label(&mut self, text_value: &str, style_key: &str) {
+        self.push_label(text_value, Vec::new(), style_key);
+    }
+
+    /// Adds a text component using inline markup, see the `markup` module. Invalid markup is a build error,
+    /// so texts typed by the user or coming from outside the app must use `label`.
+    pub fn rich_label(&mut self, text_value: &str, style_key: &str) {
+        let assets = self.api.assets_ref();
+        match markup::parse(text_value, |name| assets.font_id_by_name(name) ) {
+            Ok(spans) => self.push_label(text_value, spans, style_key),
+            Err(error) => self.gui.builder_data.errors.push(error),
+        }
+    }
+
+    fn push_label(&mut self, text_value: &str, spans: Vec<markup::GuiMarkupSpan>, style_key: &str) {
         let gui = &mut self.gui;
         let builder_data = &mut gui.builder_data;
 
@@ -109,10 +470,13 @@ impl<'a> GuiBuilder<'a> {
             Some(GuiComponentStyle::Label(label_style)) => &label_style.base,
             _ => unreachable!("GuiComponentStyle cannot be something else than Font")
         };
-        let label = build_label_component(self.api, text_value, style);
+        let label = match spans.is_empty() {
+            true => build_label_component(self.api, text_value, style),
+            false => build_rich_label_component(self.api, &spans, style),
+        };
         gui.component_data.push(GuiComponentData::Label(label));
 
-        self.update_layout(self.layout_item.size);
+        self.update_layout(self.layout_item);
         self.item_index += 1;
     }
 
@@ -147,14 +511,15 @@ impl<'a> GuiBuilder<'a> {
         };
         let frame = GuiFrame {
             texture: style.texture,
-            size: item.size,
             texcoord: style.region,
+            borders: style.borders,
+            border_scale: style.border_scale,
             color: style.color,
         };
         gui.component_data.push(GuiComponentData::Frame(frame));
 
 
-        self.update_layout(item.size);
+        self.update_layout(item);
         self.push_next_layout();
 
         self.item_index += 1;
@@ -164,34 +529,130 @@ impl<'a> GuiBuilder<'a> {
         self.store_layout();
     }
 
-    fn update_layout(&mut self, item_size: SizeF32) {
-        let current_layout = match self.gui.builder_data.layouts_stack.last_mut() {
-            Some((_, layout)) => layout,
-            None => unreachable!("There will always be a layout")
-        };
+    /// Pushes the layout item and the component base of the next component. Returns the style index of the component.
+    fn push_component_base<F>(&mut self, tag: GuiComponentTag, style_key: &str, accept_callbacks: F) -> Option<u32>
+        where F: FnOnce(&GuiComponentCallbacksValue) -> bool
+    {
+        let gui = &mut self.gui;
+        let builder_data = &mut gui.builder_data;
 
-        current_layout.children_count += 1;
+        // Layout item
+        gui.layout_items.push(self.layout_item);
 
-        match current_layout.ty {
-            GuiLayoutType::VBox => {
-                current_layout.width = f32::max(current_layout.width, item_size.width);
-                current_layout.height += item_size.height;
+        // Component base
+        let callbacks = builder_data.last_callbacks.take();
+        let callbacks_index = match accept_callbacks(&callbacks) {
+            true => {
+                gui.callbacks.push(callbacks);
+                (gui.callbacks.len() - 1) as u32
             },
-            GuiLayoutType::HBox => {
-                current_layout.width += item_size.width;
-                current_layout.height = f32::max(current_layout.height, item_size.height);
+            false => u32::MAX,
+        };
+
+        let style_index = match builder_data.styles.get(&(style_key, tag)) {
+            Some(style_index) => *style_index,
+            None => {
+                builder_data.errors.push(assets_err!("No style with key {:?} in builder", style_key));
+                return None;
             }
+        };
+
+        gui.component_base.push(GuiComponentBase {
+            callbacks_index,
+            style_index,
+        });
+
+        Some(style_index)
+    }
+
+    /// Adds a scroll view into the gui. The children are stacked vertically from the top of the view and
+    /// clipped to the view. The content can be scrolled with the mouse wheel, or by dragging the view or its scrollbar.
+    pub fn scroll_view<F: FnOnce(&mut GuiBuilder)>(&mut self, style_key: &str, callback: F) {
+        let mut item = self.layout_item;
+        item.has_layout = true;
+        self.layout_item = item;
+
+        let style_index = self.push_component_base(GuiComponentTag::ScrollView, style_key, |_| false);
+        self.layout_item.has_layout = false;
+
+        let style_index = match style_index {
+            Some(index) => index,
+            None => { return; }
+        };
+
+        let gui = &mut self.gui;
+        let style = match gui.styles.get(style_index as usize) {
+            Some(GuiComponentStyle::ScrollView(scroll_view_style)) => scroll_view_style.base,
+            _ => unreachable!("GuiComponentStyle cannot be something else than ScrollView")
+        };
+
+        let component_index = gui.component_data.len();
+        gui.component_data.push(GuiComponentData::ScrollView(GuiScrollView {
+            style,
+            size: item.size,
+            content_height: 0.0,
+            scroll: 0.0,
+            descendants: 0,
+            drag: GuiScrollDrag::None,
+        }));
+
+        self.update_layout(item);
+
+        let next_layout = self.next_layout;
+        self.next_layout = GuiLayout { ty: GuiLayoutType::VBox, position: GuiLayoutPosition::TopLeft, ..next_layout };
+        self.push_next_layout();
+        self.next_layout = next_layout;
+
+        let first_child = self.item_index + 1;
+        self.item_index += 1;
+
+        callback(self);
+
+        let content_height = match self.gui.builder_data.layouts_stack.last() {
+            Some((_, layout)) => layout.height,
+            None => unreachable!("Scroll view layout is always present")
+        };
+
+        self.store_layout();
+
+        if let GuiComponentData::ScrollView(scroll_view) = &mut self.gui.component_data[component_index] {
+            scroll_view.content_height = content_height;
+            scroll_view.descendants = self.item_index - first_child;
+        }
+    }
+
+    pub(super) fn push_error(&mut self, error: CommonError) {
+        self.gui.builder_data.errors.push(error);
+    }
+
+    pub(super) fn intern(&mut self, key: &str) -> &'static str {
+        self.gui.builder_data.intern(key)
+    }
+
+    /// Adds the item of the last pushed component to the current layout
+    fn update_layout(&mut self, item: GuiLayoutItem) {
+        if let Some(glyphs) = self.next_tooltip.take() {
+            let index = (self.gui.layout_items.len() - 1) as u32;
+            self.gui.tooltips.push(GuiTooltip { index, glyphs });
         }
+
+        let current_layout = match self.gui.builder_data.layouts_stack.last_mut() {
+            Some((_, layout)) => layout,
+            None => unreachable!("There will always be a layout")
+        };
+
+        current_layout.push_item(&item);
     }
 
     fn push_next_layout(&mut self) {
         let next_layout_index = self.gui.layouts.len();
         let new_layout = GuiLayout {
-            ty: self.next_layout.0,
-            position: self.next_layout.1,
             width: 0.0,
             height: 0.0,
             children_count: 0,
+            cell: SizeF32::default(),
+            flex: GuiLayoutFlex::default(),
+            ..self.next_layout
         };
 
         self.gui.layouts.push(new_layout);
@@ -209,11 +670,199 @@ impl<'a> GuiBuilder<'a> {
 
 }
 
-fn build_label_component(
+/// Items of a menu. See [GuiBuilder::menu]
+pub struct GuiMenuBuilder<'a> {
+    api: &'a LoomzApi,
+    strings: &'a Localization,
+    style: GuiLabelStyle,
+    items: Vec<GuiMenuItem>,
+    next_accelerator: Option<(GuiMenuAccelerator, GuiLabel)>,
+}
+
+impl<'a> GuiMenuBuilder<'a> {
+
+    /// Sets the keyboard accelerator of the next item. `text` is shown on the right side of the item.
+    /// Accelerators are only triggered by [Gui::update_accelerators](super::Gui::update_accelerators).
+    pub fn accelerator(&mut self, key: u32, ctrl: bool, text: &str) {
+        let label = build_label_component(self.api, text, &self.style);
+        self.next_accelerator = Some((GuiMenuAccelerator { key, ctrl }, label));
+    }
+
+    pub fn item<ID: IntoGuiCallback>(&mut self, text: &str, callback_id: ID) {
+        self.push_item(GuiMenuItemType::Item, false, text, callback_id.into_u64());
+    }
+
+    /// Adds an item showing the localized string of `key`
+    pub fn item_key<ID: IntoGuiCallback>(&mut self, key: &str, callback_id: ID) {
+        let strings = self.strings;
+        self.item(strings.get(key), callback_id);
+    }
+
+    /// Adds an item with a check mark. The check mark is toggled when the item is activated.
+    pub fn checkable_item<ID: IntoGuiCallback>(&mut self, text: &str, checked: bool, callback_id: ID) {
+        self.push_item(GuiMenuItemType::Checkable, checked, text, callback_id.into_u64());
+    }
+
+    /// Adds an item with a check mark showing the localized string of `key`
+    pub fn checkable_item_key<ID: IntoGuiCallback>(&mut self, key: &str, checked: bool, callback_id: ID) {
+        let strings = self.strings;
+        self.checkable_item(strings.get(key), checked, callback_id);
+    }
+
+    pub fn separator(&mut self) {
+        self.push_item(GuiMenuItemType::Separator, false, "", u64::MAX);
+    }
+
+    fn push_item(&mut self, ty: GuiMenuItemType, checked: bool, text: &str, callback: u64) {
+        let (accelerator, accelerator_label) = match self.next_accelerator.take() {
+            Some((accelerator, label)) => (Some(accelerator), label),
+            None => (None, build_label_component(self.api, "", &self.style)),
+        };
+
+        self.items.push(GuiMenuItem {
+            ty,
+            checked,
+            callback,
+            accelerator,
+            label: build_label_component(self.api, text, &self.style),
+            accelerator_label,
+        });
+    }
+
+}
+
+/// Builds a label in the font of `style`. If some graphemes are drawn with a fallback font, the label gets a glyph run for each font.
+pub(super) fn build_label_component(
     api: &LoomzApi,
     text_value: &str,
     style: &GuiLabelStyle,
 ) -> GuiLabel {
+    let baseline = match api.assets_ref().font(style.font) {
+        Some(font_asset) => font_asset.font_data.baseline(style.font_size),
+        None => unreachable!("Font presence is validated by the builder")
+    };
+
+    let mut glyphs = Vec::with_capacity(text_value.len());
+    let mut runs = Vec::with_capacity(1);
+    let mut advance = 0.0;
+    push_label_run(api, text_value, style.font, style.font_size, baseline, None, &mut advance, &mut glyphs, &mut runs);
+
+    if runs.len() == 1 {
+        runs.clear();
+    }
+
+    GuiLabel {
+        glyphs: glyphs.into_boxed_slice(),
+        font: style.font,
+        color: style.color,
+        runs: runs.into_boxed_slice(),
+    }
+}
+
+/// Builds a label with a glyph run for each markup span. The spans are aligned on the baseline of the largest font.
+fn build_rich_label_component(
+    api: &LoomzApi,
+    spans: &[markup::GuiMarkupSpan],
+    style: &GuiLabelStyle,
+) -> GuiLabel {
+    let assets = api.assets_ref();
+    let baseline = spans.iter()
+        .map(|span| match assets.font(span.font.unwrap_or(style.font)) {
+            Some(font_asset) => font_asset.font_data.baseline(style.font_size * span.size),
+            None => unreachable!("Font presence is validated by the markup parser")
+        })
+        .fold(0.0, f32::max);
+
+    let mut glyphs = Vec::with_capacity(spans.iter().map(|span| span.text.len() ).sum());
+    let mut runs = Vec::with_capacity(spans.len());
+    let mut advance = 0.0;
+
+    for span in spans.iter() {
+        let font = span.font.unwrap_or(style.font);
+        let scale = style.font_size * span.size;
+        push_label_run(api, span.text, font, scale, baseline, span.color, &mut advance, &mut glyphs, &mut runs);
+    }
+
+    GuiLabel {
+        glyphs: glyphs.into_boxed_slice(),
+        font: style.font,
+        color: style.color,
+        runs: runs.into_boxed_slice(),
+    }
+}
+
+/// Computes the glyphs of `text` drawn with `font` and pushes them in `runs`, with their baseline at `baseline`.
+/// Graphemes missing from `font` are drawn with the font returned by `fallback_font_id` in a run of their own.
+#[allow(clippy::too_many_arguments)]
+fn push_label_run(
+    api: &LoomzApi,
+    text: &str,
+    font: MsdfFontId,
+    scale: f32,
+    baseline: f32,
+    color: Option<RgbaU8>,
+    advance: &mut f32,
+    glyphs: &mut Vec<ComputedGlyph>,
+    runs: &mut Vec<GuiLabelRun>,
+) {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let assets = api.assets_ref();
+    let mut fonts = Vec::with_capacity(1);
+    match assets.font(font) {
+        Some(font_asset) => fonts.push((font, font_asset)),
+        None => unreachable!("Font presence is validated by the builder")
+    }
+
+    let first_run = runs.len();
+    let mut glyph = ComputedGlyph::default();
+
+    for g in text.graphemes(true) {
+        let glyph_font = match fonts[0].1.font_data.has_glyph(g) {
+            true => font,
+            false => assets.fallback_font_id(font, g).unwrap_or(font),
+        };
+
+        let font_index = match fonts.iter().position(|(id, _)| *id == glyph_font ) {
+            Some(index) => index,
+            None => match assets.font(glyph_font) {
+                Some(font_asset) => {
+                    fonts.push((glyph_font, font_asset));
+                    fonts.len() - 1
+                },
+                None => unreachable!("Fallback fonts are loaded assets")
+            }
+        };
+
+        let font_data = &fonts[font_index].1.font_data;
+        let offset = baseline - font_data.baseline(scale);
+        let a = font_data.compute_glyph(g, scale, &mut glyph);
+        glyph.position = glyph.position.translate_into(*advance, offset);
+
+        *advance += a;
+
+        match runs.last() {
+            Some(run) if runs.len() > first_run && run.font == glyph_font => {},
+            _ => runs.push(GuiLabelRun { start: glyphs.len() as u32, end: glyphs.len() as u32, font: glyph_font, color }),
+        }
+
+        glyphs.push(glyph);
+        if let Some(run) = runs.last_mut() {
+            run.end = glyphs.len() as u32;
+        }
+    }
+
+    if runs.len() == first_run {
+        let start = glyphs.len() as u32;
+        runs.push(GuiLabelRun { start, end: start, font, color });
+    }
+}
+
+fn compute_glyphs(
+    api: &LoomzApi,
+    text_value: &str,
+    style: &GuiLabelStyle,
+) -> Box<[ComputedGlyph]> {
     use unicode_segmentation::UnicodeSegmentation; 
 
     let font_asset = match api.assets_ref().font(style.font) {
@@ -236,9 +885,5 @@ fn build_label_component(
         glyphs.push(glyph);
     }
 
-    GuiLabel {
-        glyphs: glyphs.into_boxed_slice(),
-        font: style.font,
-        color: style.color
-    }
+    glyphs.into_boxed_slice()
 }
@@ -1,4 +1,6 @@
-use super::components::GuiLabelCallbackValues;
+use super::components::{GuiTextInputCallbackValues, GuiCheckboxCallbackValues,
+    GuiSliderCallbackValues, GuiDropdownCallbackValues};
+use super::GuiInnerEvent;
 
 pub(super) type RawCallbackValue = u64;
 pub trait IntoGuiCallback {
@@ -11,10 +13,88 @@ impl<T: Into<u64>+From<RawCallbackValue>> IntoGuiCallback for T {
     fn from_u64(value: RawCallbackValue) -> Self { Self::from(value) }
 }
 
+/// Cursor events of the labels and the images. Use [Gui::event_position](super::Gui::event_position) to get the cursor position of an event.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GuiPointerCallback {
+    /// Left button pressed and released over the component
+    Click,
+    /// Right button pressed and released over the component
+    RightClick,
+    /// Second click on the component in a short delay. Sent after the `Click` event of the second click.
+    DoubleClick,
+    HoverEnter,
+    HoverLeave,
+    /// Left button pressed over the component
+    Press,
+    /// Left button released after a `Press`, even if the cursor left the component
+    Release,
+    /// Cursor moved a few pixels away from the `Press` position. A drag does not send a `Click` event.
+    DragStart,
+    /// Cursor moved while dragging
+    DragMove,
+    /// Left button released while dragging
+    DragEnd,
+}
+
+/// Callbacks of the cursor events. Events without a callback are `u64::MAX`.
+#[derive(Copy, Clone)]
+pub(super) struct GuiPointerCallbackValues {
+    values: [RawCallbackValue; 10],
+}
+
+impl GuiPointerCallbackValues {
+
+    pub fn set(&mut self, callback: GuiPointerCallback, value: RawCallbackValue) {
+        self.values[callback as usize] = value;
+    }
+
+    /// Returns the callback of `event`, or `u64::MAX` if the event is not a cursor event or has no callback
+    pub fn get(&self, event: GuiInnerEvent) -> RawCallbackValue {
+        let callback = match event {
+            GuiInnerEvent::Click => GuiPointerCallback::Click,
+            GuiInnerEvent::RightClick => GuiPointerCallback::RightClick,
+            GuiInnerEvent::DoubleClick => GuiPointerCallback::DoubleClick,
+            GuiInnerEvent::HoverEnter => GuiPointerCallback::HoverEnter,
+            GuiInnerEvent::HoverLeave => GuiPointerCallback::HoverLeave,
+            GuiInnerEvent::Press => GuiPointerCallback::Press,
+            GuiInnerEvent::Release => GuiPointerCallback::Release,
+            GuiInnerEvent::DragStart => GuiPointerCallback::DragStart,
+            GuiInnerEvent::DragMove => GuiPointerCallback::DragMove,
+            GuiInnerEvent::DragEnd => GuiPointerCallback::DragEnd,
+            GuiInnerEvent::Change | GuiInnerEvent::Submit => { return u64::MAX; }
+        };
+
+        self.values[callback as usize]
+    }
+
+    pub fn contains(&self, value: RawCallbackValue) -> bool {
+        self.values.contains(&value)
+    }
+
+    pub fn on_events(&self, output: &mut Vec<RawCallbackValue>, event: GuiInnerEvent) {
+        let value = self.get(event);
+        if value != u64::MAX {
+            output.push(value);
+        }
+    }
+
+}
+
+impl Default for GuiPointerCallbackValues {
+    fn default() -> Self {
+        GuiPointerCallbackValues { values: [u64::MAX; 10] }
+    }
+}
+
 #[derive(Copy, Clone)]
 pub(super) enum GuiComponentCallbacksValue {
     None,
-    Label(GuiLabelCallbackValues)
+    Label(GuiPointerCallbackValues),
+    TextInput(GuiTextInputCallbackValues),
+    Checkbox(GuiCheckboxCallbackValues),
+    Slider(GuiSliderCallbackValues),
+    Dropdown(GuiDropdownCallbackValues),
+    Image(GuiPointerCallbackValues),
 }
 
 impl GuiComponentCallbacksValue {
@@ -25,4 +105,17 @@ impl GuiComponentCallbacksValue {
         other
     }
 
+    /// Returns `true` if any of the callbacks values is `value`
+    pub fn contains(&self, value: RawCallbackValue) -> bool {
+        match self {
+            Self::None => false,
+            Self::Label(values) => values.contains(value),
+            Self::TextInput(values) => values.change == value || values.submit == value,
+            Self::Checkbox(values) => values.change == value,
+            Self::Slider(values) => values.change == value,
+            Self::Dropdown(values) => values.change == value,
+            Self::Image(values) => values.contains(value),
+        }
+    }
+
 }
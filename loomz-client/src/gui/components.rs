@@ -5,6 +5,32 @@ pub use label::GuiLabelCallback;
 mod frame;
 pub(crate) use frame::*;
 
+mod text_input;
+pub(crate) use text_input::*;
+pub use text_input::GuiTextInputCallback;
+
+mod checkbox;
+pub(crate) use checkbox::*;
+pub use checkbox::GuiCheckboxCallback;
+
+mod slider;
+pub(crate) use slider::*;
+pub use slider::GuiSliderCallback;
+
+mod dropdown;
+pub(crate) use dropdown::*;
+pub use dropdown::GuiDropdownCallback;
+
+mod menu;
+pub(crate) use menu::*;
+
+mod scroll_view;
+pub(crate) use scroll_view::*;
+
+mod image;
+pub(crate) use image::*;
+pub use image::{GuiImageCallback, GuiImageFit};
+
 use super::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState, 
     GuiComponentCallbacksValue, RawCallbackValue, GuiInnerEvent};
 
@@ -12,7 +38,14 @@ use super::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyle
 #[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub(super) enum GuiComponentTag {
     Frame,
-    Label
+    Label,
+    TextInput,
+    Checkbox,
+    Slider,
+    Dropdown,
+    ScrollView,
+    Image,
+    Menu,
 }
 
 #[derive(Copy, Clone)]
@@ -24,6 +57,13 @@ pub(super) struct GuiComponentBase {
 pub(super) enum GuiComponentData {
     Frame(GuiFrame),
     Label(GuiLabel),
+    TextInput(GuiTextInput),
+    Checkbox(GuiCheckbox),
+    Slider(GuiSlider),
+    Dropdown(GuiDropdown),
+    ScrollView(GuiScrollView),
+    Image(GuiImage),
+    Menu(GuiMenu),
 }
 
 impl GuiComponentData {
@@ -32,6 +72,13 @@ impl GuiComponentData {
         match self {
             GuiComponentData::Frame(frame) => frame.generate_sprites(item, sprites),
             GuiComponentData::Label(label) => label.generate_sprites(item, sprites),
+            GuiComponentData::TextInput(input) => input.generate_sprites(item, sprites),
+            GuiComponentData::Checkbox(checkbox) => checkbox.generate_sprites(item, sprites),
+            GuiComponentData::Slider(slider) => slider.generate_sprites(item, sprites),
+            GuiComponentData::Dropdown(dropdown) => dropdown.generate_sprites(item, sprites),
+            GuiComponentData::ScrollView(scroll_view) => scroll_view.generate_sprites(item, sprites),
+            GuiComponentData::Image(image) => image.generate_sprites(item, sprites),
+            GuiComponentData::Menu(menu) => menu.generate_sprites(item, sprites),
         }
     }
 
@@ -39,13 +86,26 @@ impl GuiComponentData {
         match self {
             GuiComponentData::Frame(frame) => frame.update_style(style, new_state),
             GuiComponentData::Label(label) => label.update_style(style, new_state),
+            GuiComponentData::TextInput(input) => input.update_style(style, new_state),
+            GuiComponentData::Checkbox(checkbox) => checkbox.update_style(style, new_state),
+            GuiComponentData::Slider(slider) => slider.update_style(style, new_state),
+            GuiComponentData::Dropdown(dropdown) => dropdown.update_style(style, new_state),
+            GuiComponentData::ScrollView(scroll_view) => scroll_view.update_style(style, new_state),
+            GuiComponentData::Image(image) => image.update_style(style, new_state),
+            GuiComponentData::Menu(menu) => menu.update_style(style, new_state),
         }
     }
 
     pub fn on_events(&mut self, callbacks: &GuiComponentCallbacksValue, output: &mut Vec<RawCallbackValue>, event: GuiInnerEvent) {
         match self {
-            GuiComponentData::Frame(_frame) => {},
+            // Menu items push their own callbacks when they are activated
+            GuiComponentData::Frame(_) | GuiComponentData::ScrollView(_) | GuiComponentData::Menu(_) => {},
             GuiComponentData::Label(label) => label.on_events(callbacks, output, event),
+            GuiComponentData::TextInput(input) => input.on_events(callbacks, output, event),
+            GuiComponentData::Checkbox(checkbox) => checkbox.on_events(callbacks, output, event),
+            GuiComponentData::Slider(slider) => slider.on_events(callbacks, output, event),
+            GuiComponentData::Dropdown(dropdown) => dropdown.on_events(callbacks, output, event),
+            GuiComponentData::Image(image) => image.on_events(callbacks, output, event),
         }
     }
 
@@ -0,0 +1,89 @@
+use loomz_shared::base_types::{RectF32, RgbaU8};
+use loomz_shared::assets::TextureId;
+use crate::gui::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState,
+  GuiComponentCallbacksValue, RawCallbackValue, GuiInnerEvent};
+
+#[derive(Copy, Clone)]
+pub enum GuiCheckboxCallback {
+    Change
+}
+
+#[derive(Copy, Clone)]
+pub struct GuiCheckboxCallbackValues {
+    pub change: u64,
+}
+
+#[derive(Clone, Copy)]
+pub struct GuiCheckboxStyle {
+    pub texture: TextureId,
+    pub region: RectF32,
+    pub background: RgbaU8,
+    pub check: RgbaU8,
+}
+
+#[derive(Copy, Clone)]
+pub struct GuiCheckbox {
+    pub style: GuiCheckboxStyle,
+    pub checked: bool,
+}
+
+impl GuiCheckbox {
+
+    pub fn toggle(&mut self) {
+        self.checked = !self.checked;
+    }
+
+    pub fn generate_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
+        let style = &self.style;
+        let [x, y] = item.position.splat();
+        let [width, height] = item.size.splat();
+
+        // The box is a square aligned on the left side of the item
+        let side = f32::min(width, height);
+        let top = y + ((height - side) * 0.5);
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(style.texture),
+            position: RectF32 { left: x, top, right: x + side, bottom: top + side },
+            texcoord: style.region,
+            color: style.background,
+            clip: RectF32::default(),
+        });
+
+        if self.checked {
+            let inset = side * 0.25;
+            sprites.push(GuiSprite {
+                ty: GuiSpriteType::Image(style.texture),
+                position: RectF32 { left: x + inset, top: top + inset, right: x + side - inset, bottom: top + side - inset },
+                texcoord: style.region,
+                color: style.check,
+                clip: RectF32::default(),
+            });
+        }
+    }
+
+    pub fn on_events(&mut self, callbacks: &GuiComponentCallbacksValue, output: &mut Vec<RawCallbackValue>, event: GuiInnerEvent) {
+        let callbacks_value = match callbacks {
+            GuiComponentCallbacksValue::Checkbox(values) => values,
+            _ => unreachable!("Callback types are enforced by the builder")
+        };
+
+        if let GuiInnerEvent::Change = event {
+            output.push(callbacks_value.change);
+        }
+    }
+
+    pub fn update_style(&mut self, style: &GuiComponentStyle, new_state: GuiStyleState) {
+        let style = match style {
+            GuiComponentStyle::Checkbox(checkbox_style) => checkbox_style,
+            _ => unreachable!("Styles are always valid")
+        };
+
+        self.style = match new_state {
+            GuiStyleState::Base => style.base,
+            GuiStyleState::Hovered => style.hovered,
+            GuiStyleState::Selected => style.selected,
+            GuiStyleState::Focused => style.focused,
+        };
+    }
+
+}
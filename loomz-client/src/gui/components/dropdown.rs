@@ -0,0 +1,166 @@
+use loomz_shared::base_types::{PositionF32, RectF32, RgbaU8};
+use loomz_shared::assets::{MsdfFontId, TextureId};
+use crate::gui::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState,
+  GuiComponentCallbacksValue, RawCallbackValue, GuiInnerEvent};
+use super::GuiLabel;
+
+#[derive(Copy, Clone)]
+pub enum GuiDropdownCallback {
+    Change
+}
+
+#[derive(Copy, Clone)]
+pub struct GuiDropdownCallbackValues {
+    pub change: u64,
+}
+
+#[derive(Clone, Copy)]
+pub struct GuiDropdownStyle {
+    pub font: MsdfFontId,
+    pub font_size: f32,
+    pub color: RgbaU8,
+    pub texture: TextureId,
+    pub region: RectF32,
+    pub background: RgbaU8,
+    pub highlight: RgbaU8,
+}
+
+/// A combo box. When expanded, the options are listed under the component, one row per option with the same size as the layout item.
+pub struct GuiDropdown {
+    pub style: GuiDropdownStyle,
+    pub options: Vec<GuiLabel>,
+    pub selected: u32,
+    pub hovered_option: u32,
+    pub expanded: bool,
+}
+
+impl GuiDropdown {
+
+    /// Returns the area covered by the options list when the dropdown is expanded
+    pub fn options_view(&self, item: &GuiLayoutItem) -> RectF32 {
+        let [x, y] = item.position.splat();
+        let [width, height] = item.size.splat();
+        let bottom = y + height;
+        RectF32 { left: x, top: bottom, right: x + width, bottom: bottom + (height * self.options.len() as f32) }
+    }
+
+    /// Returns the index of the option under `position`, or `u32::MAX` if there is none
+    pub fn option_at(&self, item: &GuiLayoutItem, position: PositionF32) -> u32 {
+        let view = self.options_view(item);
+        if !self.expanded || !view.is_point_inside(position) {
+            return u32::MAX;
+        }
+
+        let index = ((position.y - view.top) / item.size.height) as u32;
+        u32::min(index, self.options.len() as u32 - 1)
+    }
+
+    /// Moves the hovered option by `direction`. Starts from the selected option if no option is hovered.
+    pub fn hover_next(&mut self, direction: i32) {
+        let last = self.options.len() as i32 - 1;
+        let hovered = match self.hovered_option {
+            u32::MAX => self.selected as i32,
+            hovered => hovered as i32 + direction,
+        };
+
+        self.hovered_option = i32::clamp(hovered, 0, last) as u32;
+    }
+
+    /// Selects the hovered option and closes the dropdown. Returns `true` if the selected value changed.
+    pub fn select_hovered(&mut self) -> bool {
+        let hovered = self.hovered_option;
+        self.expanded = false;
+        self.hovered_option = u32::MAX;
+
+        if hovered != u32::MAX && hovered != self.selected {
+            self.selected = hovered;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn generate_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
+        let style = &self.style;
+        let [x, y] = item.position.splat();
+        let [width, height] = item.size.splat();
+
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(style.texture),
+            position: RectF32 { left: x, top: y, right: x + width, bottom: y + height },
+            texcoord: style.region,
+            color: style.background,
+            clip: RectF32::default(),
+        });
+
+        if let Some(option) = self.options.get(self.selected as usize) {
+            option.generate_sprites(item, sprites);
+        }
+    }
+
+    /// The options list is generated after every other component so that it is drawn over them
+    pub fn generate_options_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
+        if !self.expanded {
+            return;
+        }
+
+        let style = &self.style;
+        let mut option_item = *item;
+        for (index, option) in self.options.iter().enumerate() {
+            option_item.position.y += item.size.height;
+
+            let color = match index as u32 == self.hovered_option {
+                true => style.highlight,
+                false => style.background
+            };
+
+            let [x, y] = option_item.position.splat();
+            sprites.push(GuiSprite {
+                ty: GuiSpriteType::Image(style.texture),
+                position: RectF32 { left: x, top: y, right: x + item.size.width, bottom: y + item.size.height },
+                texcoord: style.region,
+                color,
+                clip: RectF32::default(),
+            });
+
+            option.generate_sprites(&option_item, sprites);
+        }
+    }
+
+    pub fn on_events(&mut self, callbacks: &GuiComponentCallbacksValue, output: &mut Vec<RawCallbackValue>, event: GuiInnerEvent) {
+        let callbacks_value = match callbacks {
+            GuiComponentCallbacksValue::Dropdown(values) => values,
+            _ => unreachable!("Callback types are enforced by the builder")
+        };
+
+        if let GuiInnerEvent::Change = event {
+            output.push(callbacks_value.change);
+        }
+    }
+
+    pub fn update_style(&mut self, style: &GuiComponentStyle, new_state: GuiStyleState) {
+        let style = match style {
+            GuiComponentStyle::Dropdown(dropdown_style) => dropdown_style,
+            _ => unreachable!("Styles are always valid")
+        };
+
+        let style = match new_state {
+            GuiStyleState::Base => style.base,
+            GuiStyleState::Hovered => style.hovered,
+            GuiStyleState::Selected => style.selected,
+            GuiStyleState::Focused => style.focused,
+        };
+
+        // Note: Like labels, font changes are not supported
+        self.style.color = style.color;
+        self.style.texture = style.texture;
+        self.style.region = style.region;
+        self.style.background = style.background;
+        self.style.highlight = style.highlight;
+
+        for option in self.options.iter_mut() {
+            option.color = style.color;
+        }
+    }
+
+}
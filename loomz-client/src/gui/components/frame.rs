@@ -1,5 +1,5 @@
 
-use loomz_shared::base_types::{SizeF32, RectF32, RgbaU8};
+use loomz_shared::base_types::{RectF32, RgbaU8};
 use loomz_shared::assets::TextureId;
 use super::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState};
 
@@ -7,28 +7,69 @@ use super::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyle
 pub(crate) struct GuiFrameStyle {
     pub texture: TextureId,
     pub region: RectF32,
+    /// Nine-slice border insets of `region` in texels. Each field is the width of the border on that side.
+    pub borders: RectF32,
+    /// Size in pixels of a border texel. This is the scale of the gui when the style was built.
+    pub border_scale: f32,
     pub color: RgbaU8,
 }
 
 #[derive(Copy, Clone)]
 pub(crate) struct GuiFrame {
     pub texture: TextureId,
-    pub size: SizeF32,
     pub texcoord: RectF32,
+    pub borders: RectF32,
+    pub border_scale: f32,
     pub color: RgbaU8,
 }
 
 impl GuiFrame {
     pub fn generate_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
-        sprites.push(GuiSprite {
-            ty: GuiSpriteType::Image(self.texture),
-            position: RectF32 {
-                left: item.position.x, right: item.position.x + self.size.width,
-                top: item.position.y, bottom: item.position.y + self.size.height,
-            },
-            texcoord: self.texcoord,
-            color: self.color,
-        });
+        let position = RectF32 {
+            left: item.position.x, right: item.position.x + item.size.width,
+            top: item.position.y, bottom: item.position.y + item.size.height,
+        };
+
+        let t = self.borders;
+        if t.left + t.top + t.right + t.bottom <= 0.0 {
+            sprites.push(GuiSprite {
+                ty: GuiSpriteType::Image(self.texture),
+                position,
+                texcoord: self.texcoord,
+                color: self.color,
+                clip: RectF32::default(),
+            });
+            return;
+        }
+
+        // Borders keep their size in pixels unless the frame is too small to fit them
+        let b = t * self.border_scale;
+        let scale_x = f32::min(1.0, item.size.width / f32::max(b.left + b.right, 1.0));
+        let scale_y = f32::min(1.0, item.size.height / f32::max(b.top + b.bottom, 1.0));
+
+        let p = position;
+        let c = self.texcoord;
+        let xs = [p.left, p.left + (b.left * scale_x), p.right - (b.right * scale_x), p.right];
+        let ys = [p.top, p.top + (b.top * scale_y), p.bottom - (b.bottom * scale_y), p.bottom];
+        let us = [c.left, c.left + t.left, c.right - t.right, c.right];
+        let vs = [c.top, c.top + t.top, c.bottom - t.bottom, c.bottom];
+
+        for row in 0..3 {
+            for col in 0..3 {
+                let position = RectF32 { left: xs[col], top: ys[row], right: xs[col+1], bottom: ys[row+1] };
+                if !position.has_area() {
+                    continue;
+                }
+
+                sprites.push(GuiSprite {
+                    ty: GuiSpriteType::Image(self.texture),
+                    position,
+                    texcoord: RectF32 { left: us[col], top: vs[row], right: us[col+1], bottom: vs[row+1] },
+                    color: self.color,
+                    clip: RectF32::default(),
+                });
+            }
+        }
     }
 
     pub fn update_style(&mut self, style: &GuiComponentStyle, new_state: GuiStyleState) {
@@ -41,10 +82,13 @@ impl GuiFrame {
             GuiStyleState::Base => style.base,
             GuiStyleState::Hovered => style.hovered,
             GuiStyleState::Selected => style.selected,
+            GuiStyleState::Focused => style.focused,
         };
 
         self.texture = style.texture;
         self.texcoord = style.region;
+        self.borders = style.borders;
+        self.border_scale = style.border_scale;
         self.color = style.color;
     }
 }
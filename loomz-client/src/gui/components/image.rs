@@ -0,0 +1,100 @@
+use loomz_shared::base_types::{RectF32, RgbaU8};
+use loomz_shared::assets::TextureId;
+use crate::gui::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState,
+  GuiComponentCallbacksValue, GuiPointerCallback, RawCallbackValue, GuiInnerEvent};
+
+pub type GuiImageCallback = GuiPointerCallback;
+
+/// How an image is sized in its layout item
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GuiImageFit {
+    /// Scale the image to fit in the item while keeping its aspect ratio
+    Fit,
+    /// Scale the image to cover the item while keeping its aspect ratio. Parts of the image outside the item are cropped.
+    Fill,
+    /// Stretch the image to the size of the item
+    Stretch,
+}
+
+#[derive(Clone, Copy)]
+pub struct GuiImageStyle {
+    pub tint: RgbaU8,
+}
+
+#[derive(Copy, Clone)]
+pub struct GuiImage {
+    pub texture: TextureId,
+    /// Region of the image in the texture, in texels
+    pub region: RectF32,
+    pub fit: GuiImageFit,
+    pub tint: RgbaU8,
+}
+
+impl GuiImage {
+
+    pub fn generate_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
+        let [x, y] = item.position.splat();
+        let [width, height] = item.size.splat();
+        let [region_width, region_height] = self.region.size();
+        if region_width <= 0.0 || region_height <= 0.0 {
+            return;
+        }
+
+        let mut position = RectF32 { left: x, top: y, right: x + width, bottom: y + height };
+        let mut texcoord = self.region;
+
+        match self.fit {
+            GuiImageFit::Fit => {
+                let scale = f32::min(width / region_width, height / region_height);
+                let [w, h] = [region_width * scale, region_height * scale];
+                position.left = x + ((width - w) * 0.5);
+                position.top = y + ((height - h) * 0.5);
+                position.right = position.left + w;
+                position.bottom = position.top + h;
+            },
+            GuiImageFit::Fill => {
+                let scale = f32::max(width / region_width, height / region_height);
+                let [w, h] = [width / scale, height / scale];
+                texcoord.left += (region_width - w) * 0.5;
+                texcoord.top += (region_height - h) * 0.5;
+                texcoord.right = texcoord.left + w;
+                texcoord.bottom = texcoord.top + h;
+            },
+            GuiImageFit::Stretch => {}
+        }
+
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(self.texture),
+            position,
+            texcoord,
+            color: self.tint,
+            clip: RectF32::default(),
+        });
+    }
+
+    pub fn on_events(&mut self, callbacks: &GuiComponentCallbacksValue, output: &mut Vec<RawCallbackValue>, event: GuiInnerEvent) {
+        let callbacks_value = match callbacks {
+            GuiComponentCallbacksValue::Image(values) => values,
+            _ => unreachable!("Callback types are enforced by the builder")
+        };
+
+        callbacks_value.on_events(output, event);
+    }
+
+    pub fn update_style(&mut self, style: &GuiComponentStyle, new_state: GuiStyleState) {
+        let style = match style {
+            GuiComponentStyle::Image(image_style) => image_style,
+            _ => unreachable!("Styles are always valid")
+        };
+
+        let style = match new_state {
+            GuiStyleState::Base => style.base,
+            GuiStyleState::Hovered => style.hovered,
+            GuiStyleState::Selected => style.selected,
+            GuiStyleState::Focused => style.focused,
+        };
+
+        self.tint = style.tint;
+    }
+
+}
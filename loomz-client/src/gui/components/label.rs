@@ -1,18 +1,10 @@
-use loomz_shared::base_types::{SizeF32, RgbaU8};
+use loomz_shared::base_types::{SizeF32, RectF32, RgbaU8};
 use loomz_shared::assets::MsdfFontId;
 use loomz_shared::assets::msdf_font::ComputedGlyph;
 use crate::gui::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState,
-  GuiComponentCallbacksValue, RawCallbackValue, GuiInnerEvent};
+  GuiComponentCallbacksValue, GuiPointerCallback, RawCallbackValue, GuiInnerEvent};
 
-#[derive(Copy, Clone)]
-pub enum GuiLabelCallback {
-    Click
-}
-
-#[derive(Copy, Clone)]
-pub struct GuiLabelCallbackValues {
-    pub click: u64,
-}
+pub type GuiLabelCallback = GuiPointerCallback;
 
 #[derive(Clone, Copy)]
 pub struct GuiLabelStyle {
@@ -21,10 +13,23 @@ pub struct GuiLabelStyle {
     pub color: RgbaU8
 }
 
+/// Glyphs of a label built from markup. See the `markup` module.
+#[derive(Clone, Copy)]
+pub struct GuiLabelRun {
+    /// Range of the run in `GuiLabel::glyphs`
+    pub start: u32,
+    pub end: u32,
+    pub font: MsdfFontId,
+    /// Uses the color of the label style if `None`
+    pub color: Option<RgbaU8>,
+}
+
 pub struct GuiLabel {
     pub glyphs: Box<[ComputedGlyph]>,
     pub color: RgbaU8,
     pub font: MsdfFontId,
+    /// Empty if the label was built without markup. Otherwise, the runs cover all the glyphs.
+    pub runs: Box<[GuiLabelRun]>,
 }
 
 impl GuiLabel {
@@ -46,21 +51,32 @@ impl GuiLabel {
     }
 
     pub fn generate_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
-        let font = self.font;
-        let color = self.color;
         let [mut x, mut y] = item.position.splat();
 
         let size = self.size();
         x += (item.size.width - size.width) / 2.0;
         y += (item.size.height - size.height) / 2.0;
 
-        for glyph in self.glyphs.iter() {
-            sprites.push(GuiSprite {
-                ty: GuiSpriteType::Font(font),
-                position: glyph.position.translate_into(x, y),
-                texcoord: glyph.texcoord,
-                color,
-            });
+        let mut push_glyphs = |glyphs: &[ComputedGlyph], font: MsdfFontId, color: RgbaU8| {
+            for glyph in glyphs.iter() {
+                sprites.push(GuiSprite {
+                    ty: GuiSpriteType::Font(font),
+                    position: glyph.position.translate_into(x, y),
+                    texcoord: glyph.texcoord,
+                    color,
+                    clip: RectF32::default(),
+                });
+            }
+        };
+
+        if self.runs.is_empty() {
+            push_glyphs(&self.glyphs, self.font, self.color);
+            return;
+        }
+
+        for run in self.runs.iter() {
+            let glyphs = &self.glyphs[(run.start as usize)..(run.end as usize)];
+            push_glyphs(glyphs, run.font, run.color.unwrap_or(self.color));
         }
     }
 
@@ -74,6 +90,7 @@ impl GuiLabel {
             GuiStyleState::Base => style.base,
             GuiStyleState::Hovered => style.hovered,
             GuiStyleState::Selected => style.selected,
+            GuiStyleState::Focused => style.focused,
         };
 
         // Note: Font change not supported because recomputing the glyph would be a pain in the ass
@@ -85,12 +102,8 @@ impl GuiLabel {
             GuiComponentCallbacksValue::Label(values) => values,
             _ => unreachable!("Callback types are enforced by the builder")
         };
-        
-        match event {
-            GuiInnerEvent::Click => {
-                output.push(callbacks_value.click);
-            }
-        }
+
+        callbacks_value.on_events(output, event);
     }
 }
 
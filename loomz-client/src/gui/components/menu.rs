@@ -0,0 +1,278 @@
+use loomz_shared::base_types::{PositionF32, RectF32, RgbaU8};
+use loomz_shared::assets::{MsdfFontId, TextureId};
+use crate::gui::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState};
+use super::GuiLabel;
+
+/// Height of a separator row relative to the height of an item row
+const SEPARATOR_SCALE: f32 = 0.5;
+
+/// Space between the border of the popup and the text of the items
+const ITEM_PADDING: f32 = 8.0;
+
+/// Minimum space between the text of an item and its accelerator
+const ACCELERATOR_SPACING: f32 = 30.0;
+
+#[derive(Clone, Copy)]
+pub struct GuiMenuStyle {
+    pub font: MsdfFontId,
+    pub font_size: f32,
+    pub color: RgbaU8,
+    pub texture: TextureId,
+    pub region: RectF32,
+    pub background: RgbaU8,
+    pub highlight: RgbaU8,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum GuiMenuItemType {
+    Item,
+    Checkable,
+    Separator,
+}
+
+/// Key that activates a menu item while the gui is visible, even if the menu is closed
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct GuiMenuAccelerator {
+    pub key: u32,
+    pub ctrl: bool,
+}
+
+pub struct GuiMenuItem {
+    pub ty: GuiMenuItemType,
+    pub checked: bool,
+    pub callback: u64,
+    pub accelerator: Option<GuiMenuAccelerator>,
+    pub label: GuiLabel,
+    /// Text of the accelerator, drawn on the right side of the item
+    pub accelerator_label: GuiLabel,
+}
+
+/// An entry of a menu bar. When expanded, the items are listed in a popup under the component.
+pub struct GuiMenu {
+    pub style: GuiMenuStyle,
+    pub title: GuiLabel,
+    pub items: Vec<GuiMenuItem>,
+    pub hovered_item: u32,
+    pub expanded: bool,
+}
+
+impl GuiMenuItem {
+
+    fn height(&self, row_height: f32) -> f32 {
+        match self.ty {
+            GuiMenuItemType::Separator => row_height * SEPARATOR_SCALE,
+            _ => row_height
+        }
+    }
+
+    /// Toggles checkable items. Returns the callback of the item.
+    pub fn activate(&mut self) -> u64 {
+        if self.ty == GuiMenuItemType::Checkable {
+            self.checked = !self.checked;
+        }
+
+        self.callback
+    }
+
+}
+
+impl GuiMenu {
+
+    /// Returns the area covered by the popup when the menu is expanded. Item rows have the same height as the menu
+    /// and the popup is wide enough to fit the text of every item.
+    pub fn popup_view(&self, item: &GuiLayoutItem) -> RectF32 {
+        let row_height = item.size.height;
+        let mut width = item.size.width;
+        let mut height = 0.0;
+        for menu_item in self.items.iter() {
+            let accelerator_width = match menu_item.accelerator_label.glyphs.is_empty() {
+                true => 0.0,
+                false => ACCELERATOR_SPACING + menu_item.accelerator_label.size().width,
+            };
+
+            width = f32::max(width, row_height + menu_item.label.size().width + accelerator_width + ITEM_PADDING);
+            height += menu_item.height(row_height);
+        }
+
+        let [x, y] = item.position.splat();
+        let top = y + row_height;
+        RectF32 { left: x, top, right: x + width, bottom: top + height }
+    }
+
+    /// Returns the index of the item under `position`, or `u32::MAX` if there is none. Separators cannot be hovered.
+    pub fn item_at(&self, item: &GuiLayoutItem, position: PositionF32) -> u32 {
+        let view = self.popup_view(item);
+        if !self.expanded || !view.is_point_inside(position) {
+            return u32::MAX;
+        }
+
+        let mut top = view.top;
+        for (index, menu_item) in self.items.iter().enumerate() {
+            let bottom = top + menu_item.height(item.size.height);
+            if position.y < bottom {
+                return match menu_item.ty {
+                    GuiMenuItemType::Separator => u32::MAX,
+                    _ => index as u32
+                };
+            }
+
+            top = bottom;
+        }
+
+        u32::MAX
+    }
+
+    /// Moves the hovered item by `direction`, skipping the separators. Starts from the first item if no item is hovered.
+    pub fn hover_next(&mut self, direction: i32) {
+        let count = self.items.len() as i32;
+        let mut index = match self.hovered_item {
+            u32::MAX => -1,
+            hovered => hovered as i32,
+        };
+
+        let direction = if direction < 0 && index >= 0 { -1 } else { 1 };
+        for _ in 0..count {
+            index = (index + direction).rem_euclid(count);
+            if self.items[index as usize].ty != GuiMenuItemType::Separator {
+                self.hovered_item = index as u32;
+                return;
+            }
+        }
+    }
+
+    /// Closes the popup and activates the hovered item. Returns the callback of the activated item.
+    pub fn activate_hovered(&mut self) -> Option<u64> {
+        let hovered = self.hovered_item;
+        self.close();
+
+        self.items.get_mut(hovered as usize).map(|item| item.activate() )
+    }
+
+    pub fn close(&mut self) {
+        self.expanded = false;
+        self.hovered_item = u32::MAX;
+    }
+
+    pub fn generate_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
+        let style = &self.style;
+        let [x, y] = item.position.splat();
+        let [width, height] = item.size.splat();
+
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(style.texture),
+            position: RectF32 { left: x, top: y, right: x + width, bottom: y + height },
+            texcoord: style.region,
+            color: style.background,
+            clip: RectF32::default(),
+        });
+
+        self.title.generate_sprites(item, sprites);
+    }
+
+    /// The popup is generated after every other component so that it is drawn over them.
+    /// Checkable items have a check mark column on the left and the accelerators are aligned on the right.
+    pub fn generate_popup_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
+        if !self.expanded {
+            return;
+        }
+
+        let style = &self.style;
+        let view = self.popup_view(item);
+        let row_height = item.size.height;
+
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(style.texture),
+            position: view,
+            texcoord: style.region,
+            color: style.background,
+            clip: RectF32::default(),
+        });
+
+        let mut top = view.top;
+        for (index, menu_item) in self.items.iter().enumerate() {
+            let height = menu_item.height(row_height);
+            let row = RectF32 { left: view.left, top, right: view.right, bottom: top + height };
+            top += height;
+
+            if menu_item.ty == GuiMenuItemType::Separator {
+                let center = row.top + (height * 0.5);
+                sprites.push(GuiSprite {
+                    ty: GuiSpriteType::Image(style.texture),
+                    position: RectF32 { left: row.left + ITEM_PADDING, top: center - 0.5, right: row.right - ITEM_PADDING, bottom: center + 0.5 },
+                    texcoord: style.region,
+                    color: style.color,
+                    clip: RectF32::default(),
+                });
+                continue;
+            }
+
+            if index as u32 == self.hovered_item {
+                sprites.push(GuiSprite {
+                    ty: GuiSpriteType::Image(style.texture),
+                    position: row,
+                    texcoord: style.region,
+                    color: style.highlight,
+                    clip: RectF32::default(),
+                });
+            }
+
+            if menu_item.checked {
+                let inset = row_height * 0.3;
+                sprites.push(GuiSprite {
+                    ty: GuiSpriteType::Image(style.texture),
+                    position: RectF32 { left: row.left + inset, top: row.top + inset, right: row.left + row_height - inset, bottom: row.bottom - inset },
+                    texcoord: style.region,
+                    color: style.color,
+                    clip: RectF32::default(),
+                });
+            }
+
+            push_text(&menu_item.label, row.left + row_height, &row, sprites);
+
+            let accelerator_width = menu_item.accelerator_label.size().width;
+            push_text(&menu_item.accelerator_label, row.right - ITEM_PADDING - accelerator_width, &row, sprites);
+        }
+    }
+
+    pub fn update_style(&mut self, style: &GuiComponentStyle, new_state: GuiStyleState) {
+        let style = match style {
+            GuiComponentStyle::Menu(menu_style) => menu_style,
+            _ => unreachable!("Styles are always valid")
+        };
+
+        let style = match new_state {
+            GuiStyleState::Base => style.base,
+            GuiStyleState::Hovered => style.hovered,
+            GuiStyleState::Selected => style.selected,
+            GuiStyleState::Focused => style.focused,
+        };
+
+        // Note: Like labels, font changes are not supported
+        self.style.color = style.color;
+        self.style.texture = style.texture;
+        self.style.region = style.region;
+        self.style.background = style.background;
+        self.style.highlight = style.highlight;
+
+        self.title.color = style.color;
+        for menu_item in self.items.iter_mut() {
+            menu_item.label.color = style.color;
+            menu_item.accelerator_label.color = style.color;
+        }
+    }
+
+}
+
+/// Pushes the glyphs of `label` starting at `x`, vertically centered in `row`
+fn push_text(label: &GuiLabel, x: f32, row: &RectF32, sprites: &mut Vec<GuiSprite>) {
+    let y = row.top + ((row.height() - label.size().height) * 0.5);
+    for glyph in label.glyphs.iter() {
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Font(label.font),
+            position: glyph.position.translate_into(x, y),
+            texcoord: glyph.texcoord,
+            color: label.color,
+            clip: RectF32::default(),
+        });
+    }
+}
@@ -0,0 +1,125 @@
+use loomz_shared::base_types::{SizeF32, RectF32, RgbaU8};
+use loomz_shared::assets::TextureId;
+use crate::gui::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState};
+
+const SCROLLBAR_WIDTH: f32 = 12.0;
+const SCROLLBAR_MIN_HANDLE: f32 = 20.0;
+
+#[derive(Clone, Copy)]
+pub struct GuiScrollViewStyle {
+    pub texture: TextureId,
+    pub region: RectF32,
+    pub background: RgbaU8,
+    pub scrollbar: RgbaU8,
+    pub handle: RgbaU8,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum GuiScrollDrag {
+    None,
+    Content,
+    Scrollbar,
+}
+
+/// A vertical container that clips its children. Children are the `descendants` components following the scroll view.
+#[derive(Copy, Clone)]
+pub struct GuiScrollView {
+    pub style: GuiScrollViewStyle,
+    pub size: SizeF32,
+    pub content_height: f32,
+    pub scroll: f32,
+    pub descendants: u32,
+    pub drag: GuiScrollDrag,
+}
+
+impl GuiScrollView {
+
+    pub fn max_scroll(&self) -> f32 {
+        f32::max(0.0, self.content_height - self.size.height)
+    }
+
+    /// Sets the scroll offset. Returns by how much the scroll offset moved.
+    pub fn set_scroll(&mut self, scroll: f32) -> f32 {
+        let scroll = f32::clamp(scroll, 0.0, self.max_scroll());
+        let delta = scroll - self.scroll;
+        self.scroll = scroll;
+        delta
+    }
+
+    /// Returns the area covered by the scrollbar. The scrollbar is only shown if the content is bigger than the view.
+    pub fn scrollbar_view(&self, item: &GuiLayoutItem) -> Option<RectF32> {
+        if self.max_scroll() <= 0.0 {
+            return None;
+        }
+
+        let [x, y] = item.position.splat();
+        let right = x + self.size.width;
+        Some(RectF32 { left: right - SCROLLBAR_WIDTH, top: y, right, bottom: y + self.size.height })
+    }
+
+    fn handle_height(&self) -> f32 {
+        let height = self.size.height;
+        f32::min(height, f32::max(SCROLLBAR_MIN_HANDLE, height * (height / self.content_height)))
+    }
+
+    /// Scrolls the content so that the center of the scrollbar handle is at the screen position `y`. Returns by how much the scroll offset moved.
+    pub fn scroll_from_scrollbar(&mut self, item: &GuiLayoutItem, y: f32) -> f32 {
+        let handle_height = self.handle_height();
+        let track_height = self.size.height - handle_height;
+        if track_height <= 0.0 {
+            return 0.0;
+        }
+
+        let t = f32::clamp((y - item.position.y - (handle_height * 0.5)) / track_height, 0.0, 1.0);
+        self.set_scroll(t * self.max_scroll())
+    }
+
+    pub fn generate_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
+        let style = &self.style;
+        let [x, y] = item.position.splat();
+
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(style.texture),
+            position: RectF32 { left: x, top: y, right: x + self.size.width, bottom: y + self.size.height },
+            texcoord: style.region,
+            color: style.background,
+            clip: RectF32::default(),
+        });
+
+        if let Some(scrollbar) = self.scrollbar_view(item) {
+            let handle_height = self.handle_height();
+            let handle_top = scrollbar.top + ((self.size.height - handle_height) * (self.scroll / self.max_scroll()));
+
+            sprites.push(GuiSprite {
+                ty: GuiSpriteType::Image(style.texture),
+                position: scrollbar,
+                texcoord: style.region,
+                color: style.scrollbar,
+                clip: RectF32::default(),
+            });
+
+            sprites.push(GuiSprite {
+                ty: GuiSpriteType::Image(style.texture),
+                position: RectF32 { left: scrollbar.left, top: handle_top, right: scrollbar.right, bottom: handle_top + handle_height },
+                texcoord: style.region,
+                color: style.handle,
+                clip: RectF32::default(),
+            });
+        }
+    }
+
+    pub fn update_style(&mut self, style: &GuiComponentStyle, new_state: GuiStyleState) {
+        let style = match style {
+            GuiComponentStyle::ScrollView(scroll_view_style) => scroll_view_style,
+            _ => unreachable!("Styles are always valid")
+        };
+
+        self.style = match new_state {
+            GuiStyleState::Base => style.base,
+            GuiStyleState::Hovered => style.hovered,
+            GuiStyleState::Selected => style.selected,
+            GuiStyleState::Focused => style.focused,
+        };
+    }
+
+}
@@ -0,0 +1,147 @@
+use loomz_shared::base_types::{RectF32, RgbaU8};
+use loomz_shared::assets::TextureId;
+use crate::gui::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState,
+  GuiComponentCallbacksValue, RawCallbackValue, GuiInnerEvent};
+
+#[derive(Copy, Clone)]
+pub enum GuiSliderCallback {
+    Change
+}
+
+#[derive(Copy, Clone)]
+pub struct GuiSliderCallbackValues {
+    pub change: u64,
+}
+
+#[derive(Clone, Copy)]
+pub struct GuiSliderStyle {
+    pub texture: TextureId,
+    pub region: RectF32,
+    pub track: RgbaU8,
+    pub handle: RgbaU8,
+}
+
+/// A horizontal slider. `value` is always in `[min, max]` and snapped to `step` (if `step` is greater than 0)
+#[derive(Copy, Clone)]
+pub struct GuiSlider {
+    pub style: GuiSliderStyle,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    pub value: f32,
+}
+
+impl GuiSlider {
+
+    pub fn new(style: GuiSliderStyle, value: f32, min: f32, max: f32, step: f32) -> Self {
+        let mut slider = GuiSlider { style, min, max, step, value: min };
+        slider.set_value(value);
+        slider
+    }
+
+    /// Sets the value of the slider. Returns `true` if the value changed.
+    pub fn set_value(&mut self, value: f32) -> bool {
+        let mut value = f32::clamp(value, self.min, f32::max(self.min, self.max));
+        if self.step > 0.0 {
+            value = self.min + (((value - self.min) / self.step).round() * self.step);
+            value = f32::min(value, self.max);
+        }
+
+        let changed = value != self.value;
+        self.value = value;
+        changed
+    }
+
+    /// Moves the value by one step in `direction`. Continuous sliders move by a tenth of their range. Returns `true` if the value changed.
+    pub fn step_by(&mut self, direction: i32) -> bool {
+        let step = match self.step > 0.0 {
+            true => self.step,
+            false => (self.max - self.min) * 0.1,
+        };
+
+        self.set_value(self.value + (step * direction as f32))
+    }
+
+    /// Sets the value of the slider from the screen position `x`. Returns `true` if the value changed.
+    pub fn set_value_from_position(&mut self, item: &GuiLayoutItem, x: f32) -> bool {
+        let handle_width = Self::handle_width(item);
+        let usable_width = item.size.width - handle_width;
+        if usable_width <= 0.0 {
+            return false;
+        }
+
+        let t = f32::clamp((x - item.position.x - (handle_width * 0.5)) / usable_width, 0.0, 1.0);
+        self.set_value(self.min + ((self.max - self.min) * t))
+    }
+
+    fn handle_width(item: &GuiLayoutItem) -> f32 {
+        item.size.height * 0.5
+    }
+
+    pub fn generate_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
+        let style = &self.style;
+        let [x, y] = item.position.splat();
+        let [width, height] = item.size.splat();
+
+        let handle_width = Self::handle_width(item);
+        let range = self.max - self.min;
+        let t = match range > 0.0 {
+            true => (self.value - self.min) / range,
+            false => 0.0
+        };
+
+        let track_height = height * 0.25;
+        let track_top = y + ((height - track_height) * 0.5);
+        let handle_x = x + ((width - handle_width) * t);
+
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(style.texture),
+            position: RectF32 { left: x, top: track_top, right: x + width, bottom: track_top + track_height },
+            texcoord: style.region,
+            color: style.track,
+            clip: RectF32::default(),
+        });
+
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(style.texture),
+            position: RectF32 { left: x, top: track_top, right: handle_x, bottom: track_top + track_height },
+            texcoord: style.region,
+            color: style.handle,
+            clip: RectF32::default(),
+        });
+
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(style.texture),
+            position: RectF32 { left: handle_x, top: y, right: handle_x + handle_width, bottom: y + height },
+            texcoord: style.region,
+            color: style.handle,
+            clip: RectF32::default(),
+        });
+    }
+
+    pub fn on_events(&mut self, callbacks: &GuiComponentCallbacksValue, output: &mut Vec<RawCallbackValue>, event: GuiInnerEvent) {
+        let callbacks_value = match callbacks {
+            GuiComponentCallbacksValue::Slider(values) => values,
+            _ => unreachable!("Callback types are enforced by the builder")
+        };
+
+        if let GuiInnerEvent::Change = event {
+            output.push(callbacks_value.change);
+        }
+    }
+
+    pub fn update_style(&mut self, style: &GuiComponentStyle, new_state: GuiStyleState) {
+        let style = match style {
+            GuiComponentStyle::Slider(slider_style) => slider_style,
+            _ => unreachable!("Styles are always valid")
+        };
+
+        self.style = match new_state {
+            GuiStyleState::Base => style.base,
+            GuiStyleState::Hovered => style.hovered,
+            GuiStyleState::Selected => style.selected,
+            GuiStyleState::Focused => style.focused,
+        };
+    }
+
+}
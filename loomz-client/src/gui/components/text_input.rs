@@ -0,0 +1,361 @@
+use loomz_shared::base_types::{RectF32, RgbaU8, rgba};
+use loomz_shared::assets::{MsdfFontId, TextureId};
+use loomz_shared::assets::msdf_font::ComputedGlyph;
+use loomz_shared::LoomzApi;
+use crate::gui::{GuiLayoutItem, GuiSprite, GuiSpriteType, GuiComponentStyle, GuiStyleState,
+  GuiComponentCallbacksValue, RawCallbackValue, GuiInnerEvent};
+
+const TEXT_PADDING: f32 = 8.0;
+const CARET_WIDTH: f32 = 2.0;
+
+#[derive(Copy, Clone)]
+#[allow(dead_code)]
+pub enum GuiTextInputCallback {
+    Change,
+    Submit,
+}
+
+#[derive(Copy, Clone)]
+pub struct GuiTextInputCallbackValues {
+    pub change: u64,
+    pub submit: u64,
+}
+
+#[derive(Clone, Copy)]
+pub struct GuiTextInputStyle {
+    pub font: MsdfFontId,
+    pub font_size: f32,
+    pub color: RgbaU8,
+    pub texture: TextureId,
+    pub region: RectF32,
+    pub background: RgbaU8,
+}
+
+/// A single line text field. Positions in the text (`caret`, `anchor`) are expressed in graphemes.
+pub struct GuiTextInput {
+    pub style: GuiTextInputStyle,
+    pub value: String,
+    pub placeholder: String,
+    pub max_length: u32,
+    pub caret: u32,
+    pub anchor: u32,
+    pub focused: bool,
+
+    /// Glyphs of `value`, or of `placeholder` if `value` is empty
+    pub glyphs: Vec<ComputedGlyph>,
+    /// X offset of every grapheme boundary of `value`. Length is always `graphemes_count + 1`
+    pub offsets: Vec<f32>,
+    /// Byte offset of every grapheme boundary of `value`. Length is always `graphemes_count + 1`
+    pub bytes: Vec<u32>,
+}
+
+impl GuiTextInput {
+
+    pub fn new(api: &LoomzApi, style: GuiTextInputStyle, value: &str, placeholder: &str, max_length: u32) -> Self {
+        let mut input = GuiTextInput {
+            style,
+            value: value.to_string(),
+            placeholder: placeholder.to_string(),
+            max_length,
+            caret: 0,
+            anchor: 0,
+            focused: false,
+            glyphs: Vec::with_capacity(value.len()),
+            offsets: Vec::with_capacity(value.len() + 1),
+            bytes: Vec::with_capacity(value.len() + 1),
+        };
+
+        input.truncate_to_max_length();
+        input.compute_glyphs(api);
+        input.caret = input.graphemes_count();
+        input.anchor = input.caret;
+        input
+    }
+
+    pub fn graphemes_count(&self) -> u32 {
+        (self.bytes.len() as u32).saturating_sub(1)
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.caret != self.anchor
+    }
+
+    pub fn selection(&self) -> (u32, u32) {
+        (u32::min(self.caret, self.anchor), u32::max(self.caret, self.anchor))
+    }
+
+    /// Recompute the glyphs and the grapheme boundaries. Must be called every time `value` changes.
+    pub fn compute_glyphs(&mut self, api: &LoomzApi) {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        self.glyphs.clear();
+        self.offsets.clear();
+        self.bytes.clear();
+
+        let font_asset = match api.assets_ref().font(self.style.font) {
+            Some(font) => font,
+            None => unreachable!("Font presence is validated by the builder")
+        };
+
+        let scale = self.style.font_size;
+        let mut advance = 0.0;
+        let mut glyph = ComputedGlyph::default();
+
+        for (index, g) in self.value.grapheme_indices(true) {
+            self.offsets.push(advance);
+            self.bytes.push(index as u32);
+
+            let a = font_asset.font_data.compute_glyph(g, scale, &mut glyph);
+            glyph.position.left += advance;
+            glyph.position.right += advance;
+
+            advance += a;
+            self.glyphs.push(glyph);
+        }
+
+        self.offsets.push(advance);
+        self.bytes.push(self.value.len() as u32);
+
+        if self.value.is_empty() {
+            for g in self.placeholder.graphemes(true) {
+                let a = font_asset.font_data.compute_glyph(g, scale, &mut glyph);
+                glyph.position.left += advance;
+                glyph.position.right += advance;
+
+                advance += a;
+                self.glyphs.push(glyph);
+            }
+        }
+    }
+
+    /// Moves the caret to the grapheme boundary closest to the screen position `x`
+    pub fn caret_from_position(&self, item: &GuiLayoutItem, x: f32) -> u32 {
+        let local_x = x - (item.position.x + TEXT_PADDING);
+        let mut best = 0;
+        let mut best_distance = f32::MAX;
+        for (index, &offset) in self.offsets.iter().enumerate() {
+            let distance = f32::abs(offset - local_x);
+            if distance < best_distance {
+                best = index as u32;
+                best_distance = distance;
+            }
+        }
+
+        best
+    }
+
+    pub fn move_caret(&mut self, caret: u32, extend_selection: bool) {
+        self.caret = u32::min(caret, self.graphemes_count());
+        if !extend_selection {
+            self.anchor = self.caret;
+        }
+    }
+
+    pub fn move_left(&mut self, extend_selection: bool) {
+        if self.has_selection() && !extend_selection {
+            let (start, _) = self.selection();
+            self.move_caret(start, false);
+        } else {
+            self.move_caret(self.caret.saturating_sub(1), extend_selection);
+        }
+    }
+
+    pub fn move_right(&mut self, extend_selection: bool) {
+        if self.has_selection() && !extend_selection {
+            let (_, end) = self.selection();
+            self.move_caret(end, false);
+        } else {
+            self.move_caret(self.caret + 1, extend_selection);
+        }
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = 0;
+        self.caret = self.graphemes_count();
+    }
+
+    /// Replace the current selection by `text`. Returns `true` if the value changed.
+    pub fn insert(&mut self, api: &LoomzApi, text: &str) -> bool {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let deleted = self.delete_selection();
+
+        let available = self.max_length.saturating_sub(self.graphemes_count()) as usize;
+        let mut inserted_bytes = 0;
+        let mut inserted_count = 0;
+        for g in text.graphemes(true).take(available) {
+            inserted_bytes += g.len();
+            inserted_count += 1;
+        }
+
+        if inserted_count == 0 {
+            if deleted {
+                self.compute_glyphs(api);
+            }
+            return deleted;
+        }
+
+        let byte_offset = self.bytes[self.caret as usize] as usize;
+        self.value.insert_str(byte_offset, &text[0..inserted_bytes]);
+        self.compute_glyphs(api);
+        self.move_caret(self.caret + inserted_count, false);
+
+        true
+    }
+
+    /// Removes the selection, or the grapheme before the caret. Returns `true` if the value changed.
+    pub fn backspace(&mut self, api: &LoomzApi) -> bool {
+        if !self.has_selection() {
+            if self.caret == 0 {
+                return false;
+            }
+            self.anchor = self.caret - 1;
+        }
+
+        self.delete_selection();
+        self.compute_glyphs(api);
+        true
+    }
+
+    /// Removes the selection, or the grapheme after the caret. Returns `true` if the value changed.
+    pub fn delete(&mut self, api: &LoomzApi) -> bool {
+        if !self.has_selection() {
+            if self.caret >= self.graphemes_count() {
+                return false;
+            }
+            self.anchor = self.caret + 1;
+        }
+
+        self.delete_selection();
+        self.compute_glyphs(api);
+        true
+    }
+
+    /// Removes the selected text without recomputing the glyphs
+    fn delete_selection(&mut self) -> bool {
+        if !self.has_selection() {
+            return false;
+        }
+
+        let (start, end) = self.selection();
+        let start_byte = self.bytes[start as usize] as usize;
+        let end_byte = self.bytes[end as usize] as usize;
+        self.value.replace_range(start_byte..end_byte, "");
+
+        // Keep the boundaries valid until the glyphs are recomputed
+        let removed = end_byte - start_byte;
+        self.bytes.drain((start as usize + 1)..=(end as usize));
+        for byte in self.bytes.iter_mut().skip(start as usize + 1) {
+            *byte -= removed as u32;
+        }
+
+        self.caret = start;
+        self.anchor = start;
+
+        true
+    }
+
+    fn truncate_to_max_length(&mut self) {
+        use unicode_segmentation::UnicodeSegmentation;
+        if let Some((index, _)) = self.value.grapheme_indices(true).nth(self.max_length as usize) {
+            self.value.truncate(index);
+        }
+    }
+
+    pub fn generate_sprites(&self, item: &GuiLayoutItem, sprites: &mut Vec<GuiSprite>) {
+        let style = &self.style;
+        let [x, y] = item.position.splat();
+        let [width, height] = item.size.splat();
+
+        sprites.push(GuiSprite {
+            ty: GuiSpriteType::Image(style.texture),
+            position: RectF32 { left: x, top: y, right: x + width, bottom: y + height },
+            texcoord: style.region,
+            color: style.background,
+            clip: RectF32::default(),
+        });
+
+        let text_x = x + TEXT_PADDING;
+        let text_y = y + ((height - style.font_size) * 0.5);
+        let text_bottom = text_y + style.font_size;
+
+        if self.focused && self.has_selection() {
+            let (start, end) = self.selection();
+            let c = style.color;
+            sprites.push(GuiSprite {
+                ty: GuiSpriteType::Image(style.texture),
+                position: RectF32 {
+                    left: text_x + self.offsets[start as usize], top: text_y,
+                    right: text_x + self.offsets[end as usize], bottom: text_bottom,
+                },
+                texcoord: style.region,
+                color: rgba(c.r, c.g, c.b, 80),
+                clip: RectF32::default(),
+            });
+        }
+
+        let color = match self.value.is_empty() {
+            true => rgba(style.color.r, style.color.g, style.color.b, style.color.a / 2),
+            false => style.color,
+        };
+
+        for glyph in self.glyphs.iter() {
+            sprites.push(GuiSprite {
+                ty: GuiSpriteType::Font(style.font),
+                position: glyph.position.translate_into(text_x, text_y),
+                texcoord: glyph.texcoord,
+                color,
+                clip: RectF32::default(),
+            });
+        }
+
+        if self.focused {
+            let caret_x = text_x + self.offsets[self.caret as usize];
+            sprites.push(GuiSprite {
+                ty: GuiSpriteType::Image(style.texture),
+                position: RectF32 { left: caret_x, top: text_y, right: caret_x + CARET_WIDTH, bottom: text_bottom },
+                texcoord: style.region,
+                color: style.color,
+                clip: RectF32::default(),
+            });
+        }
+    }
+
+    pub fn on_events(&mut self, callbacks: &GuiComponentCallbacksValue, output: &mut Vec<RawCallbackValue>, event: GuiInnerEvent) {
+        let callbacks_value = match callbacks {
+            GuiComponentCallbacksValue::TextInput(values) => values,
+            _ => unreachable!("Callback types are enforced by the builder")
+        };
+
+        let value = match event {
+            GuiInnerEvent::Change => callbacks_value.change,
+            GuiInnerEvent::Submit => callbacks_value.submit,
+            _ => u64::MAX,
+        };
+
+        if value != u64::MAX {
+            output.push(value);
+        }
+    }
+
+    pub fn update_style(&mut self, style: &GuiComponentStyle, new_state: GuiStyleState) {
+        let style = match style {
+            GuiComponentStyle::TextInput(text_input_style) => text_input_style,
+            _ => unreachable!("Styles are always valid")
+        };
+
+        let style = match new_state {
+            GuiStyleState::Base => style.base,
+            GuiStyleState::Hovered => style.hovered,
+            GuiStyleState::Selected => style.selected,
+            GuiStyleState::Focused => style.focused,
+        };
+
+        // Note: Like labels, font changes are not supported
+        self.style.color = style.color;
+        self.style.texture = style.texture;
+        self.style.region = style.region;
+        self.style.background = style.background;
+    }
+
+}
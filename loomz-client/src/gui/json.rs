@@ -0,0 +1,359 @@
+//! Gui styles and components described in a json asset. Example:
+//!
+//! ```json
+//! {
+//!   "root_layout": { "type": "VBox", "position": "Center" },
+//!   "toggle_transition": { "duration": 0.2, "easing": "EaseOut", "fade": true, "slide": [0, -40] },
+//!   "tooltip": { "font": "roboto", "size": 18, "color": [220, 200, 180], "frame": "panel", "padding": 6, "delay": 0.5 },
+//!   "styles": [
+//!     { "type": "label", "key": "menu_item", "state": "Base", "font": "bubblegum", "size": 90, "color": [71, 43, 26] },
+//!     { "type": "frame", "key": "panel", "state": "Base", "texture": "gui", "region": [0, 0, 2, 2], "borders": [1, 1, 1, 1], "color": [24, 18, 15] },
+//!     { "type": "transition", "key": "menu_item", "duration": 0.15, "easing": "EaseOut", "scale": 1.05 }
+//!   ],
+//!   "components": [
+//!     {
+//!       "type": "frame", "style": "panel",
+//!       "item": { "width": 500, "height": "50%", "padding": [10, 10, 10, 10] },
+//!       "layout": { "type": "VBox", "position": "Center", "spacing": 5 },
+//!       "children": [
+//!         { "type": "label", "text_key": "menu.exit", "style": "menu_item", "callback": "exit", "tooltip_key": "menu.exit_tooltip", "item": { "width": "1*", "height": 110 } }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Sizes are either a number (pixels), a percentage (`"50%"`) or a weight (`"2*"`). Colors are `[r, g, b]` or `[r, g, b, a]`.
+//! Rects (`region`, `borders`, `margin`, `padding`) are `[left, top, right, bottom]`.
+//!
+//! `callback` is the name of the callback in the callbacks list passed to [Gui::build_from_json](super::Gui::build_from_json).
+//! It is the click callback of labels and images, the submit callback of text inputs and the change callback of the other components.
+//!
+//! Labels and tooltips are either a literal `text` / `tooltip` or a localized string key `text_key` / `tooltip_key`.
+//! The text of a label with `"markup": true` uses inline markup, see the `markup` module.
+use serde_json::Value;
+use loomz_shared::{RectF32, RgbaU8, CommonError, assets_err, rect, rgba, pos};
+use super::{
+    GuiBuilder, GuiStyleBuilder, GuiStyleState, GuiLayoutType, GuiLayoutPosition, GuiSize, GuiImageFit, GuiEasing,
+    GuiLabelCallback, GuiTextInputCallback, GuiCheckboxCallback, GuiSliderCallback, GuiDropdownCallback, GuiImageCallback,
+};
+
+pub(super) fn build_style(style: &mut GuiStyleBuilder, json: &Value) {
+    if let Some(root) = json.get("root_layout") {
+        match (parse_layout_type(&root["type"]), parse_layout_position(&root["position"])) {
+            (Ok(ty), Ok(position)) => style.root_layout(ty, position),
+            (Err(error), _) | (_, Err(error)) => style.push_error(error),
+        }
+    }
+
+    if let Some(transition) = json.get("toggle_transition") {
+        if let Err(error) = build_toggle_transition(style, transition) {
+            style.push_error(error);
+        }
+    }
+
+    let styles = match json["styles"].as_array() {
+        Some(styles) => styles,
+        None => { return; }
+    };
+
+    for value in styles {
+        if let Err(error) = build_style_value(style, value) {
+            style.push_error(error);
+        }
+    }
+
+    // The tooltip frame is one of the styles
+    if let Some(tooltip) = json.get("tooltip") {
+        if let Err(error) = build_tooltip_style(style, tooltip) {
+            style.push_error(error);
+        }
+    }
+}
+
+fn build_tooltip_style(style: &mut GuiStyleBuilder, value: &Value) -> Result<(), CommonError> {
+    let frame_key = style.intern(parse_str(value, "frame")?);
+    let padding = value["padding"].as_f64().unwrap_or(0.0) as f32;
+    style.tooltip(parse_str(value, "font")?, parse_f32(value, "size")?, parse_color(&value["color"])?, frame_key, padding, parse_f32(value, "delay")?);
+    Ok(())
+}
+
+fn build_toggle_transition(style: &mut GuiStyleBuilder, value: &Value) -> Result<(), CommonError> {
+    let slide = match parse_numbers::<2>(&value["slide"]) {
+        Some([x, y]) => pos(x, y),
+        None => pos(0.0, 0.0),
+    };
+
+    style.toggle_transition(parse_f32(value, "duration")?, parse_easing(&value["easing"])?, value["fade"].as_bool().unwrap_or(false), slide);
+    Ok(())
+}
+
+fn build_style_value(style: &mut GuiStyleBuilder, value: &Value) -> Result<(), CommonError> {
+    let key = style.intern(parse_str(value, "key")?);
+
+    // Transitions apply to every state of a style
+    if parse_str(value, "type")? == "transition" {
+        let scale = value["scale"].as_f64().unwrap_or(1.0) as f32;
+        style.transition(key, parse_f32(value, "duration")?, parse_easing(&value["easing"])?, scale);
+        return Ok(());
+    }
+
+    let state = parse_style_state(&value["state"])?;
+
+    match parse_str(value, "type")? {
+        "label" => style.label(key, state, parse_str(value, "font")?, parse_f32(value, "size")?, parse_color(&value["color"])?),
+        "frame" => style.nine_slice_frame(
+            key,
+            state,
+            parse_str(value, "texture")?,
+            parse_rect(&value["region"])?,
+            parse_optional_rect(&value["borders"])?,
+            parse_color(&value["color"])?,
+        ),
+        "image" => style.image(key, state, parse_color(&value["tint"])?),
+        "text_input" => {
+            let frame_key = style.intern(parse_str(value, "frame")?);
+            style.text_input(key, state, parse_str(value, "font")?, parse_f32(value, "size")?, parse_color(&value["color"])?, frame_key);
+        },
+        "dropdown" => {
+            let frame_key = style.intern(parse_str(value, "frame")?);
+            style.dropdown(key, state, parse_str(value, "font")?, parse_f32(value, "size")?, parse_color(&value["color"])?, frame_key);
+        },
+        "checkbox" => style.checkbox(
+            key,
+            state,
+            parse_str(value, "texture")?,
+            parse_rect(&value["region"])?,
+            parse_color(&value["background"])?,
+            parse_color(&value["check"])?,
+        ),
+        "slider" => style.slider(
+            key,
+            state,
+            parse_str(value, "texture")?,
+            parse_rect(&value["region"])?,
+            parse_color(&value["track"])?,
+            parse_color(&value["handle"])?,
+        ),
+        "scroll_view" => {
+            let frame_key = style.intern(parse_str(value, "frame")?);
+            style.scroll_view(key, state, frame_key, parse_color(&value["scrollbar"])?, parse_color(&value["handle"])?);
+        },
+        ty => { return Err(assets_err!("Unknown style type {ty:?}")); }
+    }
+
+    Ok(())
+}
+
+pub(super) fn build(gui: &mut GuiBuilder, json: &Value, callbacks: &[(&str, u64)]) {
+    match json["components"].as_array() {
+        Some(components) => build_components(gui, components, callbacks),
+        None => gui.push_error(assets_err!("Missing json key \"components\"")),
+    }
+}
+
+fn build_components(gui: &mut GuiBuilder, components: &[Value], callbacks: &[(&str, u64)]) {
+    for value in components {
+        if let Err(error) = build_component(gui, value, callbacks) {
+            gui.push_error(error);
+        }
+    }
+}
+
+fn build_component(gui: &mut GuiBuilder, value: &Value, callbacks: &[(&str, u64)]) -> Result<(), CommonError> {
+    if let Some(layout) = value.get("layout") {
+        gui.layout(parse_layout_type(&layout["type"])?, parse_layout_position(&layout["position"])?);
+        gui.layout_spacing(layout["spacing"].as_f64().unwrap_or(0.0) as f32);
+        gui.layout_columns(layout["columns"].as_u64().unwrap_or(1) as u32);
+    }
+
+    if let Some(item) = value.get("item") {
+        gui.layout_item_sized(parse_size(&item["width"])?, parse_size(&item["height"])?);
+        gui.layout_item_margin(parse_optional_rect(&item["margin"])?);
+        gui.layout_item_padding(parse_optional_rect(&item["padding"])?);
+    }
+
+    let callback = match value["callback"].as_str() {
+        Some(name) => match callbacks.iter().find(|(callback_name, _)| *callback_name == name ) {
+            Some((_, id)) => Some(*id),
+            None => { return Err(assets_err!("Unknown callback {name:?}")); }
+        },
+        None => None,
+    };
+
+    if let Some(tooltip) = value["tooltip"].as_str() {
+        gui.tooltip(tooltip);
+    } else if let Some(key) = value["tooltip_key"].as_str() {
+        gui.tooltip_key(key);
+    }
+
+    let style_key = value["style"].as_str().unwrap_or("");
+    let children = value["children"].as_array().map(|children| children.as_slice() ).unwrap_or(&[]);
+
+    match parse_str(value, "type")? {
+        "frame" => {
+            let style_key = gui.intern(style_key);
+            gui.frame(style_key, |gui| build_components(gui, children, callbacks) );
+        },
+        "scroll_view" => {
+            gui.scroll_view(style_key, |gui| build_components(gui, children, callbacks) );
+        },
+        "label" => {
+            if let Some(id) = callback { gui.label_callback(GuiLabelCallback::Click, id); }
+            let markup = value["markup"].as_bool().unwrap_or(false);
+            match (value["text_key"].as_str(), markup) {
+                (Some(key), false) => gui.label_key(key, style_key),
+                (Some(key), true) => gui.rich_label_key(key, style_key),
+                (None, false) => gui.label(parse_str(value, "text")?, style_key),
+                (None, true) => gui.rich_label(parse_str(value, "text")?, style_key),
+            }
+        },
+        "image" => {
+            if let Some(id) = callback { gui.image_callback(GuiImageCallback::Click, id); }
+            let fit = parse_image_fit(&value["fit"])?;
+            match value["atlas"].as_str() {
+                Some(atlas) => gui.atlas_image(atlas, parse_str(value, "sprite")?, fit, style_key),
+                None => gui.image(parse_str(value, "texture")?, parse_rect(&value["region"])?, fit, style_key),
+            }
+        },
+        "text_input" => {
+            if let Some(id) = callback { gui.text_input_callback(GuiTextInputCallback::Submit, id); }
+            let max_length = value["max_length"].as_u64().unwrap_or(32) as u32;
+            gui.text_input(value["value"].as_str().unwrap_or(""), value["placeholder"].as_str().unwrap_or(""), max_length, style_key);
+        },
+        "checkbox" => {
+            if let Some(id) = callback { gui.checkbox_callback(GuiCheckboxCallback::Change, id); }
+            gui.checkbox(value["checked"].as_bool().unwrap_or(false), style_key);
+        },
+        "slider" => {
+            if let Some(id) = callback { gui.slider_callback(GuiSliderCallback::Change, id); }
+            let step = value["step"].as_f64().unwrap_or(0.0) as f32;
+            gui.slider(parse_f32(value, "value")?, parse_f32(value, "min")?, parse_f32(value, "max")?, step, style_key);
+        },
+        "dropdown" => {
+            if let Some(id) = callback { gui.dropdown_callback(GuiDropdownCallback::Change, id); }
+            let options = value["options"].as_array()
+                .map(|options| options.iter().filter_map(|option| option.as_str() ).collect::<Vec<_>>() )
+                .unwrap_or_default();
+            gui.dropdown(&options, value["selected"].as_u64().unwrap_or(0) as u32, style_key);
+        },
+        ty => { return Err(assets_err!("Unknown component type {ty:?}")); }
+    }
+
+    Ok(())
+}
+
+fn parse_str<'a>(value: &'a Value, key: &str) -> Result<&'a str, CommonError> {
+    value[key].as_str().ok_or_else(|| assets_err!("Missing string json key {key:?}") )
+}
+
+fn parse_f32(value: &Value, key: &str) -> Result<f32, CommonError> {
+    value[key].as_f64().map(|v| v as f32 ).ok_or_else(|| assets_err!("Missing number json key {key:?}") )
+}
+
+fn parse_numbers<const N: usize>(value: &Value) -> Option<[f32; N]> {
+    let array = value.as_array()?;
+    let mut out = [0.0; N];
+    for (out, value) in out.iter_mut().zip(array.iter()) {
+        *out = value.as_f64()? as f32;
+    }
+
+    Some(out)
+}
+
+fn parse_rect(value: &Value) -> Result<RectF32, CommonError> {
+    match (value.as_array().map(|array| array.len() ), parse_numbers::<4>(value)) {
+        (Some(4), Some([left, top, right, bottom])) => Ok(rect(left, top, right, bottom)),
+        _ => Err(assets_err!("Rect values must be [left, top, right, bottom], got {value}"))
+    }
+}
+
+fn parse_optional_rect(value: &Value) -> Result<RectF32, CommonError> {
+    match value.is_null() {
+        true => Ok(RectF32::default()),
+        false => parse_rect(value),
+    }
+}
+
+fn parse_color(value: &Value) -> Result<RgbaU8, CommonError> {
+    let len = value.as_array().map(|array| array.len() ).unwrap_or(0);
+    match (len, parse_numbers::<4>(value)) {
+        (3, Some([r, g, b, _])) => Ok(rgba(r as u8, g as u8, b as u8, 255)),
+        (4, Some([r, g, b, a])) => Ok(rgba(r as u8, g as u8, b as u8, a as u8)),
+        _ => Err(assets_err!("Color values must be [r, g, b] or [r, g, b, a], got {value}"))
+    }
+}
+
+fn parse_size(value: &Value) -> Result<GuiSize, CommonError> {
+    if let Some(size) = value.as_f64() {
+        return Ok(GuiSize::Fixed(size as f32));
+    }
+
+    let size = value.as_str().unwrap_or("");
+    let parsed = if let Some(percent) = size.strip_suffix('%') {
+        percent.trim().parse::<f32>().ok().map(|percent| GuiSize::Percent(percent / 100.0) )
+    } else if let Some(weight) = size.strip_suffix('*') {
+        match weight.trim() {
+            "" => Some(GuiSize::Weight(1.0)),
+            weight => weight.parse::<f32>().ok().map(GuiSize::Weight),
+        }
+    } else {
+        None
+    };
+
+    parsed.ok_or_else(|| assets_err!("Sizes must be a number, a percentage (\"50%\") or a weight (\"2*\"), got {value}") )
+}
+
+fn parse_style_state(value: &Value) -> Result<GuiStyleState, CommonError> {
+    match value.as_str().unwrap_or("Base") {
+        "Base" => Ok(GuiStyleState::Base),
+        "Hovered" => Ok(GuiStyleState::Hovered),
+        "Selected" => Ok(GuiStyleState::Selected),
+        "Focused" => Ok(GuiStyleState::Focused),
+        state => Err(assets_err!("Unknown style state {state:?}"))
+    }
+}
+
+fn parse_layout_type(value: &Value) -> Result<GuiLayoutType, CommonError> {
+    match value.as_str().unwrap_or("VBox") {
+        "VBox" => Ok(GuiLayoutType::VBox),
+        "HBox" => Ok(GuiLayoutType::HBox),
+        "Grid" => Ok(GuiLayoutType::Grid),
+        ty => Err(assets_err!("Unknown layout type {ty:?}"))
+    }
+}
+
+fn parse_layout_position(value: &Value) -> Result<GuiLayoutPosition, CommonError> {
+    use GuiLayoutPosition::*;
+    match value.as_str().unwrap_or("Center") {
+        "TopLeft" => Ok(TopLeft),
+        "Top" => Ok(Top),
+        "TopRight" => Ok(TopRight),
+        "Left" => Ok(Left),
+        "Center" => Ok(Center),
+        "Right" => Ok(Right),
+        "BottomLeft" => Ok(BottomLeft),
+        "Bottom" => Ok(Bottom),
+        "BottomRight" => Ok(BottomRight),
+        position => Err(assets_err!("Unknown layout position {position:?}"))
+    }
+}
+
+fn parse_image_fit(value: &Value) -> Result<GuiImageFit, CommonError> {
+    match value.as_str().unwrap_or("Fit") {
+        "Fit" => Ok(GuiImageFit::Fit),
+        "Fill" => Ok(GuiImageFit::Fill),
+        "Stretch" => Ok(GuiImageFit::Stretch),
+        fit => Err(assets_err!("Unknown image fit {fit:?}"))
+    }
+}
+
+fn parse_easing(value: &Value) -> Result<GuiEasing, CommonError> {
+    match value.as_str().unwrap_or("Linear") {
+        "Linear" => Ok(GuiEasing::Linear),
+        "EaseIn" => Ok(GuiEasing::EaseIn),
+        "EaseOut" => Ok(GuiEasing::EaseOut),
+        "EaseInOut" => Ok(GuiEasing::EaseInOut),
+        easing => Err(assets_err!("Unknown easing {easing:?}"))
+    }
+}
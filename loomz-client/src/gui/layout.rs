@@ -1,18 +1,52 @@
 mod compute;
 pub use compute::compute;
 
-use loomz_shared::base_types::{PositionF32, SizeF32};
+use loomz_shared::base_types::{PositionF32, SizeF32, RectF32};
 
-#[derive(Copy, Clone)]
+/// Anchor of the children of a layout in the layout view
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum GuiLayoutPosition {
     TopLeft,
+    Top,
+    TopRight,
+    Left,
     Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum GuiLayoutType {
     VBox,
     HBox,
+    /// Items are placed left to right in rows of `columns` cells. Each cell has the size of the biggest item.
+    Grid,
+}
+
+/// Size of a layout item on one axis
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GuiSize {
+    /// Size in pixels, multiplied by the scale of the gui
+    Fixed(f32),
+    /// Fraction of the layout view (`1.0` is the whole view)
+    Percent(f32),
+    /// Share of the space left in the layout after the fixed and percent items are placed.
+    /// On the cross axis of a box layout, the item is stretched to the size of the layout.
+    Weight(f32),
+}
+
+/// Flexible items of a layout, accumulated by the builder.
+/// For grids, `weight` and `cross_percent` are not zero if an item has a flexible width or height.
+#[derive(Copy, Clone, Default, Debug)]
+pub(super) struct GuiLayoutFlex {
+    /// Sum of the percentages of the items on the main axis (height for VBox, width for HBox)
+    pub percent: f32,
+    /// Sum of the weights of the items on the main axis
+    pub weight: f32,
+    /// Largest percentage of the items on the cross axis. Weighted items count as `1.0`
+    pub cross_percent: f32,
 }
 
 #[derive(Copy, Clone)]
@@ -20,15 +54,139 @@ pub(super) struct GuiLayout {
     pub ty: GuiLayoutType,
     pub position: GuiLayoutPosition,
     pub children_count: u32,
+    /// Size taken by the fixed size items, including margins and spacing
     pub width: f32,
     pub height: f32,
+    /// Space between two items
+    pub spacing: f32,
+    /// Number of columns of a `Grid` layout
+    pub columns: u32,
+    /// Size of the cells of a `Grid` layout
+    pub cell: SizeF32,
+    pub flex: GuiLayoutFlex,
 }
 
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone)]
 pub(super) struct GuiLayoutItem {
     pub has_layout: bool,
     pub position: PositionF32,
+    /// Computed size of the item
     pub size: SizeF32,
+    pub width: GuiSize,
+    pub height: GuiSize,
+    /// Space around the item. Each field is the width of the margin on that side.
+    pub margin: RectF32,
+    /// Space between the item and its children. Each field is the width of the padding on that side.
+    pub padding: RectF32,
+}
+
+impl GuiLayoutItem {
+
+    pub fn fixed(width: f32, height: f32) -> Self {
+        GuiLayoutItem {
+            size: SizeF32 { width, height },
+            width: GuiSize::Fixed(width),
+            height: GuiSize::Fixed(height),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the area available to the children of the item
+    pub fn content_view(&self) -> RectF32 {
+        let p = self.padding;
+        RectF32 {
+            left: self.position.x + p.left,
+            top: self.position.y + p.top,
+            right: f32::max(self.position.x + p.left, self.position.x + self.size.width - p.right),
+            bottom: f32::max(self.position.y + p.top, self.position.y + self.size.height - p.bottom),
+        }
+    }
+
+}
+
+impl GuiLayout {
+
+    /// Adds an item to the layout and accumulates the size taken by the fixed size items
+    pub fn push_item(&mut self, item: &GuiLayoutItem) {
+        let m = item.margin;
+        let width = item.width.fixed() + m.left + m.right;
+        let height = item.height.fixed() + m.top + m.bottom;
+        let spacing = match self.children_count > 0 {
+            true => self.spacing,
+            false => 0.0
+        };
+
+        self.children_count += 1;
+
+        match self.ty {
+            GuiLayoutType::VBox => {
+                self.width = f32::max(self.width, width);
+                self.height += height + spacing;
+                self.flex.push(item.height, item.width);
+            },
+            GuiLayoutType::HBox => {
+                self.width += width + spacing;
+                self.height = f32::max(self.height, height);
+                self.flex.push(item.width, item.height);
+            },
+            GuiLayoutType::Grid => {
+                let cell = &mut self.cell;
+                cell.width = f32::max(cell.width, width);
+                cell.height = f32::max(cell.height, height);
+
+                let columns = u32::min(self.children_count, self.columns) as f32;
+                let rows = self.children_count.div_ceil(self.columns) as f32;
+                self.width = (cell.width * columns) + (self.spacing * (columns - 1.0));
+                self.height = (cell.height * rows) + (self.spacing * (rows - 1.0));
+
+                if !matches!(item.width, GuiSize::Fixed(_)) {
+                    self.flex.weight = 1.0;
+                }
+                if !matches!(item.height, GuiSize::Fixed(_)) {
+                    self.flex.cross_percent = 1.0;
+                }
+            }
+        }
+    }
+
+}
+
+impl GuiLayoutFlex {
+
+    /// Accumulates the flexible sizes of an item in a box layout
+    fn push(&mut self, main: GuiSize, cross: GuiSize) {
+        match main {
+            GuiSize::Fixed(_) => {},
+            GuiSize::Percent(percent) => { self.percent += percent; },
+            GuiSize::Weight(weight) => { self.weight += weight; },
+        }
+
+        match cross {
+            GuiSize::Fixed(_) => {},
+            GuiSize::Percent(percent) => { self.cross_percent = f32::max(self.cross_percent, percent); },
+            GuiSize::Weight(_) => { self.cross_percent = 1.0; },
+        }
+    }
+
+}
+
+impl GuiSize {
+
+    pub fn fixed(&self) -> f32 {
+        match self {
+            GuiSize::Fixed(value) => *value,
+            _ => 0.0,
+        }
+    }
+
+    /// Multiplies fixed sizes by `scale`. Flexible sizes are relative to the layout and are not changed.
+    pub fn scaled(self, scale: f32) -> Self {
+        match self {
+            GuiSize::Fixed(value) => GuiSize::Fixed(value * scale),
+            size => size,
+        }
+    }
+
 }
 
 impl Default for GuiLayout {
@@ -39,6 +197,24 @@ impl Default for GuiLayout {
             children_count: 0,
             width: 0.0,
             height: 0.0,
+            spacing: 0.0,
+            columns: 1,
+            cell: SizeF32::default(),
+            flex: GuiLayoutFlex::default(),
+        }
+    }
+}
+
+impl Default for GuiLayoutItem {
+    fn default() -> Self {
+        GuiLayoutItem {
+            has_layout: false,
+            position: PositionF32::default(),
+            size: SizeF32::default(),
+            width: GuiSize::Fixed(0.0),
+            height: GuiSize::Fixed(0.0),
+            margin: RectF32::default(),
+            padding: RectF32::default(),
         }
     }
 }
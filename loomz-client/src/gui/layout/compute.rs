@@ -1,6 +1,6 @@
 use loomz_shared::RectF32;
 use super::super::Gui;
-use super::{GuiLayout, GuiLayoutType, GuiLayoutPosition, GuiLayoutItem};
+use super::{GuiLayout, GuiLayoutType, GuiLayoutPosition, GuiLayoutItem, GuiSize};
 
 struct LayoutComputeState<'a> {
     layout_items: &'a mut [GuiLayoutItem],
@@ -10,8 +10,8 @@ struct LayoutComputeState<'a> {
     view: RectF32,
 }
 
-/// Compute the position of the items in the layout.
-/// Sizing the layout and the layout item is done a build time in `builder`
+/// Compute the position and the size of the items in the layout.
+/// The size taken by the fixed size items is accumulated a build time in `builder`, flexible sizes are resolved here.
 pub fn compute(gui: &mut Gui) {
     // Layouts may be empty if `gui.resize` was called on an uninitialized gui
     let root_children_count = gui.layouts.get(0).map(|layout| layout.children_count ).unwrap_or(0);
@@ -37,72 +37,144 @@ fn compute_layout(state: &mut LayoutComputeState) {
     let layout = state.layouts[layout_index];
 
     match layout.ty {
-        GuiLayoutType::VBox => vbox_layout(state, layout),
-        GuiLayoutType::HBox => hbox_layout(state, layout),
+        GuiLayoutType::VBox => box_layout(state, layout, true),
+        GuiLayoutType::HBox => box_layout(state, layout, false),
+        GuiLayoutType::Grid => grid_layout(state, layout),
     }
 }
 
-fn vbox_layout(state: &mut LayoutComputeState, layout: GuiLayout) {
-    let view = state.view;
-    let view_width = view.width();
-    let view_height = view.height();
-    let mut offset_x = 0.0; 
-    let mut offset_y = 0.0; 
-
-    match layout.position {
-        GuiLayoutPosition::TopLeft => {},
-        GuiLayoutPosition::Center => {
-            offset_x = view.left + ((view_width - layout.width) * 0.5);
-            offset_y = view.top + ((view_height - layout.height) * 0.5);
-        }
+/// Returns the top left corner of a block of size `width`x`height` anchored in `view`
+fn anchor(position: GuiLayoutPosition, view: &RectF32, width: f32, height: f32) -> [f32; 2] {
+    use GuiLayoutPosition::*;
+
+    let x = match position {
+        TopLeft | Left | BottomLeft => view.left,
+        Top | Center | Bottom => view.left + ((view.width() - width) * 0.5),
+        TopRight | Right | BottomRight => view.right - width,
+    };
+
+    let y = match position {
+        TopLeft | Top | TopRight => view.top,
+        Left | Center | Right => view.top + ((view.height() - height) * 0.5),
+        BottomLeft | Bottom | BottomRight => view.bottom - height,
+    };
+
+    [x, y]
+}
+
+/// Resolves the size of an item on one axis. `available` is the size used for percentages and
+/// `weight_unit` is the size of a weight of `1.0`.
+fn resolve_size(size: GuiSize, available: f32, weight_unit: f32) -> f32 {
+    match size {
+        GuiSize::Fixed(value) => value,
+        GuiSize::Percent(percent) => available * percent,
+        GuiSize::Weight(weight) => weight * weight_unit,
     }
+}
+
+fn box_layout(state: &mut LayoutComputeState, layout: GuiLayout, vertical: bool) {
+    let view = state.view;
+    let flex = layout.flex;
+
+    let (view_main, view_cross) = match vertical {
+        true => (view.height(), view.width()),
+        false => (view.width(), view.height()),
+    };
+
+    let (fixed_main, fixed_cross) = match vertical {
+        true => (layout.height, layout.width),
+        false => (layout.width, layout.height),
+    };
+
+    let percent_main = view_main * flex.percent;
+    let free_main = f32::max(0.0, view_main - fixed_main - percent_main);
+    let (weight_unit, weighted_main) = match flex.weight > 0.0 {
+        true => (free_main / flex.weight, free_main),
+        false => (0.0, 0.0),
+    };
+
+    let content_main = fixed_main + percent_main + weighted_main;
+    let content_cross = f32::max(fixed_cross, view_cross * flex.cross_percent);
+
+    let [x, y] = match vertical {
+        true => anchor(layout.position, &view, content_cross, content_main),
+        false => anchor(layout.position, &view, content_main, content_cross),
+    };
+
+    let mut offset = 0.0;
 
     for _ in 0..layout.children_count {
         let item_index = state.item_index as usize;
         let mut item = state.layout_items[item_index];
-
-        item.position.x = offset_x;
-        item.position.y = offset_y;
-        offset_y += item.size.height;
+        let m = item.margin;
+
+        if vertical {
+            let stretch = content_cross - m.left - m.right;
+            item.size.height = resolve_size(item.height, view_main, weight_unit);
+            item.size.width = resolve_size(item.width, view_cross, f32::max(0.0, stretch));
+            item.position.x = x + m.left;
+            item.position.y = y + offset + m.top;
+            offset += m.top + item.size.height + m.bottom + layout.spacing;
+        } else {
+            let stretch = content_cross - m.top - m.bottom;
+            item.size.width = resolve_size(item.width, view_main, weight_unit);
+            item.size.height = resolve_size(item.height, view_cross, f32::max(0.0, stretch));
+            item.position.x = x + offset + m.left;
+            item.position.y = y + m.top;
+            offset += m.left + item.size.width + m.right + layout.spacing;
+        }
 
         state.layout_items[item_index] = item;
         state.item_index += 1;
 
         if item.has_layout {
-            state.view = RectF32::from_position_and_size(item.position, item.size);
+            state.view = item.content_view();
             compute_layout(state);
         }
     }
 }
 
-fn hbox_layout(state: &mut LayoutComputeState, layout: GuiLayout) {
+fn grid_layout(state: &mut LayoutComputeState, layout: GuiLayout) {
     let view = state.view;
-    let view_width = view.width();
-    let view_height = view.height();
-    let mut offset_x = 0.0;
-    let mut offset_y = 0.0;
-
-    match layout.position {
-        GuiLayoutPosition::TopLeft => {},
-        GuiLayoutPosition::Center => {
-            offset_x =  view.left + ((view_width - layout.width) * 0.5);
-            offset_y =  view.top + ((view_height - layout.height) * 0.5);
-        }
+    let flex = layout.flex;
+    let spacing = layout.spacing;
+    let columns = u32::max(1, layout.columns);
+    let rows = layout.children_count.div_ceil(columns);
+    let used_columns = u32::min(columns, layout.children_count);
+
+    // Cells are stretched to fill the view if the items have a flexible size
+    let mut cell = layout.cell;
+    if flex.weight > 0.0 {
+        cell.width = f32::max(0.0, (view.width() - (spacing * (columns - 1) as f32)) / columns as f32);
+    }
+    if flex.cross_percent > 0.0 {
+        cell.height = f32::max(0.0, (view.height() - (spacing * (rows.max(1) - 1) as f32)) / rows.max(1) as f32);
     }
 
-    for _ in 0..layout.children_count {
+    let content_width = (cell.width * used_columns as f32) + (spacing * used_columns.saturating_sub(1) as f32);
+    let content_height = (cell.height * rows as f32) + (spacing * rows.saturating_sub(1) as f32);
+    let [x, y] = anchor(layout.position, &view, content_width, content_height);
+
+    for child_index in 0..layout.children_count {
         let item_index = state.item_index as usize;
         let mut item = state.layout_items[item_index];
+        let m = item.margin;
 
-        item.position.x = offset_x;
-        item.position.y = offset_y;
-        offset_x += item.size.width;
+        let column = (child_index % columns) as f32;
+        let row = (child_index / columns) as f32;
+        let stretch_width = f32::max(0.0, cell.width - m.left - m.right);
+        let stretch_height = f32::max(0.0, cell.height - m.top - m.bottom);
+
+        item.size.width = resolve_size(item.width, cell.width, stretch_width);
+        item.size.height = resolve_size(item.height, cell.height, stretch_height);
+        item.position.x = x + ((cell.width + spacing) * column) + m.left;
+        item.position.y = y + ((cell.height + spacing) * row) + m.top;
 
         state.layout_items[item_index] = item;
         state.item_index += 1;
 
         if item.has_layout {
-            state.view = RectF32::from_position_and_size(item.position, item.size);
+            state.view = item.content_view();
             compute_layout(state);
         }
     }
@@ -112,8 +184,8 @@ fn hbox_layout(state: &mut LayoutComputeState, layout: GuiLayout) {
 mod tests {
     use loomz_shared::{LoomzApi, RectF32, PositionF32, SizeF32, rect, rgb};
     use super::super::super::{Gui, GuiStyleState};
-    use super::super::{GuiLayoutType::VBox, GuiLayoutPosition};
-    
+    use super::super::{GuiLayout, GuiLayoutItem, GuiSize, GuiLayoutType::{self, VBox, HBox, Grid}, GuiLayoutPosition};
+
 
     macro_rules! assert_layout {
         ($layout:expr, $ty:expr, $w:literal, $h:literal, $children_count:literal) => {
@@ -158,7 +230,8 @@ mod tests {
 
         assert!(style_result.is_ok(), "Gui styling failed: {:?}", style_result);
 
-        let build_result = gui.build(&api, &view, |gui| {
+        let strings = crate::localization::Localization::default();
+        let build_result = gui.build(&api, &strings, &view, |gui| {
             gui.layout(VBox, GuiLayoutPosition::Center);
             gui.layout_item(300.0, 300.0);
             gui.frame("frame", |gui| {
@@ -192,4 +265,116 @@ mod tests {
         assert_layout_item!(items[3], 400.0, 550.0, 200.0, 200.0);
     }
 
+    fn new_layout(ty: GuiLayoutType, position: GuiLayoutPosition, spacing: f32, columns: u32) -> GuiLayout {
+        GuiLayout { ty, position, spacing, columns, ..Default::default() }
+    }
+
+    /// Builds a gui where all the items are children of the root layout, accumulating sizes the same way as `GuiBuilder`
+    fn flat_gui(mut root: GuiLayout, items: &[GuiLayoutItem]) -> Gui {
+        let mut gui = Gui {
+            base_view: RectF32 { left: 0.0, top: 0.0, right: 1000.0, bottom: 1000.0 },
+            ..Default::default()
+        };
+
+        for item in items {
+            root.push_item(item);
+            gui.layout_items.push(*item);
+        }
+
+        gui.layouts.push(root);
+        super::compute(&mut gui);
+        gui
+    }
+
+    #[test]
+    fn test_layout_anchors() {
+        use GuiLayoutPosition::*;
+
+        let expected = [
+            (TopLeft, 0.0, 0.0), (Top, 450.0, 0.0), (TopRight, 900.0, 0.0),
+            (Left, 0.0, 450.0), (Center, 450.0, 450.0), (Right, 900.0, 450.0),
+            (BottomLeft, 0.0, 900.0), (Bottom, 450.0, 900.0), (BottomRight, 900.0, 900.0),
+        ];
+
+        for (position, x, y) in expected {
+            let gui = flat_gui(new_layout(VBox, position, 0.0, 1), &[GuiLayoutItem::fixed(100.0, 100.0)]);
+            assert_eq!(gui.layout_items[0].position, PositionF32 { x, y }, "Mismatched position for {position:?}");
+        }
+    }
+
+    #[test]
+    fn test_layout_spacing_and_margins() {
+        let mut item = GuiLayoutItem::fixed(100.0, 50.0);
+        item.margin = rect(10.0, 5.0, 10.0, 5.0);
+
+        let gui = flat_gui(new_layout(VBox, GuiLayoutPosition::TopLeft, 20.0, 1), &[item, item, item]);
+        assert_layout!(gui.layouts[0], VBox, 120.0, 220.0, 3);
+        assert_layout_item!(gui.layout_items[0], 10.0, 5.0, 100.0, 50.0);
+        assert_layout_item!(gui.layout_items[1], 10.0, 85.0, 100.0, 50.0);
+        assert_layout_item!(gui.layout_items[2], 10.0, 165.0, 100.0, 50.0);
+
+        let gui = flat_gui(new_layout(HBox, GuiLayoutPosition::Center, 20.0, 1), &[item, item]);
+        assert_layout!(gui.layouts[0], HBox, 260.0, 60.0, 2);
+        assert_layout_item!(gui.layout_items[0], 380.0, 475.0, 100.0, 50.0);
+        assert_layout_item!(gui.layout_items[1], 520.0, 475.0, 100.0, 50.0);
+    }
+
+    #[test]
+    fn test_layout_flexible_sizes() {
+        let sized = |width, height| GuiLayoutItem { width, height, ..Default::default() };
+        let items = [
+            GuiLayoutItem::fixed(200.0, 50.0),
+            sized(GuiSize::Percent(0.3), GuiSize::Percent(0.5)),
+            sized(GuiSize::Weight(1.0), GuiSize::Fixed(50.0)),
+            sized(GuiSize::Weight(3.0), GuiSize::Weight(1.0)),
+        ];
+
+        let gui = flat_gui(new_layout(HBox, GuiLayoutPosition::TopLeft, 0.0, 1), &items);
+        assert_layout!(gui.layouts[0], HBox, 200.0, 50.0, 4);
+        assert_layout_item!(gui.layout_items[0], 0.0, 0.0, 200.0, 50.0);
+        assert_layout_item!(gui.layout_items[1], 200.0, 0.0, 300.0, 500.0);
+        assert_layout_item!(gui.layout_items[2], 500.0, 0.0, 125.0, 50.0);
+        assert_layout_item!(gui.layout_items[3], 625.0, 0.0, 375.0, 1000.0);
+    }
+
+    #[test]
+    fn test_layout_grid() {
+        let items = [GuiLayoutItem::fixed(100.0, 50.0); 5];
+        let gui = flat_gui(new_layout(Grid, GuiLayoutPosition::Center, 10.0, 3), &items);
+        assert_layout!(gui.layouts[0], Grid, 320.0, 110.0, 5);
+        assert_layout_item!(gui.layout_items[0], 340.0, 445.0, 100.0, 50.0);
+        assert_layout_item!(gui.layout_items[2], 560.0, 445.0, 100.0, 50.0);
+        assert_layout_item!(gui.layout_items[4], 450.0, 505.0, 100.0, 50.0);
+
+        // Flexible items stretch the cells to fill the view
+        let item = GuiLayoutItem { width: GuiSize::Weight(1.0), height: GuiSize::Fixed(50.0), ..Default::default() };
+        let gui = flat_gui(new_layout(Grid, GuiLayoutPosition::TopLeft, 0.0, 4), &[item; 4]);
+        assert_layout_item!(gui.layout_items[1], 250.0, 0.0, 250.0, 50.0);
+        assert_layout_item!(gui.layout_items[3], 750.0, 0.0, 250.0, 50.0);
+    }
+
+    #[test]
+    fn test_layout_padding() {
+        let mut parent = GuiLayoutItem::fixed(400.0, 400.0);
+        parent.has_layout = true;
+        parent.padding = rect(20.0, 30.0, 20.0, 30.0);
+        let child = GuiLayoutItem { width: GuiSize::Weight(1.0), height: GuiSize::Fixed(100.0), ..Default::default() };
+
+        let mut root = new_layout(VBox, GuiLayoutPosition::Center, 0.0, 1);
+        let mut inner = new_layout(VBox, GuiLayoutPosition::Bottom, 0.0, 1);
+        root.push_item(&parent);
+        inner.push_item(&child);
+
+        let mut gui = Gui {
+            base_view: RectF32 { left: 0.0, top: 0.0, right: 1000.0, bottom: 1000.0 },
+            layouts: vec![root, inner],
+            layout_items: vec![parent, child],
+            ..Default::default()
+        };
+        super::compute(&mut gui);
+
+        assert_layout_item!(gui.layout_items[0], 300.0, 300.0, 400.0, 400.0);
+        assert_layout_item!(gui.layout_items[1], 320.0, 570.0, 360.0, 100.0);
+    }
+
 }
@@ -0,0 +1,191 @@
+//! Inline markup of the label texts. A tag changes the text that follows it until its closing tag:
+//!
+//! * `[color=#RRGGBB]` or `[color=#RRGGBBAA]` ... `[/color]`: color of the text
+//! * `[font=name]` ... `[/font]`: font of the text. Used to put icon glyphs in a text.
+//! * `[size=1.5]` ... `[/size]`: font size, relative to the font size of the label style
+//!
+//! Tags can be nested. `[[` is a literal `[`. Markup is only parsed by the rich labels, see `GuiBuilder::rich_label`.
+use loomz_shared::base_types::{RgbaU8, rgba};
+use loomz_shared::assets::MsdfFontId;
+use loomz_shared::{CommonError, assets_err};
+
+/// Text sharing the same font, color and size. `None` uses the value of the label style.
+pub(super) struct GuiMarkupSpan<'a> {
+    pub text: &'a str,
+    pub font: Option<MsdfFontId>,
+    pub color: Option<RgbaU8>,
+    pub size: f32,
+}
+
+#[derive(Default)]
+struct GuiMarkupState {
+    fonts: Vec<MsdfFontId>,
+    colors: Vec<RgbaU8>,
+    sizes: Vec<f32>,
+}
+
+impl GuiMarkupState {
+    fn span<'a>(&self, text: &'a str) -> GuiMarkupSpan<'a> {
+        GuiMarkupSpan {
+            text,
+            font: self.fonts.last().copied(),
+            color: self.colors.last().copied(),
+            size: self.sizes.last().copied().unwrap_or(1.0),
+        }
+    }
+
+    fn open_tag(&mut self, find_font: &impl Fn(&str) -> Option<MsdfFontId>, name: &str, value: &str) -> Result<(), CommonError> {
+        match name {
+            "color" => {
+                let color = parse_color(value).ok_or_else(|| assets_err!("Invalid markup color {value:?}") )?;
+                self.colors.push(color);
+            },
+            "font" => {
+                let font = find_font(value).ok_or_else(|| assets_err!("No font named {value:?} in app") )?;
+                self.fonts.push(font);
+            },
+            "size" => {
+                let size = value.parse::<f32>().ok()
+                    .filter(|size| size.is_finite() && *size > 0.0 )
+                    .ok_or_else(|| assets_err!("Invalid markup size {value:?}") )?;
+                self.sizes.push(size);
+            },
+            _ => { return Err(assets_err!("Unknown markup tag {name:?}")); }
+        }
+
+        Ok(())
+    }
+
+    fn close_tag(&mut self, name: &str) -> Result<(), CommonError> {
+        let closed = match name {
+            "color" => self.colors.pop().is_some(),
+            "font" => self.fonts.pop().is_some(),
+            "size" => self.sizes.pop().is_some(),
+            _ => { return Err(assets_err!("Unknown markup tag {name:?}")); }
+        };
+
+        match closed {
+            true => Ok(()),
+            false => Err(assets_err!("Closing markup tag {name:?} was never opened"))
+        }
+    }
+}
+
+/// Splits `text` into spans of the same style. Returns an empty list if `text` has no markup.
+/// `find_font` returns the font of a `font` tag by name.
+pub(super) fn parse<'a>(text: &'a str, find_font: impl Fn(&str) -> Option<MsdfFontId>) -> Result<Vec<GuiMarkupSpan<'a>>, CommonError> {
+    let mut spans = Vec::new();
+    if !text.contains('[') {
+        return Ok(spans);
+    }
+
+    let mut state = GuiMarkupState::default();
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        if start > 0 {
+            spans.push(state.span(&rest[..start]));
+        }
+
+        rest = &rest[start..];
+        if rest.starts_with("[[") {
+            spans.push(state.span(&rest[..1]));
+            rest = &rest[2..];
+            continue;
+        }
+
+        let end = rest.find(']').ok_or_else(|| assets_err!("Unclosed markup tag in text {text:?}") )?;
+        let tag = &rest[1..end];
+        rest = &rest[(end+1)..];
+
+        match (tag.strip_prefix('/'), tag.split_once('=')) {
+            (Some(name), _) => state.close_tag(name)?,
+            (None, Some((name, value))) => state.open_tag(&find_font, name, value)?,
+            (None, None) => { return Err(assets_err!("Markup tag {tag:?} has no value")); }
+        }
+    }
+
+    if !rest.is_empty() {
+        spans.push(state.span(rest));
+    }
+
+    Ok(spans)
+}
+
+fn parse_color(value: &str) -> Option<RgbaU8> {
+    let hex = value.strip_prefix('#')?;
+    let channel = |index: usize| hex.get(index..(index+2)).and_then(|c| u8::from_str_radix(c, 16).ok() );
+    match hex.len() {
+        6 => Some(rgba(channel(0)?, channel(2)?, channel(4)?, 255)),
+        8 => Some(rgba(channel(0)?, channel(2)?, channel(4)?, channel(6)?)),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod markup_tests {
+    use super::*;
+
+    const ICONS: MsdfFontId = MsdfFontId(7);
+
+    fn find_font(name: &str) -> Option<MsdfFontId> {
+        match name {
+            "icons" => Some(ICONS),
+            _ => None
+        }
+    }
+
+    fn texts<'a>(spans: &[GuiMarkupSpan<'a>]) -> Vec<&'a str> {
+        spans.iter().map(|span| span.text ).collect()
+    }
+
+    fn color(span: &GuiMarkupSpan) -> Option<[u8; 4]> {
+        span.color.map(|color| color.splat() )
+    }
+
+    #[test]
+    fn plain_text() {
+        let spans = parse("Hello world", find_font).unwrap();
+        assert!(spans.is_empty());
+    }
+
+    #[test]
+    fn escapes() {
+        let spans = parse("a [[b] [[[color=#FF0000]c[/color]", find_font).unwrap();
+        assert_eq!(texts(&spans), ["a ", "[", "b] ", "[", "c"]);
+        assert!(spans[..4].iter().all(|span| span.color.is_none() ));
+        assert!(color(&spans[4]) == Some([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn nesting() {
+        let spans = parse("[color=#00FF0080]a[size=2]b[font=icons]c[/font][/size]d[/color]e", find_font).unwrap();
+        assert_eq!(texts(&spans), ["a", "b", "c", "d", "e"]);
+
+        let green = Some([0, 255, 0, 128]);
+        assert!(spans[..4].iter().all(|span| color(span) == green ));
+        assert!(spans[0].size == 1.0 && spans[0].font.is_none());
+        assert!(spans[1].size == 2.0 && spans[1].font.is_none());
+        assert!(spans[2].size == 2.0 && spans[2].font == Some(ICONS));
+        assert!(spans[3].size == 1.0 && spans[3].font.is_none());
+        assert!(spans[4].color.is_none());
+    }
+
+    #[test]
+    fn unclosed_tag() {
+        assert!(parse("a [color=#FFFFFF b", find_font).is_err());
+        assert!(parse("a [/color] b", find_font).is_err());
+    }
+
+    #[test]
+    fn unknown_tag() {
+        assert!(parse("[bold=1]a[/bold]", find_font).is_err());
+        assert!(parse("[font=missing]a[/font]", find_font).is_err());
+        assert!(parse("[color=red]a[/color]", find_font).is_err());
+        assert!(parse("[maps/wip].map", find_font).is_err());
+
+        for size in ["0", "-1.5", "NaN", "inf", "big"] {
+            assert!(parse(&format!("[size={size}]a[/size]"), find_font).is_err());
+        }
+        assert!(parse("[size=0.5]a[/size]", find_font).is_ok());
+    }
+}
@@ -1,16 +1,19 @@
 use fnv::FnvHashMap;
-use loomz_shared::{LoomzApi, RgbaU8, RectF32, assets_err};
-use crate::gui::{Gui, GuiBuilderData, GuiLayoutType, GuiLayoutPosition, components::{GuiComponentTag, GuiLabelStyle, GuiFrameStyle}};
+use loomz_shared::{LoomzApi, RgbaU8, RectF32, PositionF32, CommonError, assets_err};
+use crate::gui::{Gui, GuiBuilderData, GuiLayoutType, GuiLayoutPosition, GuiEasing, GuiToggleTransition, GuiStyleTransition, GuiTooltipStyle, components::{GuiComponentTag, GuiLabelStyle, GuiFrameStyle,
+    GuiTextInputStyle, GuiCheckboxStyle, GuiSliderStyle, GuiDropdownStyle, GuiScrollViewStyle, GuiImageStyle, GuiMenuStyle}};
 
 type StyleKey = (&'static str, GuiComponentTag);
 pub(super) type GuiStyleMap = FnvHashMap<StyleKey, u32>;
 
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub enum GuiStyleState {
     Base,
     Hovered,
-    Selected
+    Selected,
+    /// The component has the keyboard focus. Uses the hovered style unless it is set explicitly.
+    Focused,
 }
 
 #[derive(Copy, Clone)]
@@ -18,18 +21,31 @@ pub(super) struct GuiComponentStyleBase<T: Copy> {
     pub base: T,
     pub hovered: T,
     pub selected: T,
+    pub focused: T,
+    pub custom_focused: bool,
 }
 
 #[derive(Copy, Clone)]
 pub(super) enum GuiComponentStyle {
     Label(GuiComponentStyleBase<GuiLabelStyle>),
-    Frame(GuiComponentStyleBase<GuiFrameStyle>)
+    Frame(GuiComponentStyleBase<GuiFrameStyle>),
+    TextInput(GuiComponentStyleBase<GuiTextInputStyle>),
+    Checkbox(GuiComponentStyleBase<GuiCheckboxStyle>),
+    Slider(GuiComponentStyleBase<GuiSliderStyle>),
+    Dropdown(GuiComponentStyleBase<GuiDropdownStyle>),
+    ScrollView(GuiComponentStyleBase<GuiScrollViewStyle>),
+    Image(GuiComponentStyleBase<GuiImageStyle>),
+    Menu(GuiComponentStyleBase<GuiMenuStyle>),
 }
 
 pub struct GuiStyleBuilder<'a> {
     api: &'a LoomzApi,
     builder_data: &'a mut GuiBuilderData,
-    styles: &'a mut Vec<GuiComponentStyle>
+    styles: &'a mut Vec<GuiComponentStyle>,
+    tooltip_style: &'a mut Option<GuiTooltipStyle>,
+    style_transitions: &'a mut Vec<GuiStyleTransition>,
+    toggle_transition: &'a mut GuiToggleTransition,
+    scale: f32,
 }
 
 impl<'a> GuiStyleBuilder<'a> {
@@ -41,6 +57,10 @@ impl<'a> GuiStyleBuilder<'a> {
             api,
             builder_data: &mut gui.builder_data,
             styles: &mut gui.styles,
+            tooltip_style: &mut gui.tooltip_style,
+            style_transitions: &mut gui.style_transitions,
+            toggle_transition: &mut gui.toggle_transition,
+            scale: gui.scale,
         }
     }
 
@@ -48,9 +68,20 @@ impl<'a> GuiStyleBuilder<'a> {
         let data = &mut gui.builder_data;
         data.styles.clear();
         gui.styles.clear();
+        gui.style_transitions.clear();
+        gui.toggle_transition = GuiToggleTransition::default();
+        gui.tooltip_style = None;
         data.root_layout_type = GuiLayoutType::VBox;
     }
 
+    pub(super) fn push_error(&mut self, error: CommonError) {
+        self.builder_data.errors.push(error);
+    }
+
+    pub(super) fn intern(&mut self, key: &str) -> &'static str {
+        self.builder_data.intern(key)
+    }
+
     /// Sets the layout of the root elements in the gui
     pub fn root_layout(&mut self, ty: GuiLayoutType, pos: GuiLayoutPosition) {
         self.builder_data.root_layout_type = ty;
@@ -75,7 +106,7 @@ impl<'a> GuiStyleBuilder<'a> {
 
         let label_style_value = GuiLabelStyle {
             font,
-            font_size,
+            font_size: font_size * self.scale,
             color,
         };
 
@@ -94,6 +125,8 @@ impl<'a> GuiStyleBuilder<'a> {
                 base: label_style_value,
                 hovered: label_style_value,
                 selected: label_style_value,
+                focused: label_style_value,
+                custom_focused: false,
             }))
         }
     }
@@ -105,6 +138,20 @@ impl<'a> GuiStyleBuilder<'a> {
         texture_key: &str,
         region: RectF32,
         color: RgbaU8
+    ) {
+        self.nine_slice_frame(style_key, state, texture_key, region, RectF32::default(), color);
+    }
+
+    /// Frame style where the corners of `region` keep their size and the edges / center are stretched.
+    /// `borders` are the insets of each side of `region` in texels.
+    pub fn nine_slice_frame(
+        &mut self,
+        style_key: &'static str,
+        state: GuiStyleState,
+        texture_key: &str,
+        region: RectF32,
+        borders: RectF32,
+        color: RgbaU8
     ) {
         let texture = match self.api.assets_ref().texture_id_by_name(texture_key) {
             Some(texture) => texture,
@@ -117,6 +164,8 @@ impl<'a> GuiStyleBuilder<'a> {
         let frame_style_value = GuiFrameStyle {
             texture,
             region,
+            borders,
+            border_scale: self.scale,
             color,
         };
 
@@ -135,18 +184,452 @@ impl<'a> GuiStyleBuilder<'a> {
                 base: frame_style_value,
                 hovered: frame_style_value,
                 selected: frame_style_value,
+                focused: frame_style_value,
+                custom_focused: false,
+            }))
+        }
+    }
+
+    /// Text input style. The background of the input uses the frame style `frame_key` of the same state.
+    /// The frame style must be defined before the text input style.
+    pub fn text_input(
+        &mut self,
+        style_key: &'static str,
+        state: GuiStyleState,
+        font_key: &str,
+        font_size: f32,
+        color: RgbaU8,
+        frame_key: &'static str,
+    ) {
+        let font = match self.api.assets_ref().font_id_by_name(font_key) {
+            Some(font) => font,
+            None => {
+                self.builder_data.errors.push(assets_err!("No font named {:?} in app", font_key));
+                return;
+            }
+        };
+
+        let frame = match self.frame_style(frame_key, state) {
+            Some(frame) => frame,
+            None => { return; }
+        };
+
+        let text_input_style_value = GuiTextInputStyle {
+            font,
+            font_size: font_size * self.scale,
+            color,
+            texture: frame.texture,
+            region: frame.region,
+            background: frame.color,
+        };
+
+        let style_key = (style_key, GuiComponentTag::TextInput);
+
+        if let Some(index) = self.builder_data.styles.get(&style_key) {
+            let style_index = *index as usize;
+            match &mut self.styles[style_index] {
+                GuiComponentStyle::TextInput(text_input_style) => update_style(state, text_input_style, text_input_style_value),
+                _ => unreachable!("Style type is enforced by the code")
+            };
+        } else {
+            let style_index = self.styles.len();
+            self.builder_data.styles.insert(style_key, style_index as u32);
+            self.styles.push(GuiComponentStyle::TextInput(GuiComponentStyleBase {
+                base: text_input_style_value,
+                hovered: text_input_style_value,
+                selected: text_input_style_value,
+                focused: text_input_style_value,
+                custom_focused: false,
+            }))
+        }
+    }
+
+    pub fn checkbox(
+        &mut self,
+        style_key: &'static str,
+        state: GuiStyleState,
+        texture_key: &str,
+        region: RectF32,
+        background: RgbaU8,
+        check: RgbaU8,
+    ) {
+        let texture = match self.api.assets_ref().texture_id_by_name(texture_key) {
+            Some(texture) => texture,
+            None => {
+                self.builder_data.errors.push(assets_err!("No texture named {:?} in app", texture_key));
+                return;
+            }
+        };
+
+        let checkbox_style_value = GuiCheckboxStyle {
+            texture,
+            region,
+            background,
+            check,
+        };
+
+        let style_key = (style_key, GuiComponentTag::Checkbox);
+
+        if let Some(index) = self.builder_data.styles.get(&style_key) {
+            let style_index = *index as usize;
+            match &mut self.styles[style_index] {
+                GuiComponentStyle::Checkbox(checkbox_style) => update_style(state, checkbox_style, checkbox_style_value),
+                _ => unreachable!("Style type is enforced by the code")
+            };
+        } else {
+            let style_index = self.styles.len();
+            self.builder_data.styles.insert(style_key, style_index as u32);
+            self.styles.push(GuiComponentStyle::Checkbox(GuiComponentStyleBase {
+                base: checkbox_style_value,
+                hovered: checkbox_style_value,
+                selected: checkbox_style_value,
+                focused: checkbox_style_value,
+                custom_focused: false,
             }))
         }
     }
 
+    /// Slider style. `track` is the color of the slider bar and `handle` the color of the handle and of the filled part of the bar.
+    pub fn slider(
+        &mut self,
+        style_key: &'static str,
+        state: GuiStyleState,
+        texture_key: &str,
+        region: RectF32,
+        track: RgbaU8,
+        handle: RgbaU8,
+    ) {
+        let texture = match self.api.assets_ref().texture_id_by_name(texture_key) {
+            Some(texture) => texture,
+            None => {
+                self.builder_data.errors.push(assets_err!("No texture named {:?} in app", texture_key));
+                return;
+            }
+        };
+
+        let slider_style_value = GuiSliderStyle {
+            texture,
+            region,
+            track,
+            handle,
+        };
+
+        let style_key = (style_key, GuiComponentTag::Slider);
+
+        if let Some(index) = self.builder_data.styles.get(&style_key) {
+            let style_index = *index as usize;
+            match &mut self.styles[style_index] {
+                GuiComponentStyle::Slider(slider_style) => update_style(state, slider_style, slider_style_value),
+                _ => unreachable!("Style type is enforced by the code")
+            };
+        } else {
+            let style_index = self.styles.len();
+            self.builder_data.styles.insert(style_key, style_index as u32);
+            self.styles.push(GuiComponentStyle::Slider(GuiComponentStyleBase {
+                base: slider_style_value,
+                hovered: slider_style_value,
+                selected: slider_style_value,
+                focused: slider_style_value,
+                custom_focused: false,
+            }))
+        }
+    }
+
+    /// Dropdown style. Like text inputs, the background uses the frame style `frame_key` of the same state.
+    /// The hovered option of an expanded dropdown uses the color of the hovered frame style.
+    pub fn dropdown(
+        &mut self,
+        style_key: &'static str,
+        state: GuiStyleState,
+        font_key: &str,
+        font_size: f32,
+        color: RgbaU8,
+        frame_key: &'static str,
+    ) {
+        let font = match self.api.assets_ref().font_id_by_name(font_key) {
+            Some(font) => font,
+            None => {
+                self.builder_data.errors.push(assets_err!("No font named {:?} in app", font_key));
+                return;
+            }
+        };
+
+        let frame = match self.frame_style(frame_key, state) {
+            Some(frame) => frame,
+            None => { return; }
+        };
+
+        let highlight = match self.frame_style(frame_key, GuiStyleState::Hovered) {
+            Some(frame) => frame.color,
+            None => { return; }
+        };
+
+        let dropdown_style_value = GuiDropdownStyle {
+            font,
+            font_size: font_size * self.scale,
+            color,
+            texture: frame.texture,
+            region: frame.region,
+            background: frame.color,
+            highlight,
+        };
+
+        let style_key = (style_key, GuiComponentTag::Dropdown);
+
+        if let Some(index) = self.builder_data.styles.get(&style_key) {
+            let style_index = *index as usize;
+            match &mut self.styles[style_index] {
+                GuiComponentStyle::Dropdown(dropdown_style) => update_style(state, dropdown_style, dropdown_style_value),
+                _ => unreachable!("Style type is enforced by the code")
+            };
+        } else {
+            let style_index = self.styles.len();
+            self.builder_data.styles.insert(style_key, style_index as u32);
+            self.styles.push(GuiComponentStyle::Dropdown(GuiComponentStyleBase {
+                base: dropdown_style_value,
+                hovered: dropdown_style_value,
+                selected: dropdown_style_value,
+                focused: dropdown_style_value,
+                custom_focused: false,
+            }))
+        }
+    }
+
+    /// Menu style. Like dropdowns, the background uses the frame style `frame_key` of the same state and
+    /// the hovered item of the popup uses the color of the hovered frame style.
+    pub fn menu(
+        &mut self,
+        style_key: &'static str,
+        state: GuiStyleState,
+        font_key: &str,
+        font_size: f32,
+        color: RgbaU8,
+        frame_key: &'static str,
+    ) {
+        let font = match self.api.assets_ref().font_id_by_name(font_key) {
+            Some(font) => font,
+            None => {
+                self.builder_data.errors.push(assets_err!("No font named {:?} in app", font_key));
+                return;
+            }
+        };
+
+        let frame = match self.frame_style(frame_key, state) {
+            Some(frame) => frame,
+            None => { return; }
+        };
+
+        let highlight = match self.frame_style(frame_key, GuiStyleState::Hovered) {
+            Some(frame) => frame.color,
+            None => { return; }
+        };
+
+        let menu_style_value = GuiMenuStyle {
+            font,
+            font_size: font_size * self.scale,
+            color,
+            texture: frame.texture,
+            region: frame.region,
+            background: frame.color,
+            highlight,
+        };
+
+        let style_key = (style_key, GuiComponentTag::Menu);
+
+        if let Some(index) = self.builder_data.styles.get(&style_key) {
+            let style_index = *index as usize;
+            match &mut self.styles[style_index] {
+                GuiComponentStyle::Menu(menu_style) => update_style(state, menu_style, menu_style_value),
+                _ => unreachable!("Style type is enforced by the code")
+            };
+        } else {
+            let style_index = self.styles.len();
+            self.builder_data.styles.insert(style_key, style_index as u32);
+            self.styles.push(GuiComponentStyle::Menu(GuiComponentStyleBase {
+                base: menu_style_value,
+                hovered: menu_style_value,
+                selected: menu_style_value,
+                focused: menu_style_value,
+                custom_focused: false,
+            }))
+        }
+    }
+
+    /// Scroll view style. The background of the view uses the frame style `frame_key` of the same state.
+    pub fn scroll_view(
+        &mut self,
+        style_key: &'static str,
+        state: GuiStyleState,
+        frame_key: &'static str,
+        scrollbar: RgbaU8,
+        handle: RgbaU8,
+    ) {
+        let frame = match self.frame_style(frame_key, state) {
+            Some(frame) => frame,
+            None => { return; }
+        };
+
+        let scroll_view_style_value = GuiScrollViewStyle {
+            texture: frame.texture,
+            region: frame.region,
+            background: frame.color,
+            scrollbar,
+            handle,
+        };
+
+        let style_key = (style_key, GuiComponentTag::ScrollView);
+
+        if let Some(index) = self.builder_data.styles.get(&style_key) {
+            let style_index = *index as usize;
+            match &mut self.styles[style_index] {
+                GuiComponentStyle::ScrollView(scroll_view_style) => update_style(state, scroll_view_style, scroll_view_style_value),
+                _ => unreachable!("Style type is enforced by the code")
+            };
+        } else {
+            let style_index = self.styles.len();
+            self.builder_data.styles.insert(style_key, style_index as u32);
+            self.styles.push(GuiComponentStyle::ScrollView(GuiComponentStyleBase {
+                base: scroll_view_style_value,
+                hovered: scroll_view_style_value,
+                selected: scroll_view_style_value,
+                focused: scroll_view_style_value,
+                custom_focused: false,
+            }))
+        }
+    }
+
+    /// Image style. `tint` is multiplied with the image color.
+    pub fn image(
+        &mut self,
+        style_key: &'static str,
+        state: GuiStyleState,
+        tint: RgbaU8,
+    ) {
+        let image_style_value = GuiImageStyle {
+            tint,
+        };
+
+        let style_key = (style_key, GuiComponentTag::Image);
+
+        if let Some(index) = self.builder_data.styles.get(&style_key) {
+            let style_index = *index as usize;
+            match &mut self.styles[style_index] {
+                GuiComponentStyle::Image(image_style) => update_style(state, image_style, image_style_value),
+                _ => unreachable!("Style type is enforced by the code")
+            };
+        } else {
+            let style_index = self.styles.len();
+            self.builder_data.styles.insert(style_key, style_index as u32);
+            self.styles.push(GuiComponentStyle::Image(GuiComponentStyleBase {
+                base: image_style_value,
+                hovered: image_style_value,
+                selected: image_style_value,
+                focused: image_style_value,
+                custom_focused: false,
+            }))
+        }
+    }
+
+    /// Animates the style changes of the components using `style_key`. Colors are interpolated over `duration` seconds
+    /// and the components are scaled by `scale` when they are not in the `Base` state.
+    /// Must be called after the styles of `style_key` are defined.
+    pub fn transition(&mut self, style_key: &'static str, duration: f32, easing: GuiEasing, scale: f32) {
+        let transition = GuiStyleTransition { duration, easing, scale };
+        let mut found = false;
+
+        for (&(key, _), &index) in self.builder_data.styles.iter() {
+            if key != style_key {
+                continue;
+            }
+
+            let index = index as usize;
+            if self.style_transitions.len() <= index {
+                self.style_transitions.resize(index + 1, GuiStyleTransition::default());
+            }
+
+            self.style_transitions[index] = transition;
+            found = true;
+        }
+
+        if !found {
+            self.builder_data.errors.push(assets_err!("No style with key {:?} in style builder", style_key));
+        }
+    }
+
+    /// Animates the gui when it is shown or hidden. The gui slides from `slide` pixels away when it is shown.
+    pub fn toggle_transition(&mut self, duration: f32, easing: GuiEasing, fade: bool, slide: PositionF32) {
+        *self.toggle_transition = GuiToggleTransition { duration, easing, fade, slide: slide * self.scale };
+    }
+
+    /// Style of the tooltips of the gui. Tooltips are shown after a component is hovered for `delay` seconds,
+    /// using the base style of the frame `frame_key` as background.
+    pub fn tooltip(
+        &mut self,
+        font_key: &str,
+        font_size: f32,
+        color: RgbaU8,
+        frame_key: &'static str,
+        padding: f32,
+        delay: f32,
+    ) {
+        let font = match self.api.assets_ref().font_id_by_name(font_key) {
+            Some(font) => font,
+            None => {
+                self.builder_data.errors.push(assets_err!("No font named {:?} in app", font_key));
+                return;
+            }
+        };
+
+        let frame = match self.frame_style(frame_key, GuiStyleState::Base) {
+            Some(frame) => frame,
+            None => { return; }
+        };
+
+        *self.tooltip_style = Some(GuiTooltipStyle {
+            label: GuiLabelStyle { font, font_size: font_size * self.scale, color },
+            frame,
+            padding: padding * self.scale,
+            delay,
+        });
+    }
+
+    fn frame_style(&mut self, frame_key: &'static str, state: GuiStyleState) -> Option<GuiFrameStyle> {
+        match self.builder_data.styles.get(&(frame_key, GuiComponentTag::Frame)) {
+            Some(index) => match &self.styles[*index as usize] {
+                GuiComponentStyle::Frame(frame_style) => Some(get_style(state, frame_style)),
+                _ => unreachable!("Style type is enforced by the code")
+            },
+            None => {
+                self.builder_data.errors.push(assets_err!("No frame style with key {:?} in style builder", frame_key));
+                None
+            }
+        }
+    }
+
 }
 
-fn update_style<T: Copy>(state: GuiStyleState, style: &mut GuiComponentStyleBase<T>, value: T) {
-    let style = match state {
-        GuiStyleState::Base => &mut style.base,
-        GuiStyleState::Hovered => &mut style.hovered,
-        GuiStyleState::Selected => &mut style.selected,
-    };
+fn get_style<T: Copy>(state: GuiStyleState, style: &GuiComponentStyleBase<T>) -> T {
+    match state {
+        GuiStyleState::Base => style.base,
+        GuiStyleState::Hovered => style.hovered,
+        GuiStyleState::Selected => style.selected,
+        GuiStyleState::Focused => style.focused,
+    }
+}
 
-    *style = value;
+fn update_style<T: Copy>(state: GuiStyleState, style: &mut GuiComponentStyleBase<T>, value: T) {
+    match state {
+        GuiStyleState::Base => { style.base = value; },
+        GuiStyleState::Hovered => {
+            style.hovered = value;
+            if !style.custom_focused {
+                style.focused = value;
+            }
+        },
+        GuiStyleState::Selected => { style.selected = value; },
+        GuiStyleState::Focused => {
+            style.focused = value;
+            style.custom_focused = true;
+        },
+    }
 }
\ No newline at end of file
@@ -0,0 +1,106 @@
+use loomz_shared::base_types::{PositionF32, SizeF32, RectF32};
+use loomz_shared::assets::msdf_font::ComputedGlyph;
+use super::{GuiLayoutItem, GuiSprite, GuiSpriteType, components::{GuiLabelStyle, GuiFrameStyle, GuiFrame}};
+
+/// Distance between the cursor and the top left corner of a tooltip
+const CURSOR_OFFSET: PositionF32 = PositionF32 { x: 12.0, y: 18.0 };
+
+#[derive(Copy, Clone)]
+pub(super) struct GuiTooltipStyle {
+    pub label: GuiLabelStyle,
+    pub frame: GuiFrameStyle,
+    /// Space between the frame and the text
+    pub padding: f32,
+    /// Time in seconds a component must be hovered before its tooltip is shown
+    pub delay: f32,
+}
+
+/// Tooltip text of a component. Tooltips are drawn over the gui and are not part of the layout.
+pub(super) struct GuiTooltip {
+    /// Index of the component showing the tooltip
+    pub index: u32,
+    pub glyphs: Box<[ComputedGlyph]>,
+}
+
+/// Hover timer of the tooltips
+#[derive(Copy, Clone)]
+pub(super) struct GuiTooltipState {
+    /// Index of the tooltip of the hovered component in `Gui.tooltips`
+    pub tooltip: u32,
+    pub hover_time: f32,
+    pub shown: bool,
+    /// Cursor position when the tooltip was shown
+    pub position: PositionF32,
+}
+
+impl GuiTooltip {
+
+    fn text_size(&self) -> SizeF32 {
+        let mut size = SizeF32::default();
+        for glyph in self.glyphs.iter() {
+            size.height = f32::max(size.height, glyph.position.height());
+        }
+
+        if let Some(glyph) = self.glyphs.last() {
+            size.width = glyph.position.right;
+        }
+
+        size
+    }
+
+    /// Generates the tooltip sprites next to `cursor`. The tooltip is moved above the cursor or to the left if it would leave `view`.
+    pub fn generate_sprites(&self, style: &GuiTooltipStyle, cursor: PositionF32, view: &RectF32, sprites: &mut Vec<GuiSprite>) {
+        let text_size = self.text_size();
+        let padding = style.padding;
+        let width = text_size.width + (padding * 2.0);
+        let height = text_size.height + (padding * 2.0);
+
+        let mut x = cursor.x + CURSOR_OFFSET.x;
+        if x + width > view.right {
+            x = cursor.x - width;
+        }
+
+        let mut y = cursor.y + CURSOR_OFFSET.y;
+        if y + height > view.bottom {
+            y = cursor.y - height;
+        }
+
+        let mut item = GuiLayoutItem::fixed(width, height);
+        item.position.x = f32::max(view.left, x);
+        item.position.y = f32::max(view.top, y);
+
+        let frame = GuiFrame {
+            texture: style.frame.texture,
+            texcoord: style.frame.region,
+            borders: style.frame.borders,
+            border_scale: style.frame.border_scale,
+            color: style.frame.color,
+        };
+
+        frame.generate_sprites(&item, sprites);
+
+        let text_x = item.position.x + padding;
+        let text_y = item.position.y + padding;
+        for glyph in self.glyphs.iter() {
+            sprites.push(GuiSprite {
+                ty: GuiSpriteType::Font(style.label.font),
+                position: glyph.position.translate_into(text_x, text_y),
+                texcoord: glyph.texcoord,
+                color: style.label.color,
+                clip: RectF32::default(),
+            });
+        }
+    }
+
+}
+
+impl Default for GuiTooltipState {
+    fn default() -> Self {
+        GuiTooltipState {
+            tooltip: u32::MAX,
+            hover_time: 0.0,
+            shown: false,
+            position: PositionF32::default(),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use loomz_shared::base_types::{PositionF32, RgbaU8};
+use loomz_shared::api::GuiSprite;
+use super::{GuiLayoutItem, tooltip::GuiTooltipState};
+
+/// Easing curve of a gui animation
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GuiEasing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+/// Animation played when a gui is shown or hidden with `Gui::toggle`. A `duration` of zero toggles the gui instantly.
+#[derive(Copy, Clone)]
+pub(super) struct GuiToggleTransition {
+    /// Duration in seconds
+    pub duration: f32,
+    pub easing: GuiEasing,
+    /// Fades the gui in and out
+    pub fade: bool,
+    /// Offset of the gui when it is hidden. The gui slides from this offset when shown
+    pub slide: PositionF32,
+}
+
+/// Animation played when the style state of a component changes. A `duration` of zero updates the style instantly.
+#[derive(Copy, Clone)]
+pub(super) struct GuiStyleTransition {
+    /// Duration in seconds
+    pub duration: f32,
+    pub easing: GuiEasing,
+    /// Scale of the component when it is not in the `Base` state
+    pub scale: f32,
+}
+
+/// Color and scale interpolation of a single component
+pub(super) struct GuiComponentTween {
+    pub index: u32,
+    pub elapsed: f32,
+    pub transition: GuiStyleTransition,
+    /// Colors of the component sprites when the tween started
+    pub from_colors: Vec<RgbaU8>,
+    pub from_scale: f32,
+    pub to_scale: f32,
+}
+
+/// Transient animation and timer state of a gui. Not stored with the gui.
+pub(super) struct GuiAnimations {
+    /// How much the gui is shown, from `0.0` (hidden) to `1.0` (shown), before easing
+    pub shown: f32,
+    /// Visibility the gui is animating toward
+    pub toggle_target: Option<bool>,
+    /// Tweens of the components. Tweens that are done are kept as long as the component is scaled.
+    pub tweens: Vec<GuiComponentTween>,
+    pub tooltip: GuiTooltipState,
+}
+
+impl GuiEasing {
+
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = f32::clamp(t, 0.0, 1.0);
+        match self {
+            GuiEasing::Linear => t,
+            GuiEasing::EaseIn => t * t * t,
+            GuiEasing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            GuiEasing::EaseInOut => match t < 0.5 {
+                true => 4.0 * t * t * t,
+                false => 1.0 - ((-2.0 * t + 2.0).powi(3) * 0.5),
+            }
+        }
+    }
+
+}
+
+impl GuiToggleTransition {
+
+    pub fn opacity(&self, shown: f32) -> f32 {
+        match self.fade {
+            true => self.easing.apply(shown),
+            false => 1.0,
+        }
+    }
+
+    pub fn offset(&self, shown: f32) -> PositionF32 {
+        let t = 1.0 - self.easing.apply(shown);
+        PositionF32 { x: self.slide.x * t, y: self.slide.y * t }
+    }
+
+}
+
+impl GuiComponentTween {
+
+    pub fn running(&self) -> bool {
+        self.elapsed < self.transition.duration
+    }
+
+    fn progress(&self) -> f32 {
+        self.transition.easing.apply(self.elapsed / self.transition.duration)
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.from_scale + ((self.to_scale - self.from_scale) * self.progress())
+    }
+
+    /// Interpolates the colors of the component sprites from the colors at the start of the tween.
+    /// Colors are left unchanged if the component generated a different number of sprites since.
+    pub fn apply_colors(&self, sprites: &mut [GuiSprite]) {
+        if !self.running() || sprites.len() != self.from_colors.len() {
+            return;
+        }
+
+        let t = self.progress();
+        for (sprite, from) in sprites.iter_mut().zip(self.from_colors.iter()) {
+            sprite.color = lerp_color(*from, sprite.color, t);
+        }
+    }
+
+    /// Scales the component sprites around the center of the component
+    pub fn apply_scale(&self, item: &GuiLayoutItem, sprites: &mut [GuiSprite]) {
+        let scale = self.scale();
+        if scale == 1.0 {
+            return;
+        }
+
+        let center_x = item.position.x + (item.size.width * 0.5);
+        let center_y = item.position.y + (item.size.height * 0.5);
+        for sprite in sprites.iter_mut() {
+            let p = &mut sprite.position;
+            p.left = center_x + ((p.left - center_x) * scale);
+            p.right = center_x + ((p.right - center_x) * scale);
+            p.top = center_y + ((p.top - center_y) * scale);
+            p.bottom = center_y + ((p.bottom - center_y) * scale);
+        }
+    }
+
+}
+
+impl Default for GuiToggleTransition {
+    fn default() -> Self {
+        GuiToggleTransition {
+            duration: 0.0,
+            easing: GuiEasing::Linear,
+            fade: false,
+            slide: PositionF32::default(),
+        }
+    }
+}
+
+impl Default for GuiStyleTransition {
+    fn default() -> Self {
+        GuiStyleTransition {
+            duration: 0.0,
+            easing: GuiEasing::Linear,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Default for GuiAnimations {
+    fn default() -> Self {
+        GuiAnimations {
+            shown: 1.0,
+            toggle_target: None,
+            tweens: Vec::new(),
+            tooltip: GuiTooltipState::default(),
+        }
+    }
+}
+
+pub(super) fn lerp_color(from: RgbaU8, to: RgbaU8, t: f32) -> RgbaU8 {
+    let lerp = |from: u8, to: u8| (from as f32 + ((to as f32 - from as f32) * t)).round() as u8;
+    RgbaU8 {
+        r: lerp(from.r, to.r),
+        g: lerp(from.g, to.g),
+        b: lerp(from.b, to.b),
+        a: lerp(from.a, to.a),
+    }
+}
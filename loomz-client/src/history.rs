@@ -0,0 +1,319 @@
+//! Undo and redo history of the editor. Every change of the map is a command that can be reverted.
+use std::collections::VecDeque;
+use crate::map::MapActor;
+use crate::terrain::TerrainEdit;
+use crate::LoomzClient;
+
+/// Default number of commands that can be undone
+pub(crate) const EDITOR_HISTORY_DEPTH: usize = 100;
+
+/// Brush strokes started less than this many seconds after the end of the previous stroke are undone together.
+/// The start of a stroke is recorded by `EditorHistory::begin_command`.
+const STROKE_MERGE_TIME: f32 = 0.5;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum MapProperty {
+    Name,
+    Author,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum EditorCommand {
+    /// Cells painted by one or more brush strokes
+    Terrain(TerrainEdit),
+    PlaceActor { index: usize, actor: MapActor },
+    RemoveActor { index: usize, actor: MapActor },
+    PlaceSpawnPoint { index: usize, point: [u32; 2] },
+    RemoveSpawnPoint { index: usize, point: [u32; 2] },
+    Property { property: MapProperty, before: String, after: String },
+}
+
+impl EditorCommand {
+
+    /// Merges `next` into this command if both can be undone as a single command. Returns `next` if they cannot.
+    /// `gap` is the number of seconds between the end of this command and the start of `next`.
+    /// Property changes merge until the history is sealed, when the text input loses the focus.
+    fn merge(&mut self, next: EditorCommand, gap: f32) -> Option<EditorCommand> {
+        match (self, next) {
+            (EditorCommand::Terrain(edit), EditorCommand::Terrain(next)) if gap < STROKE_MERGE_TIME => {
+                edit.merge(next);
+                None
+            },
+            (EditorCommand::Property { property, after, .. }, EditorCommand::Property { property: next_property, after: next_after, .. }) if *property == next_property => {
+                *after = next_after;
+                None
+            },
+            (_, next) => Some(next)
+        }
+    }
+
+}
+
+pub(crate) struct EditorHistory {
+    undo: VecDeque<EditorCommand>,
+    redo: Vec<EditorCommand>,
+    depth: usize,
+    /// Seconds since the last command was pushed
+    since_last: f32,
+    /// Value of `since_last` when the command being recorded started. See `begin_command`.
+    start_gap: Option<f32>,
+    /// If the next command must not be merged into the last command
+    sealed: bool,
+}
+
+impl EditorHistory {
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Sets the number of commands that can be undone. The oldest commands are dropped.
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        while self.undo.len() > depth {
+            self.undo.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.start_gap = None;
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.since_last += delta;
+    }
+
+    /// Records the start of a command that is pushed later, like a brush stroke that lasts several frames.
+    /// The command is merged with the last command if it started soon enough after it.
+    pub fn begin_command(&mut self) {
+        self.start_gap = Some(self.since_last);
+    }
+
+    /// Adds a command that was already applied. Clears the commands that were undone.
+    pub fn push(&mut self, command: EditorCommand) {
+        self.redo.clear();
+
+        let gap = self.start_gap.take().unwrap_or(self.since_last);
+        let sealed = self.sealed;
+        self.since_last = 0.0;
+        self.sealed = false;
+
+        let command = match self.undo.back_mut().filter(|_| !sealed ) {
+            Some(last) => match last.merge(command, gap) {
+                Some(command) => command,
+                None => { return; }
+            },
+            None => command,
+        };
+
+        self.undo.push_back(command);
+        if self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Ends the merging of the last command. The next command is always a new entry.
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    /// Removes the last command to revert it. The command is added to the redo list by the caller once reverted.
+    fn take_undo(&mut self) -> Option<EditorCommand> {
+        let command = self.undo.pop_back()?;
+        self.seal();
+        Some(command)
+    }
+
+    /// Removes the last undone command to apply it again. The command is added back by the caller once applied.
+    fn take_redo(&mut self) -> Option<EditorCommand> {
+        let command = self.redo.pop()?;
+        self.seal();
+        Some(command)
+    }
+
+}
+
+impl LoomzClient {
+
+    /// Reverts the last command of the history. Returns `false` if there is nothing to undo.
+    pub(crate) fn editor_undo(&mut self) -> bool {
+        let Some(command) = self.editor_history.take_undo() else {
+            return false;
+        };
+
+        self.apply_editor_command(&command, true);
+        self.editor_history.redo.push(command);
+        true
+    }
+
+    /// Applies the last command that was undone. Returns `false` if there is nothing to redo.
+    pub(crate) fn editor_redo(&mut self) -> bool {
+        let Some(command) = self.editor_history.take_redo() else {
+            return false;
+        };
+
+        self.apply_editor_command(&command, false);
+        self.editor_history.undo.push_back(command);
+        true
+    }
+
+    fn apply_editor_command(&mut self, command: &EditorCommand, undo: bool) {
+        let map = &mut self.map;
+        match *command {
+            EditorCommand::Terrain(ref edit) => {
+                self.terrain.apply_edit(edit, undo);
+                self.terrain.sync(&self.api);
+            },
+            EditorCommand::PlaceActor { index, actor } | EditorCommand::RemoveActor { index, actor } => {
+                let place = matches!(command, EditorCommand::PlaceActor { .. }) != undo;
+                match place {
+                    true => map.actors.insert(index.min(map.actors.len()), actor),
+                    false if index < map.actors.len() => { map.actors.remove(index); },
+                    false => {}
+                }
+            },
+            EditorCommand::PlaceSpawnPoint { index, point } | EditorCommand::RemoveSpawnPoint { index, point } => {
+                let place = matches!(command, EditorCommand::PlaceSpawnPoint { .. }) != undo;
+                match place {
+                    true => map.spawn_points.insert(index.min(map.spawn_points.len()), point),
+                    false if index < map.spawn_points.len() => { map.spawn_points.remove(index); },
+                    false => {}
+                }
+            },
+            EditorCommand::Property { property, ref before, ref after } => {
+                let value = if undo { before.clone() } else { after.clone() };
+                match property {
+                    MapProperty::Name => { map.name = value; },
+                    MapProperty::Author => { map.author = value; },
+                }
+            },
+        }
+    }
+
+}
+
+impl Default for EditorHistory {
+    fn default() -> Self {
+        EditorHistory {
+            undo: VecDeque::with_capacity(EDITOR_HISTORY_DEPTH),
+            redo: Vec::new(),
+            depth: EDITOR_HISTORY_DEPTH,
+            since_last: f32::INFINITY,
+            start_gap: None,
+            sealed: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+
+    fn stroke() -> EditorCommand {
+        EditorCommand::Terrain(TerrainEdit::default())
+    }
+
+    fn spawn_point(x: u32) -> EditorCommand {
+        EditorCommand::PlaceSpawnPoint { index: 0, point: [x, 0] }
+    }
+
+    fn name(before: &str, after: &str) -> EditorCommand {
+        EditorCommand::Property { property: MapProperty::Name, before: before.to_string(), after: after.to_string() }
+    }
+
+    #[test]
+    fn merge_strokes() {
+        let mut history = EditorHistory::default();
+        history.begin_command();
+        history.push(stroke());
+
+        // Started right after the end of the first stroke, even if the stroke itself is long
+        history.update(0.1);
+        history.begin_command();
+        history.update(2.0);
+        history.push(stroke());
+        assert!(history.undo.len() == 1);
+
+        history.update(STROKE_MERGE_TIME + 0.1);
+        history.begin_command();
+        history.push(stroke());
+        assert!(history.undo.len() == 2);
+
+        history.push(spawn_point(0));
+        history.push(spawn_point(1));
+        assert!(history.undo.len() == 4);
+    }
+
+    #[test]
+    fn merge_properties() {
+        let mut history = EditorHistory::default();
+        history.push(name("", "a"));
+        history.update(10.0);
+        history.push(name("a", "ab"));
+        assert!(history.undo.len() == 1);
+        assert!(matches!(history.undo.back(), Some(EditorCommand::Property { before, after, .. }) if before.is_empty() && after == "ab"));
+
+        // The text input lost the focus
+        history.seal();
+        history.push(name("ab", "abc"));
+        assert!(history.undo.len() == 2);
+    }
+
+    #[test]
+    fn depth() {
+        let mut history = EditorHistory::default();
+        history.set_depth(3);
+        for x in 0..5 {
+            history.push(spawn_point(x));
+        }
+
+        assert!(history.undo.len() == 3);
+        assert!(matches!(history.undo.front(), Some(EditorCommand::PlaceSpawnPoint { point: [2, 0], .. })));
+
+        history.set_depth(1);
+        assert!(history.undo.len() == 1);
+        assert!(matches!(history.undo.front(), Some(EditorCommand::PlaceSpawnPoint { point: [4, 0], .. })));
+    }
+
+    #[test]
+    fn redo_cleared_on_push() {
+        let mut history = EditorHistory::default();
+        history.push(spawn_point(0));
+        history.push(spawn_point(1));
+
+        let command = history.take_undo().unwrap();
+        history.redo.push(command);
+        assert!(history.redo.len() == 1);
+
+        history.push(spawn_point(2));
+        assert!(history.redo.is_empty());
+        assert!(history.take_redo().is_none());
+    }
+
+    #[test]
+    fn seal_after_undo() {
+        let mut history = EditorHistory::default();
+        history.push(name("", "a"));
+        history.push(spawn_point(0));
+
+        let command = history.take_undo().unwrap();
+        history.redo.push(command);
+
+        // The property change before the undone command is not merged with the next change
+        history.push(name("a", "b"));
+        assert!(history.undo.len() == 2);
+
+        history.begin_command();
+        history.push(stroke());
+        let command = history.take_undo().unwrap();
+        history.redo.push(command);
+        let command = history.take_redo().unwrap();
+        history.undo.push_back(command);
+
+        history.begin_command();
+        history.push(stroke());
+        assert!(history.undo.len() == 4);
+    }
+}
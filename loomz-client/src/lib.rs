@@ -2,15 +2,25 @@ mod store;
 mod gui;
 mod animations;
 mod terrain;
+mod map;
+mod history;
+mod localization;
+mod perf;
+mod console;
 mod state;
 
 use animations::{Animations, PawnAnimationType};
+use console::Console;
+
+use gui::GuiInputCapture;
+use localization::Localization;
+use state::EditorState;
 
 use bitflags::bitflags;
 use std::time::Instant;
 use loomz_shared::base_types::PositionF32;
 use loomz_shared::api::{WorldActorId, WorldDebugFlags};
-use loomz_shared::{chain_err, CommonError, CommonErrorType, LoomzApi};
+use loomz_shared::{chain_err, save_err, CommonError, CommonErrorType, LoomzApi};
 
 bitflags! {
     #[derive(Copy, Clone, Default, Debug)]
@@ -40,23 +50,70 @@ struct ClientTiming {
     delta_ms: f64,
 }
 
+/// Layer of the context menus. Drawn over the game guis, but under the debug gui.
+pub(crate) const CONTEXT_GUI_LAYER: i32 = 50;
+
+/// Layer of the debug gui. Drawn over the game guis.
+const DEBUG_GUI_LAYER: i32 = 100;
+
+/// File written by the "Save session" item of the debug menubar
+const SESSION_PATH: &str = "./session.bin";
+
+const DEBUG_SAVE_SESSION: u64 = 1000;
+const DEBUG_LOAD_SESSION: u64 = 1001;
+const DEBUG_EXIT: u64 = 1002;
+const DEBUG_MAIN_GRID: u64 = 1010;
+const DEBUG_SUB_GRID: u64 = 1011;
+const DEBUG_GRID_TYPES: u64 = 1012;
+const DEBUG_PERF_OVERLAY: u64 = 1013;
+const DEBUG_STATE_MAIN_MENU: u64 = 1020;
+const DEBUG_STATE_GAME: u64 = 1021;
+const DEBUG_STATE_EDITOR: u64 = 1022;
+/// The index of the language is added to the callback id
+const DEBUG_LANGUAGE: u64 = 1030;
+const DEBUG_LANGUAGE_MAX: u64 = 1039;
+const DEBUG_UI_SCALE_AUTO: u64 = 1040;
+/// The index of the scale in `UI_SCALES` is added to the callback id
+const DEBUG_UI_SCALE: u64 = 1041;
+
+/// Scales of the guis that can be selected in the debug menubar
+const UI_SCALES: [(f32, &str); 5] = [(1.0, "100%"), (1.25, "125%"), (1.5, "150%"), (2.0, "200%"), (3.0, "300%")];
+
 #[derive(Default)]
 struct DebugState {
     world: WorldDebugFlags,
+    /// Scale of the guis selected by the user. `0.0` uses the scale factor of the window.
+    ui_scale: f32,
+    perf_overlay: bool,
+    /// Seconds since the last refresh of the performance overlay. Not stored.
+    perf_refresh: f32,
 }
 
 pub struct LoomzClient {
     api: LoomzApi,
     timing: ClientTiming,
     animations: Box<Animations>,
+    strings: Box<Localization>,
 
     gui: Box<gui::Gui>,
+    context_gui: Box<gui::Gui>,
     debug_gui: Box<gui::Gui>,
+    perf_gui: Box<gui::Gui>,
+    /// Terrain palette of the editor
+    palette_gui: Box<gui::Gui>,
+    console_gui: Box<gui::Gui>,
+    gui_capture: GuiInputCapture,
     terrain: Box<terrain::Terrain>,
+    /// Metadata and actors of the map. The cells of the map are in `terrain`.
+    map: Box<map::Map>,
+    /// Undo history of the editor. Only the depth of the history is stored.
+    editor_history: Box<history::EditorHistory>,
+    console: Box<Console>,
 
     state: GameState,
     input_flags: GameInputFlags,
-    debug_state: DebugState
+    debug_state: DebugState,
+    editor_state: EditorState,
 }
 
 impl LoomzClient {
@@ -71,20 +128,34 @@ impl LoomzClient {
             api: api.clone(),
             timing,
             animations: Box::default(),
+            strings: Box::default(),
 
             gui: Box::default(),
+            context_gui: Box::default(),
             debug_gui: Box::default(),
+            perf_gui: Box::default(),
+            palette_gui: Box::default(),
+            console_gui: Box::default(),
+            gui_capture: GuiInputCapture::None,
             terrain: Box::default(),
+            map: Box::default(),
+            editor_history: Box::default(),
+            console: Box::default(),
 
             state: GameState::Uninitialized,
             input_flags: GameInputFlags::empty(),
             debug_state: DebugState::default(),
+            editor_state: EditorState::default(),
         }
     }
 
     pub fn init(api: &LoomzApi) -> Result<Self, CommonError> {
         let mut client = Self::build_client(api);
+        client.register_console_commands();
         client.animations.load(api)?;
+        client.strings.load(api)?;
+        client.terrain.load_types(api)?;
+        client.apply_ui_scale();
         client.on_reload()?;
         client.init_editor()?;
         Ok(client)
@@ -99,10 +170,26 @@ impl LoomzClient {
         client.state = reader.read_from_u32();
         client.input_flags = reader.read_from_u32();
         client.debug_state = reader.load();
+        client.editor_state = reader.load();
         client.animations = Box::new(reader.read());
+        client.strings.load(api)?;
+        client.strings.set_language(reader.read_u32() as usize);
         client.gui = Box::new(reader.load());
+        client.context_gui = Box::new(reader.load());
         client.debug_gui = Box::new(reader.load());
+        client.perf_gui = Box::new(reader.load());
+        client.palette_gui = Box::new(reader.load());
+        client.console_gui = Box::new(reader.load());
         client.terrain = Box::new(reader.load());
+        client.terrain.load_types(api)?;
+        client.map = Box::new(reader.load());
+        client.editor_history.set_depth(reader.read_u32() as usize);
+        client.console = Box::new(reader.load());
+        if reader.overflowed() {
+            return Err(save_err!("Stored session is truncated or corrupted"));
+        }
+
+        client.register_console_commands();
 
         client.on_reload()?;
 
@@ -113,14 +200,26 @@ impl LoomzClient {
         writer.write_into_u32(self.state);
         writer.write_into_u32(self.input_flags);
         writer.store(&self.debug_state);
+        writer.store(&self.editor_state);
         writer.write(self.animations.as_ref());
+        writer.write_u32(self.strings.language() as u32);
         writer.store(self.gui.as_ref());
+        writer.store(self.context_gui.as_ref());
         writer.store(self.debug_gui.as_ref());
+        writer.store(self.perf_gui.as_ref());
+        writer.store(self.palette_gui.as_ref());
+        writer.store(self.console_gui.as_ref());
         writer.store(self.terrain.as_ref());
+        writer.store(self.map.as_ref());
+        writer.write_u32(self.editor_history.depth() as u32);
+        writer.store(self.console.as_ref());
     }
 
     pub fn update(&mut self) -> Result<(), CommonError> {
+        let update_start = Instant::now();
         self.update_timing();
+        self.update_ui_scale()?;
+        self.read_gui_inputs();
 
         match self.state {
             GameState::Uninitialized => self.uninitialized()?,
@@ -129,9 +228,20 @@ impl LoomzClient {
             GameState::Editor => self.editor()?,
         }
 
-        self.update_debug_state();
-        self.update_debug_gui();
+        let delta = self.timing.delta_ms as f32;
+        self.gui.update_animations(&self.api, delta);
+        self.context_gui.update_animations(&self.api, delta);
+        self.debug_gui.update_animations(&self.api, delta);
+        self.perf_gui.update_animations(&self.api, delta);
+        self.palette_gui.update_animations(&self.api, delta);
+        self.console_gui.update_animations(&self.api, delta);
+
+        self.update_debug_accelerators();
+        self.update_console()?;
+        self.update_debug_gui()?;
+        self.update_perf_overlay()?;
         self.api.client_update_finished();
+        self.api.perf().set_client_update_time(update_start.elapsed().as_secs_f32() * 1000.0);
 
         Ok(())
     }
@@ -142,29 +252,39 @@ impl LoomzClient {
         self.timing.delta_ms = elapsed.as_secs_f64();
     }
 
-    fn update_debug_state(&mut self) {
-        use loomz_shared::inputs::keys;
+    /// Reads the inputs of the guis from the top layer to the bottom layer
+    fn read_gui_inputs(&mut self) {
+        let mut guis = [&mut self.gui, &mut self.context_gui, &mut self.debug_gui, &mut self.perf_gui, &mut self.palette_gui, &mut self.console_gui];
+        guis.sort_by_key(|gui| std::cmp::Reverse(gui.layer()) );
 
-        if let Some(keystate) = self.api.keys_ref().read_updates() {
-            let mut update_debug = false;
+        let mut capture = GuiInputCapture::None;
+        for gui in guis {
+            capture = gui.read_inputs(&self.api, capture);
+        }
 
-            if keystate.just_pressed(keys::_1) {
-                self.debug_state.world.toggle(WorldDebugFlags::SHOW_MAIN_GRID);
-                update_debug = true;
-            }
-            if keystate.just_pressed(keys::_2) {
-                self.debug_state.world.toggle(WorldDebugFlags::SHOW_SUB_GRID);
-                update_debug = true;
-            }
-            if keystate.just_pressed(keys::_3) {
-                self.debug_state.world.toggle(WorldDebugFlags::SHOW_MAIN_GRID_TYPES);
-                update_debug = true;
-            }
+        self.gui_capture = capture;
+    }
 
-            if update_debug {
-                self.api.world().toggle_debug(self.debug_state.world);
-            }
+    /// Returns `true` if the cursor is over a gui or if a modal gui is shown. Mouse inputs should not reach the game world.
+    pub(crate) fn cursor_over_gui(&self) -> bool {
+        self.gui_capture != GuiInputCapture::None
+    }
+
+    /// Returns `true` if a gui handled `key` this frame, for example Esc closing a dropdown. The game should ignore the key.
+    pub(crate) fn gui_consumed_key(&self, key: u32) -> bool {
+        let guis = [&self.gui, &self.context_gui, &self.debug_gui, &self.perf_gui, &self.palette_gui, &self.console_gui];
+        guis.iter().any(|gui| gui.consumed_key(key) )
+    }
+
+    /// The debug menubar accelerators are keyboard shortcuts. They are ignored while typing in a text input.
+    fn update_debug_accelerators(&mut self) {
+        let text_focus = self.gui.has_text_focus() || self.context_gui.has_text_focus() || self.debug_gui.has_text_focus()
+            || self.console_gui.has_text_focus();
+        if text_focus {
+            return;
         }
+
+        self.debug_gui.update_accelerators(&self.api);
     }
 
     fn uninitialized(&mut self) -> Result<(), CommonError> {
@@ -175,51 +295,329 @@ impl LoomzClient {
     fn on_reload(&mut self) -> Result<(), CommonError> {
         println!("RELOAD");
         self.debug_gui()?;
+        self.perf_gui()?;
         Ok(())
     }
 
     fn debug_gui(&mut self) -> Result<(), CommonError> {
-        use crate::gui::{GuiLayoutType, GuiLayoutPosition, GuiStyleState, GuiLabelCallback};
+        use crate::gui::{GuiLayoutType, GuiLayoutPosition, GuiSize, GuiStyleState};
+        use loomz_shared::inputs::keys;
         use loomz_shared::{rect, rgb};
 
         let screen_size = self.api.inputs().screen_size_value();
         let view = loomz_shared::RectF32::from_size(screen_size);
+        let flags = self.debug_state.world;
+        let ui_scale = self.debug_state.ui_scale;
+        let perf_overlay = self.debug_state.perf_overlay;
+        let strings = &self.strings;
+
+        self.debug_gui.set_layer(&self.api, DEBUG_GUI_LAYER);
 
         self.debug_gui.build_style(&self.api, |style| {
             style.root_layout(GuiLayoutType::HBox, GuiLayoutPosition::TopLeft);
             style.frame("menubar", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(51, 51, 51));
-            style.label("menubar_item", GuiStyleState::Base, "roboto", 20.0, rgb(200, 200, 200));
-            style.label("menubar_item", GuiStyleState::Hovered, "roboto", 20.0, rgb(150, 150, 250));
+            style.frame("menu_frame", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(51, 51, 51));
+            style.frame("menu_frame", GuiStyleState::Hovered, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(75, 75, 75));
+            style.menu("menubar_item", GuiStyleState::Base, "roboto", 20.0, rgb(200, 200, 200), "menu_frame");
+            style.menu("menubar_item", GuiStyleState::Hovered, "roboto", 20.0, rgb(150, 150, 250), "menu_frame");
         })?;
 
-        self.debug_gui.build(&self.api, &view, |gui| {
-            gui.layout(GuiLayoutType::HBox, GuiLayoutPosition::TopLeft);
-            gui.layout_item(view.width(), 25.0);
+        self.debug_gui.build(&self.api, &self.strings, &view, |gui| {
+            gui.layout(GuiLayoutType::HBox, GuiLayoutPosition::Left);
+            gui.layout_spacing(5.0);
+            gui.layout_item_sized(GuiSize::Percent(1.0), GuiSize::Fixed(25.0));
+            gui.layout_item_padding(loomz_shared::rect(5.0, 0.0, 5.0, 0.0));
             gui.frame("menubar", |gui| {
-                gui.layout_item(45.0, 20.0);
-                gui.label_callback(GuiLabelCallback::Click, 1000u64);
-                gui.label("File", "menubar_item");
-
-                gui.layout_item(45.0, 20.0);
-                gui.label_callback(GuiLabelCallback::Click, 1001u64);
-                gui.label("Exit", "menubar_item");
+                gui.layout_item(60.0, 25.0);
+                gui.menu_key("debug.file", "menubar_item", |menu| {
+                    menu.accelerator(keys::S, true, "Ctrl+S");
+                    menu.item_key("debug.save_session", DEBUG_SAVE_SESSION);
+                    menu.accelerator(keys::L, true, "Ctrl+L");
+                    menu.item_key("debug.load_session", DEBUG_LOAD_SESSION);
+                    menu.separator();
+                    menu.item_key("menu.exit", DEBUG_EXIT);
+                });
+
+                gui.menu_key("debug.view", "menubar_item", |menu| {
+                    menu.accelerator(keys::_1, false, "1");
+                    menu.checkable_item_key("debug.main_grid", flags.contains(WorldDebugFlags::SHOW_MAIN_GRID), DEBUG_MAIN_GRID);
+                    menu.accelerator(keys::_2, false, "2");
+                    menu.checkable_item_key("debug.sub_grid", flags.contains(WorldDebugFlags::SHOW_SUB_GRID), DEBUG_SUB_GRID);
+                    menu.accelerator(keys::_3, false, "3");
+                    menu.checkable_item_key("debug.cell_types", flags.contains(WorldDebugFlags::SHOW_MAIN_GRID_TYPES), DEBUG_GRID_TYPES);
+                    menu.separator();
+                    menu.accelerator(keys::_4, false, "4");
+                    menu.checkable_item_key("debug.performance", perf_overlay, DEBUG_PERF_OVERLAY);
+                });
+
+                gui.menu_key("debug.state", "menubar_item", |menu| {
+                    menu.item_key("debug.main_menu", DEBUG_STATE_MAIN_MENU);
+                    menu.item_key("debug.game", DEBUG_STATE_GAME);
+                    menu.item_key("main_menu.editor", DEBUG_STATE_EDITOR);
+                });
+
+                gui.layout_item(90.0, 25.0);
+                gui.menu_key("debug.language", "menubar_item", |menu| {
+                    for (index, (_, name)) in strings.languages().enumerate().take((DEBUG_LANGUAGE_MAX - DEBUG_LANGUAGE + 1) as usize) {
+                        menu.checkable_item(name, index == strings.language(), DEBUG_LANGUAGE + index as u64);
+                    }
+                });
+
+                gui.menu_key("debug.ui_scale", "menubar_item", |menu| {
+                    menu.checkable_item_key("debug.ui_scale_auto", ui_scale == 0.0, DEBUG_UI_SCALE_AUTO);
+                    for (index, (scale, name)) in UI_SCALES.iter().enumerate() {
+                        menu.checkable_item(name, ui_scale == *scale, DEBUG_UI_SCALE + index as u64);
+                    }
+                });
             });
         })?;
 
         Ok(())
     }
 
-    fn update_debug_gui(&mut self) {
-        self.debug_gui.read_inputs(&self.api);
+    fn update_debug_gui(&mut self) -> Result<(), CommonError> {
         while let Some(event) = self.debug_gui.next_event() {
             match event {
-                1000 => { },
-                1001u64 => {
-                    self.api.exit();
+                DEBUG_SAVE_SESSION => {
+                    if let Err(err) = self.save_session() {
+                        self.console.print_error(&err);
+                    }
+                },
+                DEBUG_LOAD_SESSION => {
+                    if let Err(err) = self.load_session() {
+                        self.console.print_error(&err);
+                    }
+
+                    // The events of the previous debug gui are dropped with it
+                    return Ok(());
+                },
+                DEBUG_EXIT => { self.api.exit(); },
+                DEBUG_MAIN_GRID => { self.set_debug_flag(WorldDebugFlags::SHOW_MAIN_GRID, DEBUG_MAIN_GRID)?; },
+                DEBUG_SUB_GRID => { self.set_debug_flag(WorldDebugFlags::SHOW_SUB_GRID, DEBUG_SUB_GRID)?; },
+                DEBUG_GRID_TYPES => { self.set_debug_flag(WorldDebugFlags::SHOW_MAIN_GRID_TYPES, DEBUG_GRID_TYPES)?; },
+                DEBUG_PERF_OVERLAY => {
+                    let checked = self.debug_gui.menu_item_checked(DEBUG_PERF_OVERLAY).unwrap_or(false);
+                    self.set_perf_overlay(checked)?;
+                },
+                DEBUG_STATE_MAIN_MENU => { self.init_main_menu()?; },
+                DEBUG_STATE_GAME => { self.init_gameplay()?; },
+                DEBUG_STATE_EDITOR => { self.init_editor()?; },
+                DEBUG_LANGUAGE..=DEBUG_LANGUAGE_MAX => {
+                    self.set_language((event - DEBUG_LANGUAGE) as usize)?;
+
+                    // The events of the previous debug gui are dropped with it
+                    return Ok(());
+                },
+                DEBUG_UI_SCALE_AUTO => {
+                    self.set_ui_scale(0.0)?;
+                    return Ok(());
+                },
+                DEBUG_UI_SCALE.. if event < DEBUG_UI_SCALE + UI_SCALES.len() as u64 => {
+                    self.set_ui_scale(UI_SCALES[(event - DEBUG_UI_SCALE) as usize].0)?;
+                    return Ok(());
                 },
                 _ => {}
             }
         }
+
+        Ok(())
+    }
+
+    fn set_debug_flag(&mut self, flag: WorldDebugFlags, callback: u64) -> Result<(), CommonError> {
+        let checked = self.debug_gui.menu_item_checked(callback).unwrap_or(false);
+        self.update_debug_flag(flag, checked)
+    }
+
+    fn update_debug_flag(&mut self, flag: WorldDebugFlags, value: bool) -> Result<(), CommonError> {
+        self.debug_state.world.set(flag, value);
+        self.api.world().toggle_debug(self.debug_state.world);
+
+        // The editor panel shows the same flags
+        if let GameState::Editor = self.state {
+            self.editor_debug_flags_changed()?;
+        }
+
+        Ok(())
+    }
+
+    /// Selects the language at `index` and rebuilds the guis with the strings of the new language
+    fn set_language(&mut self, index: usize) -> Result<(), CommonError> {
+        if !self.strings.set_language(index) {
+            return Ok(());
+        }
+
+        self.rebuild_guis()
+    }
+
+    /// Returns the scale of the guis. Uses the scale factor of the window unless the user selected a scale.
+    fn ui_scale(&self) -> f32 {
+        match self.debug_state.ui_scale > 0.0 {
+            true => self.debug_state.ui_scale,
+            false => self.api.inputs().scale_factor_value(),
+        }
+    }
+
+    fn apply_ui_scale(&mut self) {
+        let scale = self.ui_scale();
+        self.gui.set_scale(scale);
+        self.context_gui.set_scale(scale);
+        self.debug_gui.set_scale(scale);
+        self.perf_gui.set_scale(scale);
+        self.palette_gui.set_scale(scale);
+        self.console_gui.set_scale(scale);
+    }
+
+    /// Rebuilds the guis when the scale factor of the window changed and no scale was selected by the user
+    fn update_ui_scale(&mut self) -> Result<(), CommonError> {
+        let scale_changed = self.api.inputs().scale_factor().is_some();
+        if scale_changed && self.debug_state.ui_scale == 0.0 && self.ui_scale() != self.debug_gui.scale() {
+            self.apply_ui_scale();
+            self.rebuild_guis()?;
+        }
+
+        Ok(())
+    }
+
+    /// Selects the scale of the guis. `0.0` uses the scale factor of the window.
+    fn set_ui_scale(&mut self, ui_scale: f32) -> Result<(), CommonError> {
+        self.debug_state.ui_scale = ui_scale;
+        self.apply_ui_scale();
+        self.rebuild_guis()
+    }
+
+    /// Rebuilds the styles and the components of the guis of the current state, and the debug gui
+    fn rebuild_guis(&mut self) -> Result<(), CommonError> {
+        self.debug_gui()?;
+        self.perf_gui()?;
+        self.rebuild_console_gui()?;
+
+        match self.state {
+            GameState::Uninitialized => {},
+            GameState::MainMenu => self.init_main_menu_menu()?,
+            GameState::Game => self.init_gameplay_gui()?,
+            GameState::Editor => {
+                self.close_editor_context_menu();
+                self.init_editor_gui()?;
+                self.build_editor_gui()?;
+                self.build_editor_palette()?;
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Updates the check marks of the debug menubar after the debug flags were changed from another gui
+    pub(crate) fn sync_debug_menu(&mut self) {
+        let flags = self.debug_state.world;
+        let gui = &mut self.debug_gui;
+        gui.set_menu_item_checked(&self.api, DEBUG_MAIN_GRID, flags.contains(WorldDebugFlags::SHOW_MAIN_GRID));
+        gui.set_menu_item_checked(&self.api, DEBUG_SUB_GRID, flags.contains(WorldDebugFlags::SHOW_SUB_GRID));
+        gui.set_menu_item_checked(&self.api, DEBUG_GRID_TYPES, flags.contains(WorldDebugFlags::SHOW_MAIN_GRID_TYPES));
+        gui.set_menu_item_checked(&self.api, DEBUG_PERF_OVERLAY, self.debug_state.perf_overlay);
+    }
+
+    /// Console commands of the debug menubar
+    fn register_debug_commands(console: &mut Console) {
+        console.register("debug.grid", "<on|off>", "Shows the main grid of the terrain", |client, args| client.console_debug_flag(WorldDebugFlags::SHOW_MAIN_GRID, args) );
+        console.register("debug.subgrid", "<on|off>", "Shows the sub grid of the terrain", |client, args| client.console_debug_flag(WorldDebugFlags::SHOW_SUB_GRID, args) );
+        console.register("debug.types", "<on|off>", "Shows the types of the terrain cells", |client, args| client.console_debug_flag(WorldDebugFlags::SHOW_MAIN_GRID_TYPES, args) );
+        console.register("debug.perf", "<on|off>", "Shows the performance overlay", Self::console_debug_perf);
+        console.register("state", "<main_menu|game|editor>", "Switches the game state", Self::console_state);
+        console.register("language", "<id>", "Selects the language of the guis", Self::console_language);
+        console.register("ui.scale", "<auto|scale>", "Selects the scale of the guis", Self::console_ui_scale);
+        console.register("exit", "", "Exits the application", |client, _| { client.api.exit(); Ok(()) });
+    }
+
+    fn console_debug_flag(&mut self, flag: WorldDebugFlags, args: &console::ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(1)?;
+        self.update_debug_flag(flag, args.bool(0)?)?;
+        self.sync_debug_menu();
+        Ok(())
+    }
+
+    fn console_debug_perf(&mut self, args: &console::ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(1)?;
+        self.set_perf_overlay(args.bool(0)?)?;
+        self.sync_debug_menu();
+        Ok(())
+    }
+
+    fn console_state(&mut self, args: &console::ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(1)?;
+        match args.str(0)? {
+            "main_menu" => self.init_main_menu(),
+            "game" => self.init_gameplay(),
+            "editor" => self.init_editor(),
+            state => Err(args.usage_err(&format!("unknown state {state:?}")))
+        }
+    }
+
+    fn console_language(&mut self, args: &console::ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(1)?;
+        let id = args.str(0)?;
+        let index = self.strings.languages().position(|(language_id, _)| language_id == id )
+            .ok_or_else(|| args.usage_err(&format!("unknown language {id:?}")) )?;
+
+        self.set_language(index)
+    }
+
+    fn console_ui_scale(&mut self, args: &console::ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(1)?;
+        let ui_scale = match args.str(0)? {
+            "auto" => 0.0,
+            _ => args.parse::<f32>(0)?
+        };
+
+        if ui_scale != 0.0 && !(0.5..=4.0).contains(&ui_scale) {
+            return Err(args.usage_err("scale must be between 0.5 and 4.0"));
+        }
+
+        self.set_ui_scale(ui_scale)
+    }
+
+    fn save_session(&self) -> Result<(), CommonError> {
+        let mut writer = crate::store::SaveFileWriter::new();
+        self.export(&mut writer);
+
+        std::fs::write(SESSION_PATH, writer.finalize())
+            .map_err(|err| save_err!("Failed to write session file {SESSION_PATH:?}: {err}") )
+    }
+
+    /// Replaces the client with the session saved in `SESSION_PATH`. The engine is not saved with the session,
+    /// so the guis, the world and the terrain are sent again to the engine.
+    fn load_session(&mut self) -> Result<(), CommonError> {
+        let bytes = std::fs::read(SESSION_PATH)
+            .map_err(|err| save_err!("Failed to read session file {SESSION_PATH:?}: {err}") )?;
+
+        let client = Self::init_from_data(&self.api, &bytes.into_boxed_slice())?;
+
+        self.gui.hide_instantly(&self.api);
+        self.context_gui.hide_instantly(&self.api);
+        self.debug_gui.hide_instantly(&self.api);
+        self.perf_gui.hide_instantly(&self.api);
+        self.palette_gui.hide_instantly(&self.api);
+        self.console_gui.hide_instantly(&self.api);
+        *self = client;
+
+        self.gui.resync(&self.api);
+        self.context_gui.resync(&self.api);
+        self.debug_gui.resync(&self.api);
+        self.perf_gui.resync(&self.api);
+        self.palette_gui.resync(&self.api);
+        self.console_gui.resync(&self.api);
+
+        let world = self.api.world();
+        world.toggle_world(matches!(self.state, GameState::Game | GameState::Editor));
+        world.toggle_debug(self.debug_state.world);
+
+        if !self.debug_state.perf_overlay {
+            self.api.perf().set_graph_area(None);
+        }
+
+        self.terrain.invalidate();
+        self.terrain.sync(&self.api);
+
+        Ok(())
     }
 
 }
@@ -336,13 +734,20 @@ impl loomz_shared::store::StoreAndLoad for Player {
 impl loomz_shared::store::StoreAndLoad for DebugState {
     fn load(reader: &mut loomz_shared::store::SaveFileReaderBase) -> Self {
         let world = WorldDebugFlags::from_bits(reader.read_u32() as u8).unwrap_or_default();
+        let ui_scale = reader.read_f32();
+        let perf_overlay = reader.read_bool();
         DebugState {
-            world
+            world,
+            ui_scale,
+            perf_overlay,
+            perf_refresh: 0.0,
         }
     }
 
     fn store(&self, writer: &mut loomz_shared::store::SaveFileWriterBase) {
         writer.write_u32(self.world.bits() as u32);
+        writer.write_f32(self.ui_scale);
+        writer.write_into_u32(self.perf_overlay);
     }
 }
 
@@ -0,0 +1,139 @@
+//! Translated strings of the user interface, loaded from the `strings` json asset. Format:
+//!
+//! ```json
+//! {
+//!   "languages": [
+//!     { "id": "en", "name": "English", "strings": { "main_menu.new_game": "New Game" } },
+//!     { "id": "fr", "name": "Français", "strings": { "main_menu.new_game": "Nouvelle partie" } }
+//!   ]
+//! }
+//! ```
+//!
+//! The first language is the default language. Keys missing from the selected language use the default language.
+use loomz_shared::api::LoomzApi;
+use loomz_shared::{assets_err, CommonError};
+
+/// Name of the json asset holding the string tables
+const STRINGS_ASSET: &str = "strings";
+
+struct LocalizationTable {
+    id: String,
+    name: String,
+    strings: fnv::FnvHashMap<String, String>,
+}
+
+#[derive(Default)]
+pub struct Localization {
+    languages: Vec<LocalizationTable>,
+    language: usize,
+    /// Missing keys already reported
+    #[cfg(debug_assertions)]
+    missing: std::sync::Mutex<fnv::FnvHashSet<String>>,
+}
+
+impl Localization {
+
+    /// Loads the string tables. The selected language is kept if it still exists.
+    pub fn load(&mut self, api: &LoomzApi) -> Result<(), CommonError> {
+        let source = api.assets_ref().json_by_name(STRINGS_ASSET)
+            .ok_or_else(|| assets_err!("Failed to find json {STRINGS_ASSET:?}") )?;
+        let json: serde_json::Value = serde_json::from_str(source.as_str())
+            .map_err(|err| assets_err!("Failed to parse json {STRINGS_ASSET:?}: {err:?}") )?;
+
+        let languages = json["languages"].as_array()
+            .ok_or_else(|| assets_err!("Missing languages in json {STRINGS_ASSET:?}") )?;
+
+        let mut tables = Vec::with_capacity(languages.len());
+        for language in languages {
+            let (id, name) = match (language["id"].as_str(), language["name"].as_str()) {
+                (Some(id), Some(name)) => (id, name),
+                _ => { return Err(assets_err!("Language without id or name in json {STRINGS_ASSET:?}")); }
+            };
+
+            let strings = language["strings"].as_object()
+                .ok_or_else(|| assets_err!("Missing strings of language {id:?}") )?
+                .iter()
+                .filter_map(|(key, value)| value.as_str().map(|value| (key.clone(), value.to_string())) )
+                .collect();
+
+            tables.push(LocalizationTable { id: id.to_string(), name: name.to_string(), strings });
+        }
+
+        if tables.is_empty() {
+            return Err(assets_err!("No languages in json {STRINGS_ASSET:?}"));
+        }
+
+        let language_id = self.languages.get(self.language).map(|table| table.id.clone() );
+        self.languages = tables;
+        self.language = 0;
+        if let Some(id) = language_id {
+            self.set_language_by_id(&id);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the string of `key` in the selected language. Returns `key` if no language defines it.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        match self.find(key) {
+            Some(value) => value,
+            None => {
+                self.report_missing(key);
+                key
+            }
+        }
+    }
+
+    /// Returns the string of `key` in the selected language, or `default` if no language defines it.
+    /// For keys built from asset data that are not required to be translated.
+    pub fn get_or<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        self.find(key).unwrap_or(default)
+    }
+
+    fn find(&self, key: &str) -> Option<&str> {
+        let selected = self.languages.get(self.language).and_then(|table| table.strings.get(key) );
+        let default = || self.languages.first().and_then(|table| table.strings.get(key) );
+        selected.or_else(default).map(|value| value.as_str() )
+    }
+
+    /// Index of the selected language
+    pub fn language(&self) -> usize {
+        self.language
+    }
+
+    /// Returns the id and the name of each language
+    pub fn languages(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.languages.iter().map(|table| (table.id.as_str(), table.name.as_str()) )
+    }
+
+    /// Selects the language at `index`. Returns `false` if there is no language at this index.
+    pub fn set_language(&mut self, index: usize) -> bool {
+        if index >= self.languages.len() {
+            return false;
+        }
+
+        self.language = index;
+        true
+    }
+
+    pub fn set_language_by_id(&mut self, id: &str) -> bool {
+        match self.languages.iter().position(|table| table.id == id ) {
+            Some(index) => self.set_language(index),
+            None => false
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    fn report_missing(&self, key: &str) {
+        let mut missing = self.missing.lock().unwrap();
+        if !missing.contains(key) {
+            eprintln!("Missing localization key {key:?}");
+            missing.insert(key.to_string());
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn report_missing(&self, _key: &str) {
+    }
+
+}
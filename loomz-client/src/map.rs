@@ -0,0 +1,646 @@
+//! Map files of the editor. A map holds the terrain cells, the actors placed on the terrain and some metadata.
+//!
+//! Maps are saved in the binary format of the `store` module, or in json if the path ends with `.json`.
+//! The json format is meant to be diffable, each row of cells is a string of hexadecimal type indices:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "name": "Island",
+//!   "author": "Someone",
+//!   "width": 4,
+//!   "height": 2,
+//!   "terrain_types": ["grass", "sand", "water"],
+//!   "cells": ["02010102", "02010002"],
+//!   "actors": [{ "kind": "pawn", "x": 1, "y": 0 }],
+//!   "spawn_points": [{ "x": 2, "y": 1 }]
+//! }
+//! ```
+//!
+//! Both formats store the names of the terrain types. Cells are matched to the terrain types by name when a map is loaded,
+//! so reordering the terrain json does not break the maps. Cells of an unknown type use the default terrain.
+use std::path::Path;
+use loomz_shared::api::{TerrainType, WorldActorId, TERRAIN_CELL_SIZE_PX};
+use loomz_shared::assets::terrain::TerrainTypes;
+use loomz_shared::store::{StoreAndLoad, SaveFileReaderBase, SaveFileWriterBase};
+use loomz_shared::{client_err, save_err, CommonError, PositionF32, SizeU32};
+use crate::store::{SaveFileReader, SaveFileWriter};
+use crate::terrain::generator::TerrainGenerator;
+use crate::LoomzClient;
+
+const MAP_MAGIC: u32 = 0x6FAA7602;
+
+/// Version of the map format. Increase it when the format changes and keep reading the older versions.
+const MAP_VERSION: u32 = 1;
+
+/// Largest width or height of a map, in cells
+pub(crate) const MAP_MAX_SIZE: u32 = 1024;
+
+/// Limit of the actors and the spawn points of a map. Protects the loader from corrupted files.
+const MAP_MAX_OBJECTS: u32 = 100_000;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum MapActorKind {
+    Pawn,
+    Warrior,
+}
+
+impl MapActorKind {
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MapActorKind::Pawn => "pawn",
+            MapActorKind::Warrior => "warrior",
+        }
+    }
+
+    /// Kind of an actor in the binary formats. The inverse of `kind as u32`.
+    pub fn from_id(id: u32) -> Option<Self> {
+        match id {
+            0 => Some(MapActorKind::Pawn),
+            1 => Some(MapActorKind::Warrior),
+            _ => None
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pawn" => Some(MapActorKind::Pawn),
+            "warrior" => Some(MapActorKind::Warrior),
+            _ => None
+        }
+    }
+
+}
+
+/// Actor placed on the map. `x` and `y` are the terrain cell of the actor.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) struct MapActor {
+    pub kind: MapActorKind,
+    pub x: u32,
+    pub y: u32,
+}
+
+/// The map being edited or played. The terrain cells are kept in `Terrain`.
+#[derive(Default)]
+pub(crate) struct Map {
+    /// File of the map. Empty if the map was never saved.
+    pub path: String,
+    pub name: String,
+    pub author: String,
+    pub actors: Vec<MapActor>,
+    /// Cells where the players start
+    pub spawn_points: Vec<[u32; 2]>,
+    /// Actors of the map created in the world
+    spawned: Vec<WorldActorId>,
+}
+
+impl Map {
+
+    /// Removes the actors and the spawn points outside of a terrain of `size` cells. Returns the number of objects removed.
+    /// Map files with objects outside of the terrain cannot be loaded.
+    pub fn remove_outside(&mut self, size: SizeU32) -> usize {
+        let count = self.actors.len() + self.spawn_points.len();
+        self.actors.retain(|actor| actor.x < size.width && actor.y < size.height );
+        self.spawn_points.retain(|&[x, y]| x < size.width && y < size.height );
+        count - self.actors.len() - self.spawn_points.len()
+    }
+
+}
+
+/// Content of a map file
+pub(crate) struct MapFile {
+    pub name: String,
+    pub author: String,
+    pub size: SizeU32,
+    /// Cells of the terrain, row by row
+    pub cells: Vec<TerrainType>,
+    pub actors: Vec<MapActor>,
+    pub spawn_points: Vec<[u32; 2]>,
+}
+
+impl MapFile {
+
+    pub fn load(path: &str, types: &TerrainTypes) -> Result<Self, CommonError> {
+        let bytes = std::fs::read(path)
+            .map_err(|err| save_err!("Failed to read map file {path:?}: {err}") )?;
+
+        match is_json_path(path) {
+            true => {
+                let source = String::from_utf8(bytes)
+                    .map_err(|_| save_err!("Map file {path:?} is not valid utf8") )?;
+                Self::from_json(&source, types)
+            },
+            false => Self::from_bytes(&bytes, types)
+        }
+    }
+
+    pub fn save(&self, path: &str, types: &TerrainTypes) -> Result<(), CommonError> {
+        let bytes = match is_json_path(path) {
+            true => self.to_json(types).into_bytes(),
+            false => self.to_bytes(types),
+        };
+
+        if let Some(parent) = Path::new(path).parent().filter(|parent| !parent.as_os_str().is_empty() ) {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| save_err!("Failed to create the directory of map file {path:?}: {err}") )?;
+        }
+
+        std::fs::write(path, bytes)
+            .map_err(|err| save_err!("Failed to write map file {path:?}: {err}") )
+    }
+
+    pub fn from_bytes(bytes: &[u8], types: &TerrainTypes) -> Result<Self, CommonError> {
+        let mut reader = SaveFileReader::with_magic(bytes, MAP_MAGIC)?;
+        let version = reader.version();
+        if version == 0 || version > MAP_VERSION {
+            return Err(save_err!("Unsupported map file version {version}"));
+        }
+
+        let name = read_string(&mut reader)?;
+        let author = read_string(&mut reader)?;
+        expect_remaining(&reader, 2)?;
+        let size = validate_size(reader.read_u32(), reader.read_u32())?;
+
+        let type_count = read_count(&mut reader, 2)?;
+        let mut type_names = Vec::with_capacity(type_count);
+        for _ in 0..type_count {
+            type_names.push(read_string(&mut reader)?);
+        }
+
+        // Cells are packed four per u32
+        let cell_count = (size.width * size.height) as usize;
+        let packed_count = cell_count.div_ceil(4);
+        expect_remaining(&reader, packed_count + 1)?;
+        if reader.data[reader.current_offset] as usize != packed_count {
+            return Err(save_err!("Map file cells do not match the size of the map"));
+        }
+
+        let packed: &[u32] = reader.read_slice();
+
+        let remap = remap_types(&type_names, types);
+        let cells = packed.iter()
+            .flat_map(|value| value.to_le_bytes() )
+            .take(cell_count)
+            .map(|index| remap.get(index as usize).copied().unwrap_or_default() )
+            .collect();
+
+        let actor_count = read_count(&mut reader, 3)?;
+        let mut actors = Vec::with_capacity(actor_count);
+        for _ in 0..actor_count {
+            let kind = reader.read_u32();
+            let kind = MapActorKind::from_id(kind)
+                .ok_or_else(|| save_err!("Invalid actor kind {kind} in map file") )?;
+            let [x, y] = validate_cell(size, reader.read_u32() as u64, reader.read_u32() as u64)?;
+            actors.push(MapActor { kind, x, y });
+        }
+
+        let spawn_count = read_count(&mut reader, 2)?;
+        let mut spawn_points = Vec::with_capacity(spawn_count);
+        for _ in 0..spawn_count {
+            spawn_points.push(validate_cell(size, reader.read_u32() as u64, reader.read_u32() as u64)?);
+        }
+
+        Ok(MapFile { name, author, size, cells, actors, spawn_points })
+    }
+
+    pub fn to_bytes(&self, types: &TerrainTypes) -> Vec<u8> {
+        let mut writer = SaveFileWriter::with_magic(MAP_MAGIC, MAP_VERSION);
+        writer.write_str(&self.name);
+        writer.write_str(&self.author);
+        writer.write_u32(self.size.width);
+        writer.write_u32(self.size.height);
+
+        writer.write_u32(types.len() as u32);
+        for (_, info) in types.iter() {
+            writer.write_str(&info.name);
+        }
+
+        let packed: Vec<u32> = self.cells.chunks(4)
+            .map(|cells| {
+                let mut bytes = [0u8; 4];
+                for (byte, cell) in bytes.iter_mut().zip(cells) {
+                    *byte = cell.0;
+                }
+                u32::from_le_bytes(bytes)
+            })
+            .collect();
+        writer.write_slice(&packed);
+
+        writer.write_u32(self.actors.len() as u32);
+        for actor in self.actors.iter() {
+            writer.write_u32(actor.kind as u32);
+            writer.write_u32(actor.x);
+            writer.write_u32(actor.y);
+        }
+
+        writer.write_u32(self.spawn_points.len() as u32);
+        for &[x, y] in self.spawn_points.iter() {
+            writer.write_u32(x);
+            writer.write_u32(y);
+        }
+
+        writer.finalize()
+    }
+
+    pub fn from_json(source: &str, types: &TerrainTypes) -> Result<Self, CommonError> {
+        let json: serde_json::Value = serde_json::from_str(source)
+            .map_err(|err| save_err!("Failed to parse map json: {err:?}") )?;
+
+        let version = json["version"].as_u64().unwrap_or(0);
+        if version == 0 || version > MAP_VERSION as u64 {
+            return Err(save_err!("Unsupported map file version {version}"));
+        }
+
+        let read_u32 = |value: &serde_json::Value| value.as_u64().map(|v| v as u32).unwrap_or(0);
+        let size = validate_size(read_u32(&json["width"]), read_u32(&json["height"]))?;
+
+        let type_names: Vec<String> = json["terrain_types"].as_array()
+            .map(|names| names.iter().map(|name| name.as_str().unwrap_or("").to_string() ).collect() )
+            .unwrap_or_default();
+
+        let remap = remap_types(&type_names, types);
+        let rows = json["cells"].as_array()
+            .filter(|rows| rows.len() == size.height as usize )
+            .ok_or_else(|| save_err!("Map json cells do not match the height of the map") )?;
+
+        let mut cells = Vec::with_capacity((size.width * size.height) as usize);
+        for row in rows {
+            let row = row.as_str()
+                .filter(|row| row.len() == (size.width as usize) * 2 && row.is_ascii() )
+                .ok_or_else(|| save_err!("Map json cells do not match the width of the map") )?;
+
+            for index in 0..(size.width as usize) {
+                let index = u8::from_str_radix(&row[index*2..index*2+2], 16)
+                    .map_err(|_| save_err!("Invalid terrain type in map json cells") )?;
+                cells.push(remap.get(index as usize).copied().unwrap_or_default());
+            }
+        }
+
+        // Coordinates that are missing or not positive integers are out of the map
+        let read_cell = |value: &serde_json::Value| {
+            let read_coord = |coord: &serde_json::Value| coord.as_u64().unwrap_or(u64::MAX);
+            validate_cell(size, read_coord(&value["x"]), read_coord(&value["y"]))
+        };
+
+        let mut actors = Vec::new();
+        for actor in json["actors"].as_array().map(|v| v.as_slice() ).unwrap_or(&[]) {
+            let kind = actor["kind"].as_str()
+                .and_then(MapActorKind::from_name)
+                .ok_or_else(|| save_err!("Invalid actor kind in map json: {}", actor["kind"]) )?;
+            let [x, y] = read_cell(actor)?;
+            actors.push(MapActor { kind, x, y });
+        }
+
+        let mut spawn_points = Vec::new();
+        for point in json["spawn_points"].as_array().map(|v| v.as_slice() ).unwrap_or(&[]) {
+            spawn_points.push(read_cell(point)?);
+        }
+
+        let file = MapFile {
+            name: json["name"].as_str().unwrap_or("").to_string(),
+            author: json["author"].as_str().unwrap_or("").to_string(),
+            size,
+            cells,
+            actors,
+            spawn_points,
+        };
+
+        Ok(file)
+    }
+
+    pub fn to_json(&self, types: &TerrainTypes) -> String {
+        use serde_json::json;
+
+        let type_names: Vec<&str> = types.iter().map(|(_, info)| info.name.as_str() ).collect();
+        let rows: Vec<String> = self.cells.chunks(self.size.width.max(1) as usize)
+            .map(|row| row.iter().map(|cell| format!("{:02x}", cell.0) ).collect() )
+            .collect();
+        let actors: Vec<serde_json::Value> = self.actors.iter()
+            .map(|actor| json!({ "kind": actor.kind.name(), "x": actor.x, "y": actor.y }) )
+            .collect();
+        let spawn_points: Vec<serde_json::Value> = self.spawn_points.iter()
+            .map(|[x, y]| json!({ "x": x, "y": y }) )
+            .collect();
+
+        let json = json!({
+            "version": MAP_VERSION,
+            "name": self.name,
+            "author": self.author,
+            "width": self.size.width,
+            "height": self.size.height,
+            "terrain_types": type_names,
+            "cells": rows,
+            "actors": actors,
+            "spawn_points": spawn_points,
+        });
+
+        serde_json::to_string_pretty(&json).unwrap_or_default()
+    }
+
+}
+
+impl LoomzClient {
+
+    /// Replaces the map with an empty map of `width` by `height` cells
+    pub(crate) fn new_map(&mut self, width: u32, height: u32) -> Result<(), CommonError> {
+        let size = validate_size(width, height)?;
+        self.despawn_map_actors();
+        self.editor_history.clear();
+        *self.map = Map::default();
+        self.terrain.set_world_size(size.width, size.height);
+        self.terrain.sync(&self.api);
+        Ok(())
+    }
+
+    /// Replaces the map with a procedural island of `width` by `height` cells. The same seed always generates the same map.
+    pub(crate) fn generate_map(&mut self, width: u32, height: u32, seed: u64, rivers: u32) -> Result<(), CommonError> {
+        self.new_map(width, height)?;
+
+        let generator = TerrainGenerator { seed, width, height, rivers };
+        let generated = generator.generate(self.terrain.types());
+
+        self.map.name = format!("Random map {seed}");
+        self.map.spawn_points.extend(generated.spawn_point);
+        self.terrain.set_cells(0, 0, width, height, &generated.cells);
+        self.terrain.sync(&self.api);
+
+        Ok(())
+    }
+
+    pub(crate) fn open_map(&mut self, path: &str) -> Result<(), CommonError> {
+        let file = MapFile::load(path, self.terrain.types())?;
+
+        self.despawn_map_actors();
+        self.editor_history.clear();
+        *self.map = Map {
+            path: path.to_string(),
+            name: file.name,
+            author: file.author,
+            actors: file.actors,
+            spawn_points: file.spawn_points,
+            spawned: Vec::new(),
+        };
+
+        let size = file.size;
+        self.terrain.set_world_size(size.width, size.height);
+        self.terrain.set_cells(0, 0, size.width, size.height, &file.cells);
+        self.terrain.sync(&self.api);
+
+        Ok(())
+    }
+
+    /// Saves the map to `path`. The map remembers `path` for the next saves.
+    pub(crate) fn save_map(&mut self, path: &str) -> Result<(), CommonError> {
+        if path.is_empty() {
+            return Err(client_err!("The map file path is empty"));
+        }
+
+        let map = &self.map;
+        let file = MapFile {
+            name: map.name.clone(),
+            author: map.author.clone(),
+            size: self.terrain.size(),
+            cells: self.terrain.cells(),
+            actors: map.actors.clone(),
+            spawn_points: map.spawn_points.clone(),
+        };
+
+        file.save(path, self.terrain.types())?;
+        self.map.path = path.to_string();
+
+        Ok(())
+    }
+
+    /// Creates the actors of the map in the world
+    pub(crate) fn spawn_map_actors(&mut self) {
+        self.despawn_map_actors();
+
+        for index in 0..self.map.actors.len() {
+            let id = self.spawn_actor(self.map.actors[index]);
+            self.map.spawned.push(id);
+        }
+    }
+
+    /// Creates an actor in the world, at the center of its cell
+    pub(crate) fn spawn_actor(&self, actor: MapActor) -> WorldActorId {
+        let animation = match actor.kind {
+            MapActorKind::Pawn => &self.animations.pawn.idle,
+            MapActorKind::Warrior => &self.animations.warrior.idle,
+        };
+
+        let id = WorldActorId::new();
+        let cell_size = TERRAIN_CELL_SIZE_PX as f32;
+        let position = PositionF32 { x: (actor.x as f32 + 0.5) * cell_size, y: (actor.y as f32 + 0.5) * cell_size };
+        self.api.world().create_actor(&id, position, animation);
+        id
+    }
+
+    pub(crate) fn despawn_map_actors(&mut self) {
+        let world = self.api.world();
+        for id in self.map.spawned.drain(..) {
+            world.destroy_actor(&id);
+        }
+    }
+
+}
+
+impl StoreAndLoad for Map {
+    fn load(reader: &mut SaveFileReaderBase) -> Self {
+        let mut map = Map {
+            path: reader.read_str().to_string(),
+            name: reader.read_str().to_string(),
+            author: reader.read_str().to_string(),
+            ..Default::default()
+        };
+
+        for _ in 0..reader.read_len(3) {
+            let kind = MapActorKind::from_id(reader.read_u32()).unwrap_or(MapActorKind::Pawn);
+            map.actors.push(MapActor { kind, x: reader.read_u32(), y: reader.read_u32() });
+        }
+
+        for _ in 0..reader.read_len(2) {
+            map.spawn_points.push([reader.read_u32(), reader.read_u32()]);
+        }
+
+        for _ in 0..reader.read_len(1) {
+            map.spawned.push(reader.load());
+        }
+
+        map
+    }
+
+    fn store(&self, writer: &mut SaveFileWriterBase) {
+        writer.write_str(&self.path);
+        writer.write_str(&self.name);
+        writer.write_str(&self.author);
+
+        writer.write_u32(self.actors.len() as u32);
+        for actor in self.actors.iter() {
+            writer.write_u32(actor.kind as u32);
+            writer.write_u32(actor.x);
+            writer.write_u32(actor.y);
+        }
+
+        writer.write_u32(self.spawn_points.len() as u32);
+        for &[x, y] in self.spawn_points.iter() {
+            writer.write_u32(x);
+            writer.write_u32(y);
+        }
+
+        writer.write_u32(self.spawned.len() as u32);
+        for id in self.spawned.iter() {
+            writer.store(id);
+        }
+    }
+}
+
+fn is_json_path(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json") )
+}
+
+fn validate_size(width: u32, height: u32) -> Result<SizeU32, CommonError> {
+    if width == 0 || height == 0 || width > MAP_MAX_SIZE || height > MAP_MAX_SIZE {
+        return Err(save_err!("The size of the map must be between 1 and {MAP_MAX_SIZE}"));
+    }
+
+    Ok(SizeU32 { width, height })
+}
+
+/// Checks that the cell `x`, `y` of an actor or a spawn point is inside the map
+fn validate_cell(size: SizeU32, x: u64, y: u64) -> Result<[u32; 2], CommonError> {
+    if x >= size.width as u64 || y >= size.height as u64 {
+        return Err(save_err!("Map object at {x}, {y} is outside of the map"));
+    }
+
+    Ok([x as u32, y as u32])
+}
+
+fn expect_remaining(reader: &SaveFileReaderBase, count: usize) -> Result<(), CommonError> {
+    match reader.remaining() < count {
+        true => Err(save_err!("Map file is truncated")),
+        false => Ok(())
+    }
+}
+
+/// Reads the number of items of a list, each item being at least `item_size` u32 values
+fn read_count(reader: &mut SaveFileReaderBase, item_size: usize) -> Result<usize, CommonError> {
+    expect_remaining(reader, 1)?;
+    let count = reader.read_u32();
+    if count > MAP_MAX_OBJECTS {
+        return Err(save_err!("Map file is corrupted"));
+    }
+
+    expect_remaining(reader, (count as usize) * item_size)?;
+    Ok(count as usize)
+}
+
+/// Reads a string written by `write_str`, checking that it fits in the file
+fn read_string(reader: &mut SaveFileReaderBase) -> Result<String, CommonError> {
+    expect_remaining(reader, 2)?;
+    let length = reader.data[reader.current_offset] as usize;
+    let length_padded = reader.data[reader.current_offset + 1] as usize;
+    if length > length_padded {
+        return Err(save_err!("Map file is corrupted"));
+    }
+
+    expect_remaining(reader, 2 + length_padded / 4)?;
+    Ok(reader.read_str().to_string())
+}
+
+/// Maps the terrain types of a map file to the loaded terrain types
+fn remap_types(names: &[String], types: &TerrainTypes) -> Vec<TerrainType> {
+    names.iter()
+        .map(|name| types.by_name(name).unwrap_or_default() )
+        .collect()
+}
+
+#[cfg(test)]
+mod map_tests {
+    use super::*;
+
+    fn test_types(names: &[&str]) -> TerrainTypes {
+        let types: Vec<String> = names.iter().map(|name| format!("{{ \"name\": \"{name}\" }}") ).collect();
+        TerrainTypes::from_json(&format!("{{ \"types\": [{}] }}", types.join(","))).unwrap()
+    }
+
+    fn test_map() -> MapFile {
+        MapFile {
+            name: "Island".to_string(),
+            author: "Someone".to_string(),
+            size: SizeU32 { width: 3, height: 2 },
+            cells: [0, 1, 2, 2, 1, 0].map(TerrainType).to_vec(),
+            actors: vec![MapActor { kind: MapActorKind::Warrior, x: 1, y: 1 }],
+            spawn_points: vec![[2, 0]],
+        }
+    }
+
+    fn assert_same_map(a: &MapFile, b: &MapFile) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.author, b.author);
+        assert_eq!(a.size, b.size);
+        assert_eq!(a.cells, b.cells);
+        assert_eq!(a.actors, b.actors);
+        assert_eq!(a.spawn_points, b.spawn_points);
+    }
+
+    #[test]
+    fn bytes() {
+        let types = test_types(&["grass", "sand", "water"]);
+        let map = test_map();
+        let bytes = map.to_bytes(&types);
+        assert_same_map(&map, &MapFile::from_bytes(&bytes, &types).unwrap());
+
+        // The header is 3 values. The file ends with the actor (count, kind, x, y) and the spawn point (count, x, y).
+        let words = bytes.len() / 4;
+        let corrupted = |index: usize, value: u32| {
+            let mut bytes = bytes.clone();
+            bytes[index*4..index*4+4].copy_from_slice(&value.to_le_bytes());
+            MapFile::from_bytes(&bytes, &types)
+        };
+
+        assert!(corrupted(3, 5000).is_err(), "name length");
+        assert!(corrupted(words - 7, 5000).is_err(), "actors count");
+        assert!(corrupted(words - 3, MAP_MAX_OBJECTS + 1).is_err(), "spawn points count");
+        assert!(corrupted(words - 6, 7).is_err(), "actor kind");
+        assert!(corrupted(words - 5, 3).is_err(), "actor x");
+        assert!(corrupted(words - 1, 2).is_err(), "spawn point y");
+        assert!(corrupted(words - 6, 0).is_ok());
+    }
+
+    #[test]
+    fn remove_outside() {
+        let mut map = Map {
+            actors: vec![MapActor { kind: MapActorKind::Pawn, x: 1, y: 1 }, MapActor { kind: MapActorKind::Warrior, x: 4, y: 0 }],
+            spawn_points: vec![[0, 3], [2, 2]],
+            ..Default::default()
+        };
+
+        assert!(map.remove_outside(SizeU32 { width: 3, height: 3 }) == 2);
+        assert_eq!(map.actors, vec![MapActor { kind: MapActorKind::Pawn, x: 1, y: 1 }]);
+        assert_eq!(map.spawn_points, vec![[2, 2]]);
+    }
+
+    #[test]
+    fn json() {
+        let types = test_types(&["grass", "sand", "water"]);
+        let map = test_map();
+        let json = map.to_json(&types);
+        assert_same_map(&map, &MapFile::from_json(&json, &types).unwrap());
+
+        // Types are matched by name. Unknown types use the default terrain.
+        let reordered = test_types(&["water", "grass"]);
+        let loaded = MapFile::from_json(&json, &reordered).unwrap();
+        assert_eq!(loaded.cells, [1, 0, 0, 0, 0, 1].map(TerrainType).to_vec());
+
+        let unknown_kind = json.replace("\"warrior\"", "\"dragon\"");
+        assert!(MapFile::from_json(&unknown_kind, &types).is_err());
+
+        let mut outside = map;
+        outside.spawn_points.push([3, 0]);
+        assert!(MapFile::from_json(&outside.to_json(&types), &types).is_err());
+        outside.spawn_points.pop();
+        outside.actors[0].y = 2;
+        assert!(MapFile::from_json(&outside.to_json(&types), &types).is_err());
+    }
+}
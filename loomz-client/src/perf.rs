@@ -0,0 +1,133 @@
+//! Performance overlay of the debug menubar. Shows the timings and the counters gathered by the client and the engine,
+//! and reserves an area for the frame time graph drawn by the engine.
+use loomz_shared::{CommonError, RectF32, rect};
+use crate::LoomzClient;
+
+/// Layer of the performance overlay. Drawn under the debug menubar.
+const PERF_GUI_LAYER: i32 = 90;
+
+/// Time between two refreshes of the overlay values, in seconds
+const PERF_REFRESH_INTERVAL: f32 = 0.25;
+
+/// Number of labels of the overlay. See `perf_lines`.
+const PERF_LINES: usize = 9;
+
+const PERF_WIDTH: f32 = 260.0;
+const PERF_LINE_HEIGHT: f32 = 20.0;
+const PERF_GRAPH_HEIGHT: f32 = 60.0;
+const PERF_PADDING: f32 = 8.0;
+/// Space between the overlay and the top of the screen, below the debug menubar
+const PERF_TOP: f32 = 30.0;
+
+impl LoomzClient {
+
+    pub(crate) fn set_perf_overlay(&mut self, visible: bool) -> Result<(), CommonError> {
+        self.debug_state.perf_overlay = visible;
+        match visible {
+            true => self.perf_gui()?,
+            false => {
+                self.perf_gui.toggle(&self.api, false);
+                self.api.perf().set_graph_area(None);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Refreshes the overlay values at a fixed interval. Only the text of the labels is updated.
+    pub(crate) fn update_perf_overlay(&mut self) -> Result<(), CommonError> {
+        if !self.debug_state.perf_overlay {
+            return Ok(());
+        }
+
+        self.debug_state.perf_refresh += self.timing.delta_ms as f32;
+        if self.debug_state.perf_refresh < PERF_REFRESH_INTERVAL {
+            return Ok(());
+        }
+
+        self.debug_state.perf_refresh = 0.0;
+
+        let lines = self.perf_lines();
+        for (index, line) in lines.iter().enumerate() {
+            self.perf_gui.set_label_text(&self.api, index, line);
+        }
+
+        Ok(())
+    }
+
+    /// Builds the overlay with the current stats. Does nothing if the overlay is hidden.
+    /// Once built, the values are refreshed in place by `update_perf_overlay`.
+    pub(crate) fn perf_gui(&mut self) -> Result<(), CommonError> {
+        use crate::gui::{GuiLayoutType, GuiLayoutPosition, GuiSize, GuiStyleState};
+        use loomz_shared::rgb;
+
+        if !self.debug_state.perf_overlay {
+            return Ok(());
+        }
+
+        self.debug_state.perf_refresh = 0.0;
+
+        let lines = self.perf_lines();
+        let (view, graph) = self.perf_areas(lines.len());
+
+        self.perf_gui.set_layer(&self.api, PERF_GUI_LAYER);
+        self.perf_gui.build_style(&self.api, |style| {
+            style.root_layout(GuiLayoutType::VBox, GuiLayoutPosition::TopLeft);
+            style.frame("perf_panel", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(36, 36, 40));
+            style.label("perf_label", GuiStyleState::Base, "roboto", 16.0, rgb(210, 210, 210));
+        })?;
+
+        self.perf_gui.build(&self.api, &self.strings, &view, |gui| {
+            gui.layout(GuiLayoutType::VBox, GuiLayoutPosition::TopLeft);
+            gui.layout_item_sized(GuiSize::Percent(1.0), GuiSize::Percent(1.0));
+            gui.layout_item_padding(rect(PERF_PADDING, PERF_PADDING, PERF_PADDING, PERF_PADDING));
+            gui.frame("perf_panel", |gui| {
+                for line in lines.iter() {
+                    gui.layout_item_sized(GuiSize::Percent(1.0), GuiSize::Fixed(PERF_LINE_HEIGHT));
+                    gui.label(line, "perf_label");
+                }
+            });
+        })?;
+
+        if !self.perf_gui.visible() {
+            self.perf_gui.toggle(&self.api, true);
+        }
+
+        self.api.perf().set_graph_area(Some(graph));
+
+        Ok(())
+    }
+
+    fn perf_lines(&self) -> [String; PERF_LINES] {
+        let stats = self.api.perf().stats();
+        [
+            format!("FPS: {:.0} ({:.2} ms)", stats.fps(), stats.average_frame_time()),
+            format!("Client update: {:.2} ms", stats.client_update_ms),
+            format!("Engine update: {:.2} ms", stats.engine_update_ms),
+            format!("Engine record: {:.2} ms", stats.engine_record_ms),
+            format!("GPU: {:.2} ms", stats.gpu_ms),
+            format!("Actors: {}", stats.actors),
+            format!("Terrain batches: {}", stats.terrain_batches),
+            format!("Gui sprites: {}", stats.gui_sprites),
+            format!("Staging: {:.1} KB", (stats.staging_bytes as f32) / 1024.0),
+        ]
+    }
+
+    /// Returns the screen area of the overlay and the area of the frame time graph at the bottom of the overlay
+    fn perf_areas(&self, lines_count: usize) -> (RectF32, RectF32) {
+        let scale = self.perf_gui.scale();
+        let screen_size = self.api.inputs().screen_size_value();
+
+        let width = PERF_WIDTH * scale;
+        let padding = PERF_PADDING * scale;
+        let height = ((lines_count as f32) * PERF_LINE_HEIGHT + PERF_GRAPH_HEIGHT + PERF_PADDING * 3.0) * scale;
+        let left = screen_size.width - width - padding;
+        let top = PERF_TOP * scale;
+
+        let view = rect(left, top, left + width, top + height);
+        let graph = rect(view.left + padding, view.bottom - padding - PERF_GRAPH_HEIGHT * scale, view.right - padding, view.bottom - padding);
+
+        (view, graph)
+    }
+
+}
@@ -2,3 +2,4 @@ mod shared;
 mod main_menu;
 mod gameplay;
 mod editor;
+pub(crate) use editor::EditorState;
@@ -1,17 +1,142 @@
 use loomz_shared::inputs::keys;
-use loomz_shared::CommonError;
-use crate::{LoomzClient, GameState, GameInputFlags};
+use loomz_shared::api::{TerrainType, WorldDebugFlags};
+use loomz_shared::base_types::PositionF32;
+use loomz_shared::store::{StoreAndLoad, SaveFileReaderBase, SaveFileWriterBase};
+use loomz_shared::{client_err, CommonError};
+use crate::console::{Console, ConsoleArgs};
+use crate::history::{EditorCommand, MapProperty};
+use crate::map::{MapActor, MapActorKind, MAP_MAX_SIZE};
+use crate::terrain::generator::{time_seed, TerrainGenerator, DEFAULT_RIVERS};
+use crate::{LoomzClient, GameState, GameInputFlags, CONTEXT_GUI_LAYER};
 
 const RETURN_EDITOR: u64 = 200;
 const EXIT_EDITOR: u64 = 201;
+const EDITOR_MAP_NAME: u64 = 202;
+const EDITOR_GRID_MODE: u64 = 203;
+const EDITOR_SHOW_TYPES: u64 = 204;
+const EDITOR_SCROLL_SPEED: u64 = 205;
+const EDITOR_CONTEXT_MOVE_UP: u64 = 206;
+const EDITOR_CONTEXT_MOVE_DOWN: u64 = 207;
+const EDITOR_MAP_NAME_CHANGE: u64 = 208;
+const EDITOR_PALETTE_BRUSH: u64 = 260;
+const EDITOR_PALETTE_SIZE: u64 = 261;
+/// The index of the terrain type is added to the callback id
+const EDITOR_PALETTE_TERRAIN: u64 = 400;
+const EDITOR_PALETTE_TERRAIN_MAX: u64 = EDITOR_PALETTE_TERRAIN + 255;
+
+/// Events of the labels of the panel rows. The callback id is the event id plus the row.
+const EDITOR_ROW_DRAG: u64 = 210;
+const EDITOR_ROW_ENTER: u64 = 220;
+const EDITOR_ROW_LEAVE: u64 = 230;
+const EDITOR_ROW_DROP: u64 = 240;
+const EDITOR_ROW_CONTEXT: u64 = 250;
+const EDITOR_ROW_EVENT_MAX: u64 = EDITOR_ROW_CONTEXT + EDITOR_ROW_COUNT as u64 - 1;
+
+/// Map file commands of the editor menu
+const EDITOR_MAP_PATH: u64 = 280;
+const EDITOR_MAP_NEW: u64 = 281;
+const EDITOR_MAP_OPEN: u64 = 282;
+const EDITOR_MAP_SAVE_AS: u64 = 283;
+
+const EDITOR_GRID_ROW: u32 = 0;
+const EDITOR_TYPES_ROW: u32 = 1;
+const EDITOR_SCROLL_ROW: u32 = 2;
+const EDITOR_ROW_COUNT: usize = 3;
+
+/// Size of the terrain of a new map, in cells
+const EDITOR_NEW_MAP_SIZE: u32 = 16;
+
+const CONTEXT_MENU_WIDTH: f32 = 180.0;
+const CONTEXT_ITEM_HEIGHT: f32 = 40.0;
+
+/// Layer of the terrain palette. Drawn under the editor menu, which blocks its inputs while it is shown.
+const PALETTE_GUI_LAYER: i32 = -10;
+const PALETTE_WIDTH: f32 = 200.0;
+const PALETTE_TITLE_HEIGHT: f32 = 34.0;
+const PALETTE_ITEM_HEIGHT: f32 = 28.0;
+const PALETTE_PADDING: f32 = 10.0;
+/// Space between the palette and the top of the screen, below the debug menubar
+const PALETTE_TOP: f32 = 40.0;
+const PALETTE_MAX_BRUSH_SIZE: f32 = 16.0;
+
+/// Localization keys of the grid modes
+const GRID_MODES: [&str; 3] = ["editor.grid_hidden", "editor.grid_main", "editor.grid_all"];
+
+/// Localization keys of the brushes, in the order of `EditorBrush`
+const BRUSHES: [&str; 4] = ["editor.brush_square", "editor.brush_circle", "editor.brush_fill", "editor.brush_rect"];
+
+/// Tools of the terrain palette
+#[derive(Copy, Clone, PartialEq)]
+enum EditorBrush {
+    /// Paints a square of `brush_size` cells under the cursor
+    Square,
+    /// Paints a circle of `brush_size` cells under the cursor
+    Circle,
+    /// Fills the area of connected cells of the same type
+    Fill,
+    /// Fills the rectangle between the cells where the left button was pressed and released
+    Rectangle,
+}
+
+/// Layout of the editor panel. Rows are reordered by dragging their label or from their context menu.
+pub(crate) struct EditorState {
+    /// Rows of the editor panel, from top to bottom
+    rows: [u32; EDITOR_ROW_COUNT],
+    /// Row whose label is being dragged
+    drag_row: u32,
+    /// Row whose label is under the cursor while dragging
+    drop_row: u32,
+    /// Row of the open context menu
+    context_row: u32,
+    brush: EditorBrush,
+    /// Size of the square and circle brushes, in cells
+    brush_size: u32,
+    /// Terrain type painted by the brushes
+    brush_terrain: TerrainType,
+    /// Cell where the left button was pressed. `None` if the terrain is not being painted.
+    paint_start: Option<[i32; 2]>,
+    /// Last cell painted while the left button is down
+    paint_last: [i32; 2],
+    /// Value of the file path input of the editor menu
+    map_path: String,
+    /// Result of the last map file command, shown under the file commands. Not stored.
+    map_status: String,
+    /// If a text input of the editor menu had the focus on the last update. Not stored.
+    text_focus: bool,
+}
+
+impl EditorState {
+
+    /// Moves `row` to the position of `target` in the panel
+    fn move_row(&mut self, row: u32, target: u32) {
+        let from = self.rows.iter().position(|&r| r == row );
+        let to = self.rows.iter().position(|&r| r == target );
+        match (from, to) {
+            (Some(from), Some(to)) if from < to => self.rows[from..=to].rotate_left(1),
+            (Some(from), Some(to)) if from > to => self.rows[to..=from].rotate_right(1),
+            _ => {}
+        }
+    }
+
+    /// Returns the row `offset` positions away from `row`, if it exists
+    fn adjacent_row(&self, row: u32, offset: isize) -> Option<u32> {
+        let index = self.rows.iter().position(|&r| r == row )?;
+        self.rows.get(index.checked_add_signed(offset)?).copied()
+    }
+
+}
 
 impl LoomzClient {
 
     pub(crate) fn init_editor(&mut self) -> Result<(), CommonError> {
+        self.despawn_map_actors();
         self.gui.toggle(&self.api, false);
+        self.close_editor_context_menu();
 
         self.init_editor_gui()?;
         self.build_editor_gui()?;
+        self.build_editor_palette()?;
+        self.palette_gui.toggle(&self.api, true);
     
         self.init_editor_terrain()?;
         self.api.world().toggle_world(true);
@@ -21,11 +146,22 @@ impl LoomzClient {
     }
 
     pub(crate) fn editor(&mut self) -> Result<(), CommonError> {
+        self.editor_history.update(self.timing.delta_ms as f32);
+
+        // The changes of a property typed in a text input are undone together until the input loses the focus
+        let text_focus = self.gui.has_text_focus();
+        if self.editor_state.text_focus && !text_focus {
+            self.editor_history.seal();
+        }
+        self.editor_state.text_focus = text_focus;
         self.editor_global_update()?;
 
-        if self.gui.visible() {
+        if self.editor_state.context_row != u32::MAX {
+            self.editor_context_events()?;
+        } else if self.gui.visible() {
             self.editor_gui_events()?;
         } else {
+            self.editor_palette_events()?;
             self.editor_updates();
         }
 
@@ -34,6 +170,8 @@ impl LoomzClient {
 
     fn editor_global_update(&mut self) -> Result<(), CommonError> {
         let inputs = self.api.inputs_ref();
+        let context_menu_open = self.editor_state.context_row != u32::MAX;
+        let mut close_context_menu = false;
 
         if let Some(new_size) = inputs.screen_size() {
             self.terrain.resize_view(new_size.width, new_size.height);
@@ -41,15 +179,36 @@ impl LoomzClient {
         }
 
         if let Some(buttons) = inputs.mouse_buttons() {
+            // Pressing outside of the context menu closes it
+            let any_button_down = buttons.left_button_down() || buttons.right_button_down();
+            close_context_menu |= any_button_down && context_menu_open && !self.context_gui.hovered();
+
             match buttons.right_button_down() {
-                true => { self.input_flags.insert(GameInputFlags::DRAGGING_VIEW); },
+                true if !self.cursor_over_gui() => { self.input_flags.insert(GameInputFlags::DRAGGING_VIEW); },
+                true => {},
                 false => { self.input_flags.remove(GameInputFlags::DRAGGING_VIEW); }
             }
         }
 
+        // `Some(true)` undoes the last command, `Some(false)` redoes the last undone command
+        let mut history_step = None;
+        let esc_consumed = self.gui_consumed_key(keys::ESC);
         if let Some(keystate) = self.api.keys_ref().read_updates() {
-            if keystate.just_pressed(keys::ESC) {
-                self.gui.toggle(&self.api, !self.gui.visible());
+            if keystate.just_pressed(keys::ESC) && !esc_consumed {
+                match context_menu_open {
+                    true => { close_context_menu = true; },
+                    false => self.gui.toggle(&self.api, !self.gui.visible())
+                }
+            }
+
+            // Ctrl+Z undoes, Ctrl+Y and Ctrl+Shift+Z redo. Ignored while typing in a text input.
+            let text_focus = self.gui.has_text_focus() || self.console_gui.has_text_focus();
+            if keystate.ctrl() && !context_menu_open && !text_focus {
+                if keystate.just_pressed(keys::Z) {
+                    history_step = Some(!keystate.shift());
+                } else if keystate.just_pressed(keys::Y) {
+                    history_step = Some(false);
+                }
             }
         }
 
@@ -57,72 +216,800 @@ impl LoomzClient {
             self.build_editor_gui()?;
         }
 
+        if close_context_menu {
+            self.close_editor_context_menu();
+        }
+
+        if let Some(undo) = history_step {
+            self.editor_history_step(undo)?;
+        }
+
         Ok(())
     }
 
     fn editor_gui_events(&mut self) -> Result<(), CommonError> { 
-        self.gui.read_inputs(&self.api);
-
+        let mut rebuild = false;
         while let Some(event) = self.gui.next_event() {
             match event {
-                RETURN_EDITOR => { self.gui.toggle(&self.api, false); },
+                RETURN_EDITOR | EDITOR_MAP_NAME => { self.gui.toggle(&self.api, false); },
                 EXIT_EDITOR => { self.init_main_menu()?; },
+                EDITOR_GRID_MODE | EDITOR_SHOW_TYPES => { self.editor_update_debug_flags(); },
+                EDITOR_MAP_NAME_CHANGE => {
+                    let name = self.gui.text_input_value(EDITOR_MAP_NAME_CHANGE).unwrap_or("").to_string();
+                    self.editor_set_map_property(MapProperty::Name, name);
+                },
+                EDITOR_MAP_PATH => {
+                    let path = self.gui.text_input_value(EDITOR_MAP_PATH).unwrap_or("");
+                    self.editor_state.map_path = path.to_string();
+                },
+                EDITOR_MAP_NEW | EDITOR_MAP_OPEN | EDITOR_MAP_SAVE_AS => {
+                    self.editor_map_command(event);
+                    rebuild = true;
+                },
+                EDITOR_ROW_DRAG..=EDITOR_ROW_EVENT_MAX => { rebuild |= self.editor_row_event(event)?; },
+                _ => {}
+            }
+        }
+
+        // Rebuilding the gui drops its remaining events
+        if rebuild {
+            self.build_editor_gui()?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs a map file command of the editor menu. The result is shown in the menu and printed in the console.
+    fn editor_map_command(&mut self, event: u64) {
+        let path = self.editor_state.map_path.trim().to_string();
+        let result = match event {
+            EDITOR_MAP_NEW => self.new_map(EDITOR_NEW_MAP_SIZE, EDITOR_NEW_MAP_SIZE).map(|_| "editor.map_created" ),
+            EDITOR_MAP_OPEN => self.open_map(&path).map(|_| "editor.map_opened" ),
+            _ => self.save_map(&path).map(|_| "editor.map_saved" ),
+        };
+
+        self.editor_state.map_status = match result {
+            Ok(key) => {
+                let status = format!("{} {}", self.strings.get(key), self.map.path);
+                self.console.print(&status);
+                self.editor_state.map_path = self.map.path.clone();
+                status
+            },
+            Err(err) => {
+                self.console.print_error(&err);
+                err.inner.message.clone()
+            }
+        };
+    }
+
+    /// Handles the drag and drop and the right clicks on the labels of the panel rows. Returns `true` if the rows were reordered.
+    fn editor_row_event(&mut self, event: u64) -> Result<bool, CommonError> {
+        let row = (event % 10) as u32;
+        let state = &mut self.editor_state;
+        match event - (event % 10) {
+            EDITOR_ROW_DRAG => {
+                state.drag_row = row;
+                state.drop_row = u32::MAX;
+            },
+            EDITOR_ROW_ENTER if state.drag_row != u32::MAX => {
+                state.drop_row = row;
+            },
+            EDITOR_ROW_LEAVE if state.drop_row == row => {
+                state.drop_row = u32::MAX;
+            },
+            EDITOR_ROW_DROP => {
+                let (drag_row, drop_row) = (state.drag_row, state.drop_row);
+                state.drag_row = u32::MAX;
+                state.drop_row = u32::MAX;
+                if drag_row != u32::MAX && drop_row != u32::MAX && drag_row != drop_row {
+                    state.move_row(drag_row, drop_row);
+                    return Ok(true);
+                }
+            },
+            EDITOR_ROW_CONTEXT => {
+                let position = self.gui.event_position();
+                self.open_editor_context_menu(row, position)?;
+            },
+            _ => {}
+        }
+
+        Ok(false)
+    }
+
+    fn editor_context_events(&mut self) -> Result<(), CommonError> {
+        let mut offset = 0;
+        while let Some(event) = self.context_gui.next_event() {
+            match event {
+                EDITOR_CONTEXT_MOVE_UP => { offset = -1; },
+                EDITOR_CONTEXT_MOVE_DOWN => { offset = 1; },
                 _ => {}
             }
         }
 
+        if offset == 0 {
+            return Ok(());
+        }
+
+        let state = &mut self.editor_state;
+        let row = state.context_row;
+        if let Some(target) = state.adjacent_row(row, offset) {
+            state.move_row(row, target);
+            self.build_editor_gui()?;
+        }
+
+        self.close_editor_context_menu();
+
         Ok(())
     }
 
+    /// Shows the context menu of a panel row at `position`. The context menu is modal until it is closed.
+    fn open_editor_context_menu(&mut self, row: u32, position: PositionF32) -> Result<(), CommonError> {
+        use crate::gui::GuiLabelCallback;
+        use loomz_shared::rect;
+
+        // The cursor position is in pixels, but the sizes given to the builder are multiplied by the scale of the gui
+        let screen_size = self.api.inputs().screen_size_value();
+        let view = loomz_shared::RectF32::from_size(screen_size);
+        let scale = self.context_gui.scale();
+        let height = CONTEXT_ITEM_HEIGHT * 2.0;
+        let x = f32::max(0.0, f32::min(position.x, screen_size.width - (CONTEXT_MENU_WIDTH * scale)));
+        let y = f32::max(0.0, f32::min(position.y, screen_size.height - (height * scale)));
+
+        self.context_gui.build(&self.api, &self.strings, &view, |gui| {
+            gui.layout_item_margin(rect(x / scale, y / scale, 0.0, 0.0));
+            gui.layout_item(CONTEXT_MENU_WIDTH, height);
+            gui.frame("context_menu", |gui| {
+                gui.layout_item_margin(rect(0.0, 0.0, 0.0, 0.0));
+                gui.layout_item(CONTEXT_MENU_WIDTH, CONTEXT_ITEM_HEIGHT);
+                gui.label_callback(GuiLabelCallback::Click, EDITOR_CONTEXT_MOVE_UP);
+                gui.label_key("editor.move_up", "context_item");
+                gui.label_callback(GuiLabelCallback::Click, EDITOR_CONTEXT_MOVE_DOWN);
+                gui.label_key("editor.move_down", "context_item");
+            });
+        })?;
+
+        self.context_gui.set_modal(true);
+        self.context_gui.toggle(&self.api, true);
+        self.editor_state.context_row = row;
+
+        Ok(())
+    }
+
+    pub(crate) fn close_editor_context_menu(&mut self) {
+        self.context_gui.toggle(&self.api, false);
+        self.editor_state.context_row = u32::MAX;
+    }
+
+    /// Closes the guis of the editor. Called when switching to another state, because the context menu gui is shared by every state.
+    pub(crate) fn leave_editor(&mut self) {
+        self.close_editor_context_menu();
+        self.palette_gui.toggle(&self.api, false);
+        if self.editor_state.paint_start.is_some() {
+            self.editor_end_stroke();
+        }
+    }
+
+    fn editor_palette_events(&mut self) -> Result<(), CommonError> {
+        let mut rebuild = false;
+        while let Some(event) = self.palette_gui.next_event() {
+            match event {
+                EDITOR_PALETTE_BRUSH => {
+                    let brush = self.palette_gui.dropdown_value(EDITOR_PALETTE_BRUSH).unwrap_or(0);
+                    self.editor_state.brush = brush.into();
+                },
+                EDITOR_PALETTE_SIZE => {
+                    let size = self.palette_gui.slider_value(EDITOR_PALETTE_SIZE).unwrap_or(1.0);
+                    self.editor_state.brush_size = size as u32;
+                },
+                EDITOR_PALETTE_TERRAIN..=EDITOR_PALETTE_TERRAIN_MAX => {
+                    self.editor_state.brush_terrain = ((event - EDITOR_PALETTE_TERRAIN) as u32).into();
+                    rebuild = true;
+                },
+                _ => {}
+            }
+        }
+
+        // Rebuilding the palette highlights the selected terrain type and drops the remaining events
+        if rebuild {
+            self.build_editor_palette()?;
+        }
+
+        Ok(())
+    }
+
+    fn editor_update_debug_flags(&mut self) {
+        let grid_mode = self.gui.dropdown_value(EDITOR_GRID_MODE).unwrap_or(0);
+        let show_types = self.gui.checkbox_value(EDITOR_SHOW_TYPES).unwrap_or(false);
+
+        let flags = &mut self.debug_state.world;
+        flags.set(WorldDebugFlags::SHOW_MAIN_GRID, grid_mode >= 1);
+        flags.set(WorldDebugFlags::SHOW_SUB_GRID, grid_mode >= 2);
+        flags.set(WorldDebugFlags::SHOW_MAIN_GRID_TYPES, show_types);
+
+        self.api.world().toggle_debug(self.debug_state.world);
+        self.sync_debug_menu();
+    }
+
+    /// Rebuilds the editor panel after the debug flags were changed from the debug menubar
+    pub(crate) fn editor_debug_flags_changed(&mut self) -> Result<(), CommonError> {
+        self.build_editor_gui()
+    }
+
     fn editor_updates(&mut self) {
         if self.input_flags.contains(GameInputFlags::DRAGGING_VIEW) {
             let delta = self.api.inputs().cursor_position_delta();
-            self.terrain.move_view(-delta.x as f32, -delta.y as f32);
+            let speed = self.gui.slider_value(EDITOR_SCROLL_SPEED).unwrap_or(1.0);
+            self.terrain.move_view(-delta.x as f32 * speed, -delta.y as f32 * speed);
             self.terrain.sync(&self.api);
         }
+
+        self.editor_paint();
+    }
+
+    /// Paints the terrain with the selected brush while the left button is down.
+    /// Only the chunks touched by the brush are sent to the engine.
+    fn editor_paint(&mut self) {
+        let inputs = self.api.inputs();
+        let left_down = inputs.mouse_buttons_value().left_button_down();
+        let left_pressed = left_down && inputs.mouse_buttons().is_some() && !self.cursor_over_gui();
+        let cell = self.terrain.cell_at(inputs.cursor_position_value().as_f32());
+
+        let state = &mut self.editor_state;
+        let terrain = &mut self.terrain;
+        let history = &mut self.editor_history;
+        let (brush, size, terrain_type) = (state.brush, state.brush_size, state.brush_terrain);
+
+        let mut end_stroke = false;
+        match state.paint_start {
+            None if left_pressed => {
+                state.paint_start = Some(cell);
+                state.paint_last = cell;
+                terrain.begin_edit();
+                history.begin_command();
+                match brush {
+                    EditorBrush::Square => terrain.paint_square(cell[0], cell[1], size, terrain_type),
+                    EditorBrush::Circle => terrain.paint_circle(cell[0], cell[1], size, terrain_type),
+                    EditorBrush::Fill => terrain.flood_fill(cell[0], cell[1], terrain_type),
+                    EditorBrush::Rectangle => {}
+                }
+            },
+            None => { return; },
+            Some(start) if !left_down => {
+                if brush == EditorBrush::Rectangle {
+                    terrain.fill_rect(start, cell, terrain_type);
+                }
+
+                end_stroke = true;
+            },
+            Some(_) if cell != state.paint_last => {
+                // Cells are painted along the line from the last cell, so that fast strokes have no gaps
+                let last = state.paint_last;
+                let steps = i32::max((cell[0] - last[0]).abs(), (cell[1] - last[1]).abs());
+                for step in 1..=steps {
+                    let t = (step as f32) / (steps as f32);
+                    let x = (last[0] as f32 + (cell[0] - last[0]) as f32 * t).round() as i32;
+                    let y = (last[1] as f32 + (cell[1] - last[1]) as f32 * t).round() as i32;
+                    match brush {
+                        EditorBrush::Square => terrain.paint_square(x, y, size, terrain_type),
+                        EditorBrush::Circle => terrain.paint_circle(x, y, size, terrain_type),
+                        EditorBrush::Fill | EditorBrush::Rectangle => {}
+                    }
+                }
+
+                state.paint_last = cell;
+            },
+            Some(_) => {}
+        }
+
+        terrain.sync(&self.api);
+
+        if end_stroke {
+            self.editor_end_stroke();
+        }
+    }
+
+    /// Undoes or redoes a command of the history. Returns `false` if the history had nothing to undo or redo.
+    fn editor_history_step(&mut self, undo: bool) -> Result<bool, CommonError> {
+        if self.editor_state.paint_start.is_some() {
+            self.editor_end_stroke();
+        }
+
+        let changed = match undo {
+            true => self.editor_undo(),
+            false => self.editor_redo(),
+        };
+
+        // The editor menu shows the name of the map
+        if changed {
+            self.build_editor_gui()?;
+        }
+
+        Ok(changed)
+    }
+
+    /// Changes a property of the map and adds the change to the history
+    fn editor_set_map_property(&mut self, property: MapProperty, value: String) {
+        let target = match property {
+            MapProperty::Name => &mut self.map.name,
+            MapProperty::Author => &mut self.map.author,
+        };
+
+        if *target != value {
+            let before = ::std::mem::replace(target, value.clone());
+            self.editor_history.push(EditorCommand::Property { property, before, after: value });
+        }
+    }
+
+    /// Ends the brush stroke and adds the painted cells to the history
+    fn editor_end_stroke(&mut self) {
+        self.editor_state.paint_start = None;
+        let edit = self.terrain.end_edit();
+        if !edit.is_empty() {
+            self.editor_history.push(EditorCommand::Terrain(edit));
+        }
     }
 
-    fn init_editor_gui(&mut self) -> Result<(), CommonError> {
-        use crate::gui::{GuiLayoutType, GuiLayoutPosition};
+    pub(crate) fn init_editor_gui(&mut self) -> Result<(), CommonError> {
+        use crate::gui::{GuiLayoutType, GuiLayoutPosition, GuiStyleState};
+        use loomz_shared::{rect, rgb, rgba};
 
         self.gui.build_style(&self.api, |style| {
             style.root_layout(GuiLayoutType::VBox, GuiLayoutPosition::Center);
             super::shared::main_panel_style(style);
+            style.label("file_item", GuiStyleState::Base, "bubblegum", 36.0, rgb(200, 170, 140));
+            style.label("file_item", GuiStyleState::Hovered, "bubblegum", 36.0, rgb(250, 220, 190));
+            style.label("file_status", GuiStyleState::Base, "roboto", 18.0, rgb(200, 170, 140));
+        })?;
+
+        self.context_gui.set_layer(&self.api, CONTEXT_GUI_LAYER);
+        self.context_gui.build_style(&self.api, |style| {
+            style.root_layout(GuiLayoutType::VBox, GuiLayoutPosition::TopLeft);
+            style.frame("context_menu", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(48, 36, 30));
+            style.label("context_item", GuiStyleState::Base, "bubblegum", 30.0, rgb(200, 170, 140));
+            style.label("context_item", GuiStyleState::Hovered, "bubblegum", 30.0, rgb(250, 220, 190));
+        })?;
+
+        self.palette_gui.set_layer(&self.api, PALETTE_GUI_LAYER);
+        self.palette_gui.build_style(&self.api, |style| {
+            style.root_layout(GuiLayoutType::VBox, GuiLayoutPosition::TopLeft);
+            style.frame("palette", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgba(24, 18, 15, 230));
+            style.frame("palette_input_frame", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(48, 36, 30));
+            style.frame("palette_input_frame", GuiStyleState::Hovered, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(58, 44, 36));
+            style.label("palette_title", GuiStyleState::Base, "bubblegum", 28.0, rgb(200, 170, 140));
+            style.label("palette_item", GuiStyleState::Base, "roboto", 20.0, rgb(200, 170, 140));
+            style.label("palette_item", GuiStyleState::Hovered, "roboto", 20.0, rgb(250, 220, 190));
+            style.label("palette_item_selected", GuiStyleState::Base, "roboto", 20.0, rgb(250, 200, 90));
+            style.dropdown("palette_dropdown", GuiStyleState::Base, "roboto", 20.0, rgb(200, 170, 140), "palette_input_frame");
+            style.dropdown("palette_dropdown", GuiStyleState::Hovered, "roboto", 20.0, rgb(220, 190, 160), "palette_input_frame");
+            style.slider("palette_slider", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(48, 36, 30), rgb(200, 170, 140));
+            style.slider("palette_slider", GuiStyleState::Hovered, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(58, 44, 36), rgb(220, 190, 160));
         })
     }
 
-    fn build_editor_gui(&mut self) -> Result<(), CommonError> {
-        use crate::gui::GuiLabelCallback;
+    /// Builds the terrain palette: the terrain types, the brush and the size of the brush
+    pub(crate) fn build_editor_palette(&mut self) -> Result<(), CommonError> {
+        use crate::gui::{GuiLayoutType, GuiLayoutPosition, GuiLabelCallback, GuiDropdownCallback, GuiSliderCallback};
+        use loomz_shared::rect;
 
         let screen_size = self.api.inputs().screen_size_value();
         let view = loomz_shared::RectF32::from_size(screen_size);
+        let brushes = BRUSHES.map(|key| self.strings.get(key) );
+        let state = &self.editor_state;
+        let (brush, brush_size) = (state.brush, state.brush_size);
+        let selected = u32::from(state.brush_terrain) as usize;
+
+        let names: Vec<&str> = self.terrain.types().iter()
+            .map(|(_, info)| self.strings.get_or(&format!("terrain.{}", info.name), &info.name) )
+            .collect();
+        let item_width = PALETTE_WIDTH - PALETTE_PADDING * 2.0;
+        let height = PALETTE_TITLE_HEIGHT * 3.0 + PALETTE_ITEM_HEIGHT * (names.len() + 2) as f32 + PALETTE_PADDING * 2.0;
+
+        self.palette_gui.build(&self.api, &self.strings, &view, |gui| {
+            gui.layout(GuiLayoutType::VBox, GuiLayoutPosition::TopLeft);
+            gui.layout_item(PALETTE_WIDTH, height);
+            gui.layout_item_margin(rect(PALETTE_PADDING, PALETTE_TOP, 0.0, 0.0));
+            gui.layout_item_padding(rect(PALETTE_PADDING, PALETTE_PADDING, PALETTE_PADDING, PALETTE_PADDING));
+            gui.frame("palette", |gui| {
+                gui.layout_item(item_width, PALETTE_TITLE_HEIGHT);
+                gui.label_key("editor.terrain", "palette_title");
+                for (index, name) in names.iter().enumerate() {
+                    let style_key = if index == selected { "palette_item_selected" } else { "palette_item" };
+                    gui.layout_item(item_width, PALETTE_ITEM_HEIGHT);
+                    gui.label_callback(GuiLabelCallback::Click, EDITOR_PALETTE_TERRAIN + index as u64);
+                    gui.label(name, style_key);
+                }
 
-        self.gui.build(&self.api, &view, |gui| {
-            gui.layout_item(screen_size.width, screen_size.height);
+                gui.layout_item(item_width, PALETTE_TITLE_HEIGHT);
+                gui.label_key("editor.brush", "palette_title");
+                gui.layout_item(item_width, PALETTE_ITEM_HEIGHT);
+                gui.dropdown_callback(GuiDropdownCallback::Change, EDITOR_PALETTE_BRUSH);
+                gui.dropdown(&brushes, brush.into(), "palette_dropdown");
+
+                gui.layout_item(item_width, PALETTE_TITLE_HEIGHT);
+                gui.label_key("editor.brush_size", "palette_title");
+                gui.layout_item(item_width, PALETTE_ITEM_HEIGHT);
+                gui.slider_callback(GuiSliderCallback::Change, EDITOR_PALETTE_SIZE);
+                gui.slider(brush_size as f32, 1.0, PALETTE_MAX_BRUSH_SIZE, 1.0, "palette_slider");
+            });
+        })
+    }
+
+    pub(crate) fn build_editor_gui(&mut self) -> Result<(), CommonError> {
+        use crate::gui::{GuiLayoutType, GuiLayoutPosition, GuiSize, GuiLabelCallback, GuiTextInputCallback,
+            GuiCheckboxCallback, GuiSliderCallback, GuiDropdownCallback};
+        use loomz_shared::rect;
+
+        let screen_size = self.api.inputs().screen_size_value();
+        let view = loomz_shared::RectF32::from_size(screen_size);
+        let map_name = self.map.name.clone();
+        let map_name_placeholder = self.strings.get("editor.map_name");
+        let map_path = self.editor_state.map_path.clone();
+        let map_path_placeholder = self.strings.get("editor.map_path");
+        let map_status = self.editor_state.map_status.clone();
+        let grid_modes = GRID_MODES.map(|key| self.strings.get(key) );
+        let scroll_speed = self.gui.slider_value(EDITOR_SCROLL_SPEED).unwrap_or(1.0);
+
+        let flags = self.debug_state.world;
+        let show_types = flags.contains(WorldDebugFlags::SHOW_MAIN_GRID_TYPES);
+        let grid_mode = match (flags.contains(WorldDebugFlags::SHOW_MAIN_GRID), flags.contains(WorldDebugFlags::SHOW_SUB_GRID)) {
+            (_, true) => 2,
+            (true, false) => 1,
+            (false, false) => 0,
+        };
+
+        let rows = self.editor_state.rows;
+
+        self.gui.build(&self.api, &self.strings, &view, |gui| {
+            gui.layout_item_sized(GuiSize::Percent(1.0), GuiSize::Percent(1.0));
             gui.frame("shadow", |gui| {
-                gui.layout_item(400.0, 300.0);
+                gui.layout_spacing(5.0);
+                gui.layout_item(500.0, 580.0);
                 gui.frame("main_panel_style", |gui| {
+                    gui.layout_item(400.0, 50.0);
+                    gui.text_input_callback(GuiTextInputCallback::Change, EDITOR_MAP_NAME_CHANGE);
+                    gui.text_input_callback(GuiTextInputCallback::Submit, EDITOR_MAP_NAME);
+                    gui.text_input(&map_name, map_name_placeholder, 32, "text_input");
+
+                    gui.layout_item(400.0, 50.0);
+                    gui.text_input_callback(GuiTextInputCallback::Change, EDITOR_MAP_PATH);
+                    gui.text_input(&map_path, map_path_placeholder, 128, "text_input");
+
+                    gui.layout_item(400.0, 50.0);
+                    gui.frame("panel_row", |gui| {
+                        gui.layout(GuiLayoutType::HBox, GuiLayoutPosition::Center);
+                        gui.layout_item(130.0, 50.0);
+                        gui.label_callback(GuiLabelCallback::Click, EDITOR_MAP_NEW);
+                        gui.label_key("editor.map_new", "file_item");
+                        gui.label_callback(GuiLabelCallback::Click, EDITOR_MAP_OPEN);
+                        gui.label_key("editor.map_open", "file_item");
+                        gui.label_callback(GuiLabelCallback::Click, EDITOR_MAP_SAVE_AS);
+                        gui.label_key("editor.map_save_as", "file_item");
+                    });
+
+                    gui.layout_item(400.0, 30.0);
+                    gui.label(&map_status, "file_status");
+
+                    gui.layout_item(400.0, 100.0);
+                    gui.scroll_view("panel_scroll", |gui| {
+                        gui.layout(GuiLayoutType::HBox, GuiLayoutPosition::Center);
+                        for row in rows {
+                            gui.layout_item(380.0, 50.0);
+                            gui.frame("panel_row", |gui| {
+                                gui.layout_item_sized(GuiSize::Weight(1.0), GuiSize::Fixed(50.0));
+                                gui.layout_item_margin(rect(10.0, 0.0, 10.0, 0.0));
+
+                                // Row labels can be dragged to reorder the rows
+                                let row_id = row as u64;
+                                gui.label_callback(GuiLabelCallback::DragStart, EDITOR_ROW_DRAG + row_id);
+                                gui.label_callback(GuiLabelCallback::HoverEnter, EDITOR_ROW_ENTER + row_id);
+                                gui.label_callback(GuiLabelCallback::HoverLeave, EDITOR_ROW_LEAVE + row_id);
+                                gui.label_callback(GuiLabelCallback::DragEnd, EDITOR_ROW_DROP + row_id);
+                                gui.label_callback(GuiLabelCallback::RightClick, EDITOR_ROW_CONTEXT + row_id);
+
+                                match row {
+                                    EDITOR_GRID_ROW => {
+                                        gui.label_key("editor.grid", "panel_label");
+                                        gui.layout_item(200.0, 40.0);
+                                        gui.tooltip_key("editor.grid_tooltip");
+                                        gui.dropdown_callback(GuiDropdownCallback::Change, EDITOR_GRID_MODE);
+                                        gui.dropdown(&grid_modes, grid_mode, "dropdown");
+                                    },
+                                    EDITOR_TYPES_ROW => {
+                                        gui.label_key("editor.types", "panel_label");
+                                        gui.layout_item(200.0, 30.0);
+                                        gui.tooltip_key("editor.types_tooltip");
+                                        gui.checkbox_callback(GuiCheckboxCallback::Change, EDITOR_SHOW_TYPES);
+                                        gui.checkbox(show_types, "checkbox");
+                                    },
+                                    _ => {
+                                        gui.label_key("editor.scroll", "panel_label");
+                                        gui.layout_item(200.0, 30.0);
+                                        gui.tooltip_key("editor.scroll_tooltip");
+                                        gui.slider_callback(GuiSliderCallback::Change, EDITOR_SCROLL_SPEED);
+                                        gui.slider(scroll_speed, 0.5, 3.0, 0.25, "slider");
+                                    }
+                                }
+                            });
+                        }
+                    });
+
                     gui.layout_item(300.0, 105.0);
     
                     gui.label_callback(GuiLabelCallback::Click, RETURN_EDITOR);
-                    gui.label("Continue", "menu_item");
+                    gui.label_key("menu.continue", "menu_item");
     
                     gui.label_callback(GuiLabelCallback::Click, EXIT_EDITOR);
-                    gui.label("Exit", "menu_item");
+                    gui.label_key("menu.exit", "menu_item");
                 });
             });
         })?;
 
+        // The editor menu covers the screen. Clicks must not reach the terrain while it is shown.
+        self.gui.set_modal(true);
+
         Ok(())
     }
 
+    /// Console commands of the editor
+    pub(crate) fn register_editor_commands(console: &mut Console) {
+        console.register("world.size", "<width> <height>", "Resizes the terrain. Clears the terrain cells.", Self::console_world_size);
+        console.register("map.new", "<width> <height>", "Replaces the map with an empty map", Self::console_map_new);
+        console.register("map.generate", "<width> <height> [seed] [rivers]", "Replaces the map with a procedural island. The same seed always generates the same map.", Self::console_map_generate);
+        console.register("map.open", "<path>", "Opens a map file. Files ending with .json are read as json.", Self::console_map_open);
+        console.register("map.save", "[path]", "Saves the map. Files ending with .json are written as json.", Self::console_map_save);
+        console.register("map.author", "<name>", "Sets the author of the map", Self::console_map_author);
+        console.register("map.actor", "<pawn|warrior> <x> <y>", "Places an actor of the map in the terrain cell x, y", Self::console_map_actor);
+        console.register("map.remove_actor", "<x> <y>", "Removes the last actor placed in the terrain cell x, y", Self::console_map_remove_actor);
+        console.register("map.spawn_point", "<x> <y>", "Adds a spawn point in the terrain cell x, y", Self::console_map_spawn_point);
+        console.register("map.remove_spawn_point", "<x> <y>", "Removes the spawn point of the terrain cell x, y", Self::console_map_remove_spawn_point);
+        console.register("map.info", "", "Prints the metadata of the map", Self::console_map_info);
+        console.register("undo", "", "Undoes the last change of the map (Ctrl+Z)", |client, _| client.console_history_step(true) );
+        console.register("redo", "", "Redoes the last undone change of the map (Ctrl+Y)", |client, _| client.console_history_step(false) );
+        console.register("editor.history_depth", "<depth>", "Sets the number of changes that can be undone", Self::console_history_depth);
+    }
+
+    fn console_world_size(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(2)?;
+        let width: u32 = args.parse(0)?;
+        let height: u32 = args.parse(1)?;
+        if width == 0 || height == 0 || width > MAP_MAX_SIZE || height > MAP_MAX_SIZE {
+            return Err(client_err!("world.size: the size must be between 1 and {MAP_MAX_SIZE}"));
+        }
+
+        // The history refers to the chunks of the old terrain
+        self.editor_history.clear();
+        self.terrain.set_world_size(width, height);
+        self.terrain.sync(&self.api);
+
+        let removed = self.map.remove_outside(self.terrain.size());
+        if removed > 0 {
+            self.console.print(&format!("Removed {removed} actors and spawn points outside of the new size"));
+        }
+
+        Ok(())
+    }
+
+    fn console_map_new(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(2)?;
+        self.new_map(args.parse(0)?, args.parse(1)?)?;
+        self.editor_map_changed()
+    }
+
+    fn console_map_generate(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        if args.get(1).is_none() || args.get(4).is_some() {
+            return Err(args.usage_err("expected between 2 and 4 arguments"));
+        }
+
+        let seed = match args.get(2) {
+            Some(_) => args.parse(2)?,
+            None => time_seed(),
+        };
+
+        let (width, height) = (args.parse(0)?, args.parse(1)?);
+        let rivers = match args.get(3) {
+            Some(_) => args.parse(3)?,
+            None => DEFAULT_RIVERS,
+        };
+
+        let max_rivers = TerrainGenerator::max_rivers(width, height);
+        if rivers > max_rivers {
+            return Err(args.usage_err(&format!("a map of this size has at most {max_rivers} rivers")));
+        }
+
+        self.generate_map(width, height, seed, rivers)?;
+        self.console.print(&format!("Generated map with seed {seed}"));
+        self.editor_map_changed()
+    }
+
+    fn console_map_open(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(1)?;
+        self.open_map(args.str(0)?)?;
+        self.console.print(&format!("Opened {}", self.map.path));
+        self.editor_map_changed()
+    }
+
+    fn console_map_save(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        let path = match args.get(0) {
+            Some(path) => path.to_string(),
+            None => self.map.path.clone(),
+        };
+
+        self.save_map(&path)?;
+        self.console.print(&format!("Saved {}", self.map.path));
+        self.editor_map_changed()
+    }
+
+    fn console_map_author(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(1)?;
+        self.editor_set_map_property(MapProperty::Author, args.str(0)?.to_string());
+        self.editor_history.seal();
+        Ok(())
+    }
+
+    fn console_map_actor(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(3)?;
+        let kind = MapActorKind::from_name(args.str(0)?)
+            .ok_or_else(|| args.usage_err(&format!("unknown actor {:?}", args.get(0).unwrap_or(""))) )?;
+        let (x, y) = self.console_map_cell(args, 1)?;
+        let actor = MapActor { kind, x, y };
+        self.map.actors.push(actor);
+        self.editor_history.push(EditorCommand::PlaceActor { index: self.map.actors.len() - 1, actor });
+        Ok(())
+    }
+
+    fn console_map_remove_actor(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(2)?;
+        let (x, y) = self.console_map_cell(args, 0)?;
+        let index = self.map.actors.iter().rposition(|actor| actor.x == x && actor.y == y )
+            .ok_or_else(|| args.usage_err(&format!("no actor in cell {x}, {y}")) )?;
+
+        let actor = self.map.actors.remove(index);
+        self.editor_history.push(EditorCommand::RemoveActor { index, actor });
+        Ok(())
+    }
+
+    fn console_map_spawn_point(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(2)?;
+        let (x, y) = self.console_map_cell(args, 0)?;
+        self.map.spawn_points.push([x, y]);
+        self.editor_history.push(EditorCommand::PlaceSpawnPoint { index: self.map.spawn_points.len() - 1, point: [x, y] });
+        Ok(())
+    }
+
+    fn console_map_remove_spawn_point(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(2)?;
+        let (x, y) = self.console_map_cell(args, 0)?;
+        let index = self.map.spawn_points.iter().rposition(|&point| point == [x, y] )
+            .ok_or_else(|| args.usage_err(&format!("no spawn point in cell {x}, {y}")) )?;
+
+        let point = self.map.spawn_points.remove(index);
+        self.editor_history.push(EditorCommand::RemoveSpawnPoint { index, point });
+        Ok(())
+    }
+
+    fn console_history_step(&mut self, undo: bool) -> Result<(), CommonError> {
+        if !self.editor_history_step(undo)? {
+            self.console.print(if undo { "Nothing to undo" } else { "Nothing to redo" });
+        }
+
+        Ok(())
+    }
+
+    fn console_history_depth(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(1)?;
+        let depth: usize = args.parse(0)?;
+        if depth == 0 {
+            return Err(args.usage_err("the depth must be at least 1"));
+        }
+
+        self.editor_history.set_depth(depth);
+        Ok(())
+    }
+
+    fn console_map_info(&mut self, _args: &ConsoleArgs) -> Result<(), CommonError> {
+        let map = &self.map;
+        let size = self.terrain.size();
+        let info = format!(
+            "{:?} by {:?}\nFile: {:?}\nSize: {}x{}\nActors: {}\nSpawn points: {}",
+            map.name, map.author, map.path, size.width, size.height, map.actors.len(), map.spawn_points.len()
+        );
+
+        self.console.print(&info);
+        Ok(())
+    }
+
+    /// Parses the terrain cell at `index` and `index+1` in the arguments
+    pub(crate) fn console_map_cell(&self, args: &ConsoleArgs, index: usize) -> Result<(u32, u32), CommonError> {
+        let x: i32 = args.parse(index)?;
+        let y: i32 = args.parse(index+1)?;
+        match self.terrain.contains_cell(x, y) {
+            true => Ok((x as u32, y as u32)),
+            false => Err(args.usage_err(&format!("cell {x}, {y} is outside of the terrain")))
+        }
+    }
+
+    /// Shows the metadata of the map in the editor menu after the map was changed from the console
+    fn editor_map_changed(&mut self) -> Result<(), CommonError> {
+        self.editor_state.map_path = self.map.path.clone();
+        match self.state {
+            GameState::Editor => self.build_editor_gui(),
+            _ => Ok(())
+        }
+    }
+
+    /// Keeps the map that is already loaded. Creates a new map if the terrain is empty.
     fn init_editor_terrain(&mut self) -> Result<(), CommonError> {
         let screen_size = self.api.inputs().screen_size_value();
         self.terrain.set_view(0.0, 0.0, screen_size.width, screen_size.height);
-        self.terrain.set_world_size(16, 16);
-        //self.terrain.set_cells(0, 0, 31, 1, &[loomz_shared::api::TerrainType::Sand; 31]);
+        if self.terrain.size().width == 0 {
+            self.terrain.set_world_size(EDITOR_NEW_MAP_SIZE, EDITOR_NEW_MAP_SIZE);
+        }
+
         self.terrain.sync(&self.api);
         Ok(())
     }
 
 }
+
+impl Default for EditorState {
+    fn default() -> Self {
+        EditorState {
+            rows: [EDITOR_GRID_ROW, EDITOR_TYPES_ROW, EDITOR_SCROLL_ROW],
+            drag_row: u32::MAX,
+            drop_row: u32::MAX,
+            context_row: u32::MAX,
+            brush: EditorBrush::Square,
+            brush_size: 1,
+            brush_terrain: TerrainType::default(),
+            paint_start: None,
+            paint_last: [0, 0],
+            map_path: String::new(),
+            map_status: String::new(),
+            text_focus: false,
+        }
+    }
+}
+
+impl StoreAndLoad for EditorState {
+    fn load(reader: &mut SaveFileReaderBase) -> Self {
+        let mut state = EditorState::default();
+        for row in state.rows.iter_mut() {
+            *row = reader.read_u32();
+        }
+
+        state.brush = reader.read_from_u32();
+        state.brush_size = reader.read_u32();
+        state.brush_terrain = reader.read_from_u32();
+        state.map_path = reader.read_str().to_string();
+
+        state
+    }
+
+    fn store(&self, writer: &mut SaveFileWriterBase) {
+        for &row in self.rows.iter() {
+            writer.write_u32(row);
+        }
+
+        writer.write_into_u32(self.brush);
+        writer.write_u32(self.brush_size);
+        writer.write_into_u32(self.brush_terrain);
+        writer.write_str(&self.map_path);
+    }
+}
+
+impl From<u32> for EditorBrush {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => EditorBrush::Circle,
+            2 => EditorBrush::Fill,
+            3 => EditorBrush::Rectangle,
+            _ => EditorBrush::Square,
+        }
+    }
+}
+
+impl From<EditorBrush> for u32 {
+    fn from(value: EditorBrush) -> Self {
+        value as u32
+    }
+}
@@ -1,6 +1,9 @@
 use loomz_shared::base_types::rect;
+use loomz_shared::api::TERRAIN_CELL_SIZE_PX;
 use loomz_shared::inputs::keys;
 use loomz_shared::CommonError;
+use crate::console::{Console, ConsoleArgs};
+use crate::map::{MapActor, MapActorKind};
 use crate::{GameState, LoomzClient};
 
 const RETURN_GAMEPLAY: u64 = 300;
@@ -9,13 +12,36 @@ const EXIT_GAMEPLAY: u64 = 301;
 
 impl LoomzClient {
 
+    /// Starts the game on the loaded map
     pub(crate) fn init_gameplay(&mut self) -> Result<(), CommonError> {
+        self.leave_editor();
         self.init_gameplay_gui()?;
+        self.init_gameplay_map();
         self.api.world().toggle_world(true);
         self.state = GameState::Game;
         Ok(())
     }
 
+    /// Starts the game on the map saved in `path`
+    pub(crate) fn init_gameplay_from_file(&mut self, path: &str) -> Result<(), CommonError> {
+        self.open_map(path)?;
+        self.init_gameplay()
+    }
+
+    /// Creates the actors of the map and centers the view on the first spawn point
+    fn init_gameplay_map(&mut self) {
+        self.spawn_map_actors();
+
+        if let Some(&[x, y]) = self.map.spawn_points.first() {
+            let cell_size = TERRAIN_CELL_SIZE_PX as f32;
+            let screen_size = self.api.inputs().screen_size_value();
+            let center_x = (x as f32 + 0.5) * cell_size;
+            let center_y = (y as f32 + 0.5) * cell_size;
+            self.terrain.set_view(center_x - screen_size.width * 0.5, center_y - screen_size.height * 0.5, screen_size.width, screen_size.height);
+            self.terrain.sync(&self.api);
+        }
+    }
+
     pub(crate) fn gameplay(&mut self) -> Result<(), CommonError> {
         self.gameplay_updates();
 
@@ -29,9 +55,10 @@ impl LoomzClient {
     }
 
     fn gameplay_updates(&mut self) {
+        let esc_consumed = self.gui_consumed_key(keys::ESC);
         if let Some(keystate) = self.api.keys_ref().read_updates() {
             let size = self.api.inputs_ref().screen_size_value();
-            if keystate.just_pressed(keys::ESC) {
+            if keystate.just_pressed(keys::ESC) && !esc_consumed {
                 self.gui.resize(&self.api, &rect(0.0, 0.0, size.width, size.height));
                 self.gui.toggle(&self.api, !self.gui.visible());
             }
@@ -40,9 +67,32 @@ impl LoomzClient {
         ()
     }
 
-    fn gameplay_gui_events(&mut self) -> Result<(), CommonError> {
-        self.gui.read_inputs(&self.api);
+    /// Console commands of the gameplay
+    pub(crate) fn register_gameplay_commands(console: &mut Console) {
+        console.register("spawn", "<pawn|warrior> <x> <y>", "Spawns an actor in the terrain cell x, y", Self::console_spawn);
+        console.register("map.play", "[path]", "Starts the game on a map file, or on the loaded map", Self::console_map_play);
+    }
+
+    fn console_map_play(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        match args.get(0) {
+            Some(path) => self.init_gameplay_from_file(path),
+            None => self.init_gameplay(),
+        }
+    }
+
+    fn console_spawn(&mut self, args: &ConsoleArgs) -> Result<(), CommonError> {
+        args.expect_count(3)?;
+        let kind = MapActorKind::from_name(args.str(0)?)
+            .ok_or_else(|| args.usage_err(&format!("unknown actor {:?}", args.get(0).unwrap_or(""))) )?;
+        let (x, y) = self.console_map_cell(args, 1)?;
+
+        self.spawn_actor(MapActor { kind, x, y });
+        self.console.print(&format!("Spawned {} at {}, {}", kind.name(), x, y));
 
+        Ok(())
+    }
+
+    fn gameplay_gui_events(&mut self) -> Result<(), CommonError> {
         while let Some(event) = self.gui.next_event() {
             match event {
                 RETURN_GAMEPLAY => { self.gui.toggle(&self.api, false); },
@@ -107,7 +157,7 @@ impl LoomzClient {
         // self.target_position = start_position;
     }
 
-    fn init_gameplay_gui(&mut self) -> Result<(), CommonError> {
+    pub(crate) fn init_gameplay_gui(&mut self) -> Result<(), CommonError> {
         use crate::gui::{GuiLayoutType, GuiLabelCallback, GuiLayoutPosition};
 
         let screen_size = self.api.inputs().screen_size_value();
@@ -120,17 +170,17 @@ impl LoomzClient {
             super::shared::main_panel_style(style);
         })?;
 
-        self.gui.build(&self.api, &view, |gui| {
+        self.gui.build(&self.api, &self.strings, &view, |gui| {
             gui.layout(GuiLayoutType::VBox, GuiLayoutPosition::Center);
             gui.layout_item(400.0, 300.0);
             gui.frame("main_panel_style", |gui| {
                 gui.layout_item(300.0, 100.0);
 
                 gui.label_callback(GuiLabelCallback::Click, RETURN_GAMEPLAY);
-                gui.label("Continue", "menu_item");
+                gui.label_key("menu.continue", "menu_item");
 
                 gui.label_callback(GuiLabelCallback::Click, EXIT_GAMEPLAY);
-                gui.label("Exit", "menu_item");
+                gui.label_key("menu.exit", "menu_item");
             });
         })?;
 
@@ -1,13 +1,20 @@
 use loomz_shared::CommonError;
+use crate::terrain::generator::{time_seed, DEFAULT_RIVERS};
 use crate::{LoomzClient, GameState};
 
 const START_GAME: u64 = 100;
 const START_SANDBOX: u64 = 101;
 const EXIT_GAME: u64 = 102;
+const START_RANDOM_MAP: u64 = 103;
+
+/// Width and height of the maps generated by the "Random map" option, in cells
+const RANDOM_MAP_SIZE: u32 = 64;
 
 impl LoomzClient {
 
     pub(crate) fn init_main_menu(&mut self) -> Result<(), CommonError> {
+        self.leave_editor();
+        self.despawn_map_actors();
         self.init_main_menu_menu()?;
         self.api.world().toggle_world(false);
         self.state = GameState::MainMenu;
@@ -16,17 +23,20 @@ impl LoomzClient {
     }
 
     pub(crate) fn main_menu(&mut self) -> Result<(), CommonError> {
+        if self.gui.json_changed(&self.api) {
+            self.init_main_menu_menu()?;
+        }
+
         self.main_menu_gui_events()?;
         Ok(())
     }
 
     fn main_menu_gui_events(&mut self) -> Result<(), CommonError> {
-        self.gui.read_inputs(&self.api);
-
         while let Some(event) = self.gui.next_event() {
             match event {
                 START_GAME => { self.init_gameplay()?; },
                 START_SANDBOX => { self.init_editor()?; },
+                START_RANDOM_MAP => { self.start_random_map()?; },
                 EXIT_GAME => { self.api.exit(); },
                 _ => {}
             }
@@ -35,33 +45,20 @@ impl LoomzClient {
         Ok(())
     }
 
-    fn init_main_menu_menu(&mut self) -> Result<(), CommonError> {
-        use crate::gui::{GuiLayoutType, GuiLabelCallback, GuiLayoutPosition};
+    fn start_random_map(&mut self) -> Result<(), CommonError> {
+        let seed = time_seed();
+        self.generate_map(RANDOM_MAP_SIZE, RANDOM_MAP_SIZE, seed, DEFAULT_RIVERS)?;
+        self.console.print(&format!("Generated map with seed {seed}"));
+        self.init_gameplay()
+    }
 
+    pub(crate) fn init_main_menu_menu(&mut self) -> Result<(), CommonError> {
         let screen_size = self.api.inputs().screen_size_value();
         let view = loomz_shared::RectF32::from_size(screen_size);
+        let callbacks = [("start_game", START_GAME), ("editor", START_SANDBOX), ("random_map", START_RANDOM_MAP), ("exit", EXIT_GAME)];
 
-        self.gui.build_style(&self.api, |style| {
-            style.root_layout(GuiLayoutType::VBox, GuiLayoutPosition::Center);
-            super::shared::main_panel_style(style);
-        })?;
-
-        self.gui.build(&self.api, &view, |gui| {
-            gui.layout(GuiLayoutType::VBox, GuiLayoutPosition::Center);
-            gui.layout_item(500.0, 440.0);
-            gui.frame("main_panel_style", |gui| {
-                gui.layout_item(300.0, 110.0);
-
-                gui.label_callback(GuiLabelCallback::Click, START_GAME);
-                gui.label("New Game", "menu_item");
-
-                gui.label_callback(GuiLabelCallback::Click, START_SANDBOX);
-                gui.label("Editor", "menu_item");
-
-                gui.label_callback(GuiLabelCallback::Click, EXIT_GAME);
-                gui.label("Exit", "menu_item");
-            });
-        })
+        self.gui.build_style_from_json(&self.api, "main_menu_gui")?;
+        self.gui.build_from_json(&self.api, &self.strings, &view, "main_menu_gui", &callbacks)
     }
 
 }
@@ -1,10 +1,27 @@
-use loomz_shared::{rect, rgb, rgba};
-use crate::gui::{GuiStyleBuilder, GuiStyleState};
+use loomz_shared::{rect, rgb, rgba, pos};
+use crate::gui::{GuiStyleBuilder, GuiStyleState, GuiEasing};
 
 pub fn main_panel_style(style: &mut GuiStyleBuilder) {
     style.label("menu_item", GuiStyleState::Base, "bubblegum", 90.0, rgb(71, 43, 26));
     style.label("menu_item", GuiStyleState::Hovered, "bubblegum", 90.0, rgb(71, 26, 26));
     style.label("menu_item", GuiStyleState::Selected, "bubblegum", 90.0, rgb(110, 34, 34));
-    style.frame("main_panel_style", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(24, 18, 15));
+    style.transition("menu_item", 0.15, GuiEasing::EaseOut, 1.05);
+    style.nine_slice_frame("main_panel_style", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rect(1.0, 1.0, 1.0, 1.0), rgb(24, 18, 15));
     style.frame("shadow", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgba(0, 0, 0, 200));
+    style.frame("text_input_frame", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(48, 36, 30));
+    style.frame("text_input_frame", GuiStyleState::Hovered, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(58, 44, 36));
+    style.text_input("text_input", GuiStyleState::Base, "bubblegum", 40.0, rgb(200, 170, 140), "text_input_frame");
+    style.text_input("text_input", GuiStyleState::Hovered, "bubblegum", 40.0, rgb(220, 190, 160), "text_input_frame");
+    style.label("panel_label", GuiStyleState::Base, "bubblegum", 40.0, rgb(200, 170, 140));
+    style.frame("panel_row", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgba(0, 0, 0, 0));
+    style.scroll_view("panel_scroll", GuiStyleState::Base, "panel_row", rgb(48, 36, 30), rgb(200, 170, 140));
+    style.checkbox("checkbox", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(48, 36, 30), rgb(200, 170, 140));
+    style.checkbox("checkbox", GuiStyleState::Hovered, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(58, 44, 36), rgb(220, 190, 160));
+    style.slider("slider", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(48, 36, 30), rgb(200, 170, 140));
+    style.slider("slider", GuiStyleState::Hovered, "gui", rect(0.0, 0.0, 2.0, 2.0), rgb(58, 44, 36), rgb(220, 190, 160));
+    style.dropdown("dropdown", GuiStyleState::Base, "bubblegum", 30.0, rgb(200, 170, 140), "text_input_frame");
+    style.dropdown("dropdown", GuiStyleState::Hovered, "bubblegum", 30.0, rgb(220, 190, 160), "text_input_frame");
+    style.frame("tooltip_frame", GuiStyleState::Base, "gui", rect(0.0, 0.0, 2.0, 2.0), rgba(20, 15, 12, 230));
+    style.tooltip("roboto", 18.0, rgb(220, 200, 180), "tooltip_frame", 6.0, 0.5);
+    style.toggle_transition(0.2, GuiEasing::EaseOut, true, pos(0.0, -40.0));
 }
@@ -2,44 +2,66 @@ use loomz_shared::store::{SaveFileReaderBase, SaveFileWriterBase};
 use loomz_shared::{save_err, CommonError};
 
 const MAGIC: u32 = 0x6FAA7601;
+/// Version of the session format. Sessions saved to a file can be loaded by another build, so sessions of another version are rejected.
+/// Increase it when the layout of a stored `StoreAndLoad` type changes.
+const SESSION_VERSION: u32 = 2;
 const ALIGN: usize = size_of::<u32>();
 
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct SaveFileHeader {
     pub magic: u32,
+    pub version: u32,
     pub size: u32,
 }
 
 impl SaveFileHeader {
-    pub fn new() -> Self {
+    pub fn new(magic: u32, version: u32) -> Self {
         SaveFileHeader {
-            magic: MAGIC,
+            magic,
+            version,
             size: 0,
         }
     }
 }
 
 pub struct SaveFileReader<'a> {
-    base: SaveFileReaderBase<'a>
+    base: SaveFileReaderBase<'a>,
+    version: u32,
 }
 
 impl<'a> SaveFileReader<'a> {
 
+    /// Reads a session file. Fails if the session was written with another version of the session format.
     pub fn new(bytes: &'a Box<[u8]>) -> Result<SaveFileReader<'a>, CommonError> {
+        let reader = Self::with_magic(bytes, MAGIC)?;
+        if reader.version != SESSION_VERSION {
+            return Err(save_err!("Session version {} does not match the current version {SESSION_VERSION}", reader.version));
+        }
+
+        Ok(reader)
+    }
+
+    /// Reads a file written by `SaveFileWriter::with_magic`. Fails if the magic of the file does not match `magic`.
+    pub fn with_magic(bytes: &'a [u8], magic: u32) -> Result<SaveFileReader<'a>, CommonError> {
         let data = Self::validate_data_integrity(bytes)?;
         let mut reader = SaveFileReader {
             base: SaveFileReaderBase::new(data),
+            version: 0,
         };
 
-        reader.validate_header()?;
+        reader.version = reader.validate_header(magic)?;
         reader.current_offset = size_of::<SaveFileHeader>() / ALIGN;
 
         Ok(reader)
     }
 
-    fn validate_data_integrity(bytes: &Box<[u8]>) -> Result<&[u32], CommonError> {
-        let byte_slice: &[u8] = bytes.as_ref();
+    /// Version written in the header of the file
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn validate_data_integrity(byte_slice: &[u8]) -> Result<&[u32], CommonError> {
         if byte_slice.len() < size_of::<SaveFileHeader>() {
             return Err(save_err!("Data is smaller than the save file header size"));
         }
@@ -52,11 +74,11 @@ impl<'a> SaveFileReader<'a> {
         unsafe { Ok(byte_slice.align_to::<u32>().1) }
     }
 
-    fn validate_header(&self) -> Result<(), CommonError> {
+    fn validate_header(&self, magic: u32) -> Result<u32, CommonError> {
         let header_ptr = self.base.data.as_ptr() as *const SaveFileHeader;
         let header = unsafe { ::std::ptr::read(header_ptr) };
         
-        if header.magic != MAGIC {
+        if header.magic != magic {
             return Err(save_err!("Decoder header magic does not match"));
         }
 
@@ -64,7 +86,7 @@ impl<'a> SaveFileReader<'a> {
             return Err(save_err!("Header data size does not match buffer size"));
         }
 
-        Ok(())
+        Ok(header.version)
     }
 
 }
@@ -74,20 +96,22 @@ pub struct SaveFileWriter {
 }
 
 impl SaveFileWriter {
+    /// Writes a session file
     pub fn new() -> Self {
+        Self::with_magic(MAGIC, SESSION_VERSION)
+    }
+
+    /// Writes a file identified by `magic`. `version` is returned by `SaveFileReader::version` when the file is read.
+    pub fn with_magic(magic: u32, version: u32) -> Self {
         let mut writer = SaveFileWriter {
             base: SaveFileWriterBase::new(3000)
         };
 
-        writer.write_header();
+        writer.write(&SaveFileHeader::new(magic, version));
 
         writer
     }
 
-    fn write_header(&mut self) {
-        self.write(&SaveFileHeader::new());        
-    }
-
     pub fn finalize(mut self) -> Vec<u8> {
         let total_size = self.data_offset;
 
@@ -130,3 +154,37 @@ impl<'a> ::std::ops::DerefMut for SaveFileReader<'a> {
         &mut self.base
     }
 }
+
+#[cfg(test)]
+mod store_tests {
+    use super::*;
+
+    #[test]
+    fn session_version() {
+        let mut writer = SaveFileWriter::new();
+        writer.write_u32(7);
+        let bytes = writer.finalize().into_boxed_slice();
+        assert!(SaveFileReader::new(&bytes).unwrap().read_u32() == 7);
+
+        let mut writer = SaveFileWriter::with_magic(MAGIC, SESSION_VERSION - 1);
+        writer.write_u32(7);
+        let bytes = writer.finalize().into_boxed_slice();
+        assert!(SaveFileReader::new(&bytes).is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        let mut writer = SaveFileWriter::new();
+        writer.write_str("hello");
+        writer.write_u32(1_000_000);
+        let bytes = writer.finalize().into_boxed_slice();
+
+        let mut reader = SaveFileReader::new(&bytes).unwrap();
+        assert!(reader.read_str() == "hello");
+        assert!(reader.read_len(1) == 0);
+        assert!(reader.overflowed());
+        assert!(reader.read_str().is_empty());
+        assert!(reader.read_slice::<u32>().is_empty());
+        assert!(reader.read_u32() == 0);
+    }
+}
@@ -1,6 +1,9 @@
 use bitflags::bitflags;
-use loomz_shared::api::{TerrainType, WorldTerrainChunk, TERRAIN_CHUNK_STRIDE};
-use loomz_shared::{LoomzApi, RectF32, SizeU32, rect};
+use loomz_shared::api::{TerrainType, TerrainNeighbors, TerrainChunk, WorldTerrainChunk, TERRAIN_CHUNK_STRIDE, TERRAIN_CELL_SIZE_PX};
+use loomz_shared::assets::terrain::{TerrainTypes, TERRAIN_TYPES_ASSET};
+use loomz_shared::{assets_err, CommonError, LoomzApi, PositionF32, RectF32, SizeU32, rect};
+
+pub(crate) mod generator;
 
 bitflags! {
     #[derive(Copy, Clone, Default)]
@@ -10,18 +13,142 @@ bitflags! {
     }
 }
 
+/// A cell changed by an edit. `index` is the index of the cell in its chunk, row by row.
+#[derive(Copy, Clone, Debug)]
+pub struct TerrainCellDiff {
+    pub index: u16,
+    pub before: TerrainType,
+    pub after: TerrainType,
+}
+
+#[derive(Clone, Debug)]
+pub struct TerrainChunkDiff {
+    pub chunk: usize,
+    pub cells: Vec<TerrainCellDiff>,
+}
+
+/// Cells changed between `Terrain::begin_edit` and `Terrain::end_edit`, grouped by chunk
+#[derive(Clone, Default, Debug)]
+pub struct TerrainEdit {
+    chunks: Vec<TerrainChunkDiff>,
+}
+
+impl TerrainEdit {
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Adds the changes of `other`, made after the changes of this edit
+    pub fn merge(&mut self, other: TerrainEdit) {
+        for chunk in other.chunks {
+            let target = match self.chunks.iter_mut().find(|target| target.chunk == chunk.chunk ) {
+                Some(target) => target,
+                None => {
+                    self.chunks.push(chunk);
+                    continue;
+                }
+            };
+
+            for cell in chunk.cells {
+                match target.cells.iter_mut().find(|target| target.index == cell.index ) {
+                    Some(target) => { target.after = cell.after; },
+                    None => { target.cells.push(cell); }
+                }
+            }
+        }
+    }
+
+}
+
+/// Set of indices stored as bits. Grows when an index past the end is inserted.
+#[derive(Default)]
+struct IndexSet {
+    words: Vec<u64>,
+}
+
+impl IndexSet {
+
+    fn with_len(len: usize) -> Self {
+        IndexSet { words: vec![0; len.div_ceil(64)] }
+    }
+
+    /// Returns `true` if `index` was not in the set
+    fn insert(&mut self, index: usize) -> bool {
+        let word = index / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+
+        let bit = 1 << (index % 64);
+        let inserted = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        inserted
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+}
+
 pub struct Terrain {
     /// Terrain cells
     batches: Vec<WorldTerrainChunk>,
     /// Indices of the batches that were updated 
     batches_updates: Vec<usize>,    
+    /// Same indices as `batches_updates`, to check if a batch is already updated
+    batches_updated: IndexSet,
     view: RectF32,
     size: SizeU32,
     flags: TerrainUpdateFlags,
+    /// Terrain types loaded from the terrain json. Not saved with the terrain.
+    types: TerrainTypes,
+    /// Cells of the chunks modified since `begin_edit`, before their first modification. Not saved with the terrain.
+    edit_snapshots: Option<Vec<(usize, TerrainChunk<TerrainType>)>>,
+    /// Indices of the chunks in `edit_snapshots`
+    edit_recorded: IndexSet,
 }
 
 impl Terrain {
 
+    /// Loads the terrain types. Cells with a type that does not exist anymore are reset to the default terrain.
+    pub fn load_types(&mut self, api: &LoomzApi) -> Result<(), CommonError> {
+        let source = api.assets_ref().json_by_name(TERRAIN_TYPES_ASSET)
+            .ok_or_else(|| assets_err!("Failed to find json {TERRAIN_TYPES_ASSET:?}") )?;
+
+        self.types = TerrainTypes::from_json(source.as_str())?;
+
+        let count = self.types.len();
+        let mut reset = false;
+        for batch_index in 0..self.batches.len() {
+            let unknown_cells = self.batches[batch_index].cells.iter_mut()
+                .flat_map(|row| row.iter_mut() )
+                .filter(|cell| (cell.0 as usize) >= count );
+
+            let mut batch_reset = false;
+            for cell in unknown_cells {
+                *cell = TerrainType::default();
+                batch_reset = true;
+            }
+
+            if batch_reset {
+                self.mark_updated(batch_index);
+                reset = true;
+            }
+        }
+
+        if reset {
+            self.update_neighbors([0, 0], [self.size.width as i32 - 1, self.size.height as i32 - 1]);
+        }
+
+        Ok(())
+    }
+
+    pub fn types(&self) -> &TerrainTypes {
+        &self.types
+    }
+
     // Resize the world. Clears all the existing data.
     pub fn set_world_size(&mut self, width: u32, height: u32) {
         self.size.width = width;
@@ -31,11 +158,13 @@ impl Terrain {
         let batch_y = ((height as usize) + (TERRAIN_CHUNK_STRIDE-1)) / TERRAIN_CHUNK_STRIDE;
         self.batches.clear();
         self.batches_updates.clear();
+        self.batches_updated.clear();
+        self.edit_snapshots = None;
         self.flags |= TerrainUpdateFlags::UPDATE_SIZE;
 
         for y in 0..batch_y {
             for x in 0..batch_x {
-                self.batches_updates.push(self.batches.len());
+                self.mark_updated(self.batches.len());
                 self.batches.push(WorldTerrainChunk::new(x, y))
             }
         }
@@ -56,6 +185,14 @@ impl Terrain {
         self.flags |= TerrainUpdateFlags::UPDATE_VIEW;
     }
 
+    // Sends the world size, the view and every batch to the engine on the next sync
+    pub fn invalidate(&mut self) {
+        self.flags = TerrainUpdateFlags::all();
+        for batch_index in 0..self.batches.len() {
+            self.mark_updated(batch_index);
+        }
+    }
+
     // Copy cells into the target rect. Cells buffer must match the rect
     pub fn set_cells(&mut self, x: u32, y: u32, width: u32, height: u32, cells: &[TerrainType]) {
         let total_cells = (width * height) as usize;
@@ -65,7 +202,7 @@ impl Terrain {
         assert!(x + width <= self.size.width && y + height <= self.size.height, "Rectangle is out of terrain range");
 
         let chunk_stride = TERRAIN_CHUNK_STRIDE as u32;
-        let stride = self.size.width / chunk_stride;
+        let stride = self.batches_per_row();
         let mut cell_index = 0usize;
 
         for cell_y in y..(y+height) {
@@ -77,9 +214,8 @@ impl Terrain {
             while cells_to_copy > 0 {
                 let batch_x = cell_x / chunk_stride;
                 let batch_index = ((batch_y * stride) + batch_x) as usize;
-                if !self.batches_updates.iter().any(|&i| i == batch_index) {
-                    self.batches_updates.push(batch_index);
-                }
+                self.record_chunk(batch_index);
+                self.mark_updated(batch_index);
 
                 let local_x = cell_x - (batch_x * chunk_stride);
                 let cell_count = u32::min(chunk_stride - local_x, cells_to_copy) as usize; 
@@ -99,20 +235,254 @@ impl Terrain {
                 cell_x += cell_count as u32;
             }
         }
+
+        let [x, y] = [x as i32, y as i32];
+        self.update_neighbors([x - 1, y - 1], [x + width as i32, y + height as i32]);
+    }
+
+    pub fn size(&self) -> SizeU32 {
+        self.size
+    }
+
+    /// Returns a copy of every cell, row by row
+    pub fn cells(&self) -> Vec<TerrainType> {
+        let mut cells = Vec::with_capacity((self.size.width * self.size.height) as usize);
+        for y in 0..self.size.height {
+            for x in 0..self.size.width {
+                cells.push(self.get_cell(x, y));
+            }
+        }
+
+        cells
+    }
+
+    pub fn get_cell(&self, x: u32, y: u32) -> TerrainType {
+        let (batch_index, local_x, local_y) = self.cell_location(x, y);
+        self.batches[batch_index].cells[local_y][local_x]
+    }
+
+    /// Returns the cell under `position`, in pixels on screen. The cell can be outside of the terrain.
+    pub fn cell_at(&self, position: PositionF32) -> [i32; 2] {
+        let cell_size = TERRAIN_CELL_SIZE_PX as f32;
+        let x = ((self.view.left + position.x) / cell_size).floor() as i32;
+        let y = ((self.view.top + position.y) / cell_size).floor() as i32;
+        [x, y]
+    }
+
+    pub fn contains_cell(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as u32) < self.size.width && (y as u32) < self.size.height
+    }
+
+    /// Sets the cell at `x`, `y`. Cells outside of the terrain are ignored.
+    pub fn set_cell(&mut self, x: i32, y: i32, cell: TerrainType) {
+        if !self.contains_cell(x, y) {
+            return;
+        }
+
+        let (batch_index, local_x, local_y) = self.cell_location(x as u32, y as u32);
+        if self.batches[batch_index].cells[local_y][local_x] != cell {
+            self.record_chunk(batch_index);
+            self.batches[batch_index].cells[local_y][local_x] = cell;
+            self.mark_updated(batch_index);
+            self.update_neighbors([x - 1, y - 1], [x + 1, y + 1]);
+        }
+    }
+
+    /// Sets the cells of a `size` x `size` square centered on `x`, `y`
+    pub fn paint_square(&mut self, x: i32, y: i32, size: u32, cell: TerrainType) {
+        let size = size.max(1) as i32;
+        let [left, top] = [x - (size - 1) / 2, y - (size - 1) / 2];
+        self.fill_rect([left, top], [left + size - 1, top + size - 1], cell);
+    }
+
+    /// Sets the cells of a circle of diameter `size` centered on `x`, `y`
+    pub fn paint_circle(&mut self, x: i32, y: i32, size: u32, cell: TerrainType) {
+        let size = size.max(1) as i32;
+        let [left, top] = [x - (size - 1) / 2, y - (size - 1) / 2];
+
+        // The cells whose center is inside the circle are painted
+        let radius = (size as f32) / 2.0;
+        let center = [(left as f32) + radius, (top as f32) + radius];
+        for cell_y in top..(top + size) {
+            for cell_x in left..(left + size) {
+                let dx = (cell_x as f32) + 0.5 - center[0];
+                let dy = (cell_y as f32) + 0.5 - center[1];
+                if dx * dx + dy * dy <= radius * radius {
+                    self.set_cell(cell_x, cell_y, cell);
+                }
+            }
+        }
+    }
+
+    /// Sets the cells of the rectangle between the corners `a` and `b`, included. The rectangle is clipped to the terrain.
+    pub fn fill_rect(&mut self, a: [i32; 2], b: [i32; 2], cell: TerrainType) {
+        let left = i32::max(i32::min(a[0], b[0]), 0);
+        let top = i32::max(i32::min(a[1], b[1]), 0);
+        let right = i32::min(i32::max(a[0], b[0]), self.size.width as i32 - 1);
+        let bottom = i32::min(i32::max(a[1], b[1]), self.size.height as i32 - 1);
+        if left > right || top > bottom {
+            return;
+        }
+
+        let width = (right - left + 1) as u32;
+        let height = (bottom - top + 1) as u32;
+        let cells = vec![cell; (width * height) as usize];
+        self.set_cells(left as u32, top as u32, width, height, &cells);
     }
 
-    #[allow(dead_code)]
-    pub fn get_cell(&mut self, x: u32, y: u32) -> TerrainType {
+    /// Replaces the cells connected to `x`, `y` that have the same type as it
+    pub fn flood_fill(&mut self, x: i32, y: i32, cell: TerrainType) {
+        if !self.contains_cell(x, y) {
+            return;
+        }
+
+        let target = self.get_cell(x as u32, y as u32);
+        if target == cell {
+            return;
+        }
+
+        // The filled cells are collected first, then written in bulk and their neighbors are recomputed once
+        let width = self.size.width as usize;
+        let mut visited = IndexSet::with_len(width * self.size.height as usize);
+        let mut filled = Vec::new();
+        let mut stack = vec![[x, y]];
+        while let Some([x, y]) = stack.pop() {
+            if !self.contains_cell(x, y) || !visited.insert((y as usize) * width + (x as usize)) {
+                continue;
+            }
+
+            if self.get_cell(x as u32, y as u32) != target {
+                continue;
+            }
+
+            filled.push([x, y]);
+            stack.extend_from_slice(&[[x - 1, y], [x + 1, y], [x, y - 1], [x, y + 1]]);
+        }
+
+        let mut min = [x, y];
+        let mut max = [x, y];
+        for &[x, y] in filled.iter() {
+            let (batch_index, local_x, local_y) = self.cell_location(x as u32, y as u32);
+            self.record_chunk(batch_index);
+            self.batches[batch_index].cells[local_y][local_x] = cell;
+            self.mark_updated(batch_index);
+
+            min = [i32::min(min[0], x), i32::min(min[1], y)];
+            max = [i32::max(max[0], x), i32::max(max[1], y)];
+        }
+
+        self.update_neighbors([min[0] - 1, min[1] - 1], [max[0] + 1, max[1] + 1]);
+    }
+
+    /// Recomputes the neighbors of the cells in the rectangle between the corners `a` and `b`, included.
+    /// The engine selects the autotile sprite of a cell from its neighbors, so the batches of the changed cells are sent again.
+    fn update_neighbors(&mut self, a: [i32; 2], b: [i32; 2]) {
+        let left = i32::max(a[0], 0);
+        let top = i32::max(a[1], 0);
+        let right = i32::min(b[0], self.size.width as i32 - 1);
+        let bottom = i32::min(b[1], self.size.height as i32 - 1);
+
+        for y in top..=bottom {
+            for x in left..=right {
+                let cell = self.get_cell(x as u32, y as u32);
+                let mut neighbors = TerrainNeighbors::empty();
+                for (bit, [dx, dy]) in TerrainNeighbors::OFFSETS.iter().enumerate() {
+                    let [nx, ny] = [x + dx, y + dy];
+                    if !self.contains_cell(nx, ny) || self.get_cell(nx as u32, ny as u32) == cell {
+                        neighbors |= TerrainNeighbors::from_bits_retain(1 << bit);
+                    }
+                }
+
+                let (batch_index, local_x, local_y) = self.cell_location(x as u32, y as u32);
+                let old_neighbors = &mut self.batches[batch_index].neighbors[local_y][local_x];
+                if *old_neighbors != neighbors {
+                    *old_neighbors = neighbors;
+                    self.mark_updated(batch_index);
+                }
+            }
+        }
+    }
+
+    /// Number of batches in a row of the terrain
+    fn batches_per_row(&self) -> u32 {
+        let chunk_stride = TERRAIN_CHUNK_STRIDE as u32;
+        self.size.width.div_ceil(chunk_stride)
+    }
+
+    /// Returns the index of the batch of a cell and the position of the cell in the batch
+    fn cell_location(&self, x: u32, y: u32) -> (usize, usize, usize) {
         let chunk_stride = TERRAIN_CHUNK_STRIDE as u32;
-        let stride = self.size.width / chunk_stride;
         let batch_x = x / chunk_stride;
         let batch_y = y / chunk_stride;
-        
-        let batch_index =  ((batch_y * stride) + batch_x) as usize;
+
+        let batch_index = ((batch_y * self.batches_per_row()) + batch_x) as usize;
         let local_x = x - (batch_x * chunk_stride);
         let local_y = y - (batch_y * chunk_stride);
 
-        self.batches[batch_index].cells[local_y as usize][local_x as usize]
+        (batch_index, local_x as usize, local_y as usize)
+    }
+
+    /// Starts recording the changes of the cells. See `end_edit`.
+    pub fn begin_edit(&mut self) {
+        self.edit_snapshots = Some(Vec::new());
+        self.edit_recorded.clear();
+    }
+
+    /// Stops recording and returns the cells changed since `begin_edit`
+    pub fn end_edit(&mut self) -> TerrainEdit {
+        let mut edit = TerrainEdit::default();
+        for (chunk, before) in self.edit_snapshots.take().unwrap_or_default() {
+            let after = &self.batches[chunk].cells;
+            let cells: Vec<TerrainCellDiff> = before.iter().flatten()
+                .zip(after.iter().flatten())
+                .enumerate()
+                .filter(|(_, (before, after))| before != after )
+                .map(|(index, (&before, &after))| TerrainCellDiff { index: index as u16, before, after } )
+                .collect();
+
+            if !cells.is_empty() {
+                edit.chunks.push(TerrainChunkDiff { chunk, cells });
+            }
+        }
+
+        edit
+    }
+
+    /// Restores the cells of `edit` to their values before the edit if `undo` is `true`, or after the edit otherwise
+    pub fn apply_edit(&mut self, edit: &TerrainEdit, undo: bool) {
+        let chunk_stride = TERRAIN_CHUNK_STRIDE as i32;
+        let stride = self.batches_per_row() as usize;
+        for chunk in edit.chunks.iter() {
+            // Edits are dropped when the terrain is resized, but a stale edit must not panic
+            let Some(batch) = self.batches.get_mut(chunk.chunk) else { continue };
+            for cell in chunk.cells.iter() {
+                let index = cell.index as usize;
+                let value = if undo { cell.before } else { cell.after };
+                batch.cells[index / TERRAIN_CHUNK_STRIDE][index % TERRAIN_CHUNK_STRIDE] = value;
+            }
+
+            self.mark_updated(chunk.chunk);
+
+            let x = ((chunk.chunk % stride) as i32) * chunk_stride;
+            let y = ((chunk.chunk / stride) as i32) * chunk_stride;
+            self.update_neighbors([x - 1, y - 1], [x + chunk_stride, y + chunk_stride]);
+        }
+    }
+
+    /// Saves the cells of a chunk before its first modification while an edit is recorded
+    fn record_chunk(&mut self, batch_index: usize) {
+        if let Some(snapshots) = self.edit_snapshots.as_mut() {
+            if self.edit_recorded.insert(batch_index) {
+                snapshots.push((batch_index, self.batches[batch_index].cells));
+            }
+        }
+    }
+
+    /// Sends the batch at `batch_index` to the engine on the next sync
+    fn mark_updated(&mut self, batch_index: usize) {
+        if self.batches_updated.insert(batch_index) {
+            self.batches_updates.push(batch_index);
+        }
     }
 
     #[inline(always)]
@@ -147,6 +517,7 @@ impl Terrain {
         }
 
         self.batches_updates.clear();
+        self.batches_updated.clear();
         self.flags = TerrainUpdateFlags::empty();
     }
 
@@ -175,9 +546,13 @@ impl Default for Terrain {
         Terrain {
             batches: Vec::with_capacity(16),
             batches_updates: Vec::with_capacity(16),
+            batches_updated: IndexSet::default(),
             view: RectF32::default(),
             size: SizeU32::default(),
             flags: TerrainUpdateFlags::empty(),
+            types: TerrainTypes::default(),
+            edit_snapshots: None,
+            edit_recorded: IndexSet::default(),
         }
     }
 }
@@ -186,23 +561,120 @@ impl Default for Terrain {
 mod terrain_tests {
     use super::*;
 
+    const GRASS: TerrainType = TerrainType(0);
+    const SAND: TerrainType = TerrainType(1);
+    const WATER: TerrainType = TerrainType(2);
+
+    fn cell_neighbors(terrain: &Terrain, x: u32, y: u32) -> TerrainNeighbors {
+        let (batch_index, local_x, local_y) = terrain.cell_location(x, y);
+        terrain.batches[batch_index].neighbors[local_y][local_x]
+    }
+
     #[test]
     fn set_cells() {
         let mut terrain = Terrain::default();
         terrain.set_world_size(32, 32);
 
-        terrain.set_cells(1, 1, 3, 1, &[TerrainType::Sand; 3]);
-        assert!(terrain.batches[0].cells[1][0] == TerrainType::Grass);
-        assert!(terrain.batches[0].cells[1][1] == TerrainType::Sand);
-        assert!(terrain.batches[0].cells[1][2] == TerrainType::Sand);
-        assert!(terrain.batches[0].cells[1][3] == TerrainType::Sand);
-        assert!(terrain.batches[0].cells[1][4] == TerrainType::Grass);
+        terrain.set_cells(1, 1, 3, 1, &[SAND; 3]);
+        assert!(terrain.batches[0].cells[1][0] == GRASS);
+        assert!(terrain.batches[0].cells[1][1] == SAND);
+        assert!(terrain.batches[0].cells[1][2] == SAND);
+        assert!(terrain.batches[0].cells[1][3] == SAND);
+        assert!(terrain.batches[0].cells[1][4] == GRASS);
+
+        terrain.set_cells(15, 15, 2, 2, &[SAND; 4]);
+        assert!(terrain.batches[0].cells[15][15] == SAND);
+        assert!(terrain.batches[1].cells[15][0] == SAND);
+        assert!(terrain.batches[2].cells[0][15] == SAND);
+        assert!(terrain.batches[3].cells[0][0] == SAND);
+    }
+
+    #[test]
+    fn brushes() {
+        let mut terrain = Terrain::default();
+        terrain.set_world_size(20, 20);
+
+        terrain.paint_square(0, 0, 3, SAND);
+        assert!(terrain.get_cell(0, 0) == SAND);
+        assert!(terrain.get_cell(1, 1) == SAND);
+        assert!(terrain.get_cell(2, 2) == GRASS);
+
+        terrain.paint_circle(10, 10, 5, WATER);
+        assert!(terrain.get_cell(10, 10) == WATER);
+        assert!(terrain.get_cell(12, 10) == WATER);
+        assert!(terrain.get_cell(12, 12) == GRASS);
+
+        // Clipped to the terrain, across the batches
+        terrain.fill_rect([18, 14], [25, 17], SAND);
+        assert!(terrain.get_cell(15, 14) == GRASS);
+        assert!(terrain.get_cell(16, 14) == GRASS);
+        assert!(terrain.get_cell(18, 17) == SAND);
+        assert!(terrain.get_cell(19, 14) == SAND);
+    }
+
+    #[test]
+    fn neighbors() {
+        let mut terrain = Terrain::default();
+        terrain.set_world_size(20, 20);
+        assert!(cell_neighbors(&terrain, 0, 0) == TerrainNeighbors::all());
+
+        // Neighbors are updated across the batches
+        terrain.set_cell(16, 5, WATER);
+        assert!(cell_neighbors(&terrain, 16, 5) == TerrainNeighbors::empty());
+        assert!(cell_neighbors(&terrain, 15, 5) == TerrainNeighbors::all() - TerrainNeighbors::E);
+        assert!(cell_neighbors(&terrain, 17, 6) == TerrainNeighbors::all() - TerrainNeighbors::NW);
+
+        terrain.fill_rect([15, 4], [17, 6], WATER);
+        assert!(cell_neighbors(&terrain, 16, 5) == TerrainNeighbors::all());
+        assert!(cell_neighbors(&terrain, 15, 4) == TerrainNeighbors::E | TerrainNeighbors::SE | TerrainNeighbors::S);
+    }
 
-        terrain.set_cells(15, 15, 2, 2, &[TerrainType::Sand; 4]);
-        assert!(terrain.batches[0].cells[15][15] == TerrainType::Sand);
-        assert!(terrain.batches[1].cells[15][0] == TerrainType::Sand);
-        assert!(terrain.batches[2].cells[0][15] == TerrainType::Sand);
-        assert!(terrain.batches[3].cells[0][0] == TerrainType::Sand);
+    #[test]
+    fn flood_fill() {
+        let mut terrain = Terrain::default();
+        terrain.set_world_size(20, 20);
+
+        // A wall of water splits the terrain in two
+        terrain.fill_rect([5, 0], [5, 19], WATER);
+        terrain.flood_fill(0, 0, SAND);
+        assert!(terrain.get_cell(4, 19) == SAND);
+        assert!(terrain.get_cell(5, 10) == WATER);
+        assert!(terrain.get_cell(6, 0) == GRASS);
+        assert!(terrain.get_cell(19, 19) == GRASS);
+
+        // The neighbors are recomputed around the filled cells
+        let wall = TerrainNeighbors::NE | TerrainNeighbors::E | TerrainNeighbors::SE;
+        assert!(cell_neighbors(&terrain, 4, 10) == TerrainNeighbors::all() - wall);
+        assert!(cell_neighbors(&terrain, 3, 10) == TerrainNeighbors::all());
     }
 
+
+    #[test]
+    fn edit_undo() {
+        let mut terrain = Terrain::default();
+        terrain.set_world_size(32, 32);
+
+        terrain.begin_edit();
+        terrain.fill_rect([14, 14], [17, 17], SAND);
+        terrain.set_cell(0, 0, WATER);
+        let mut edit = terrain.end_edit();
+        assert!(edit.chunks.len() == 4);
+
+        terrain.begin_edit();
+        terrain.set_cell(15, 15, WATER);
+        edit.merge(terrain.end_edit());
+        assert!(edit.chunks.len() == 4);
+
+        terrain.apply_edit(&edit, true);
+        assert!(terrain.get_cell(15, 15) == GRASS);
+        assert!(terrain.get_cell(17, 17) == GRASS);
+        assert!(terrain.get_cell(0, 0) == GRASS);
+        assert!(cell_neighbors(&terrain, 13, 13) == TerrainNeighbors::all());
+
+        terrain.apply_edit(&edit, false);
+        assert!(terrain.get_cell(15, 15) == WATER);
+        assert!(terrain.get_cell(16, 16) == SAND);
+        assert!(terrain.get_cell(0, 0) == WATER);
+        assert!(cell_neighbors(&terrain, 13, 13) == TerrainNeighbors::all() - TerrainNeighbors::SE);
+    }
 }
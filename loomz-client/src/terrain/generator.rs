@@ -0,0 +1,263 @@
+//! Procedural terrain generation. The terrain is an island shaped by fractal value noise, banded into water, sand and grass,
+//! with optional rivers flowing from the hills to the sea.
+//!
+//! The generation only depends on the settings of `TerrainGenerator`: the same seed always generates the same terrain.
+//! The terrain types are found by name (`water`, `sand` and `grass`). Missing types use the default terrain.
+use loomz_shared::api::TerrainType;
+use loomz_shared::assets::terrain::TerrainTypes;
+
+/// Number of rivers of the maps generated without explicit settings
+pub const DEFAULT_RIVERS: u32 = 2;
+
+/// Heights under this level are water
+const WATER_LEVEL: f32 = 0.0;
+/// Heights between the water level and this level are sand
+const SAND_LEVEL: f32 = 0.06;
+/// Height removed at the center of the terrain. Controls the ratio of land and water.
+const ISLAND_BASE: f32 = 0.35;
+/// Height removed at the edges of the terrain, on top of `ISLAND_BASE`. Keeps the edges under water.
+const ISLAND_FALLOFF: f32 = 0.8;
+const NOISE_OCTAVES: u32 = 4;
+/// Smallest size of the largest noise features, in cells
+const NOISE_MIN_SCALE: f32 = 8.0;
+/// Number of cells tried when looking for the source of a river. The highest cell is used.
+const RIVER_SOURCE_CANDIDATES: u32 = 32;
+
+pub struct TerrainGenerator {
+    pub seed: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Number of rivers. Rivers start on the highest cells of the island. Limited to `max_rivers`.
+    pub rivers: u32,
+}
+
+pub struct GeneratedTerrain {
+    /// Cells of the terrain, row by row. See `Terrain::set_cells`.
+    pub cells: Vec<TerrainType>,
+    /// Land cell closest to the center of the terrain. `None` if the terrain has no land.
+    pub spawn_point: Option<[u32; 2]>,
+}
+
+impl TerrainGenerator {
+
+    /// Largest number of rivers of a terrain of `width` by `height` cells. Each river is a walk of up to `4 * (width + height)` cells.
+    pub fn max_rivers(width: u32, height: u32) -> u32 {
+        width.saturating_add(height)
+    }
+
+    pub fn generate(&self, types: &TerrainTypes) -> GeneratedTerrain {
+        let water = types.by_name("water").unwrap_or_default();
+        let sand = types.by_name("sand").unwrap_or_default();
+        let grass = types.by_name("grass").unwrap_or_default();
+
+        let heights = self.heights();
+        let mut cells: Vec<TerrainType> = heights.iter()
+            .map(|&height| match height {
+                height if height < WATER_LEVEL => water,
+                height if height < SAND_LEVEL => sand,
+                _ => grass,
+            })
+            .collect();
+
+        let mut sequence = SeedSequence(self.seed ^ 0x5249_5645_5253);
+        for _ in 0..u32::min(self.rivers, Self::max_rivers(self.width, self.height)) {
+            for index in self.river(&heights, &mut sequence) {
+                cells[index] = water;
+            }
+        }
+
+        let spawn_point = self.spawn_point(&cells, grass);
+
+        GeneratedTerrain { cells, spawn_point }
+    }
+
+    /// Height of every cell, row by row. Heights are lower near the edges so that the land forms an island.
+    fn heights(&self) -> Vec<f32> {
+        let (width, height) = (self.width as f32, self.height as f32);
+        let scale = f32::max(f32::max(width, height) / 4.0, NOISE_MIN_SCALE);
+
+        let mut heights = Vec::with_capacity((self.width * self.height) as usize);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                // Distance to the center. 0 at the center, 1 at the middle of the edges
+                let dx = ((x as f32) + 0.5) / width * 2.0 - 1.0;
+                let dy = ((y as f32) + 0.5) / height * 2.0 - 1.0;
+                let distance = dx * dx + dy * dy;
+
+                let noise = self.fractal_noise((x as f32) / scale, (y as f32) / scale);
+                heights.push(noise - ISLAND_BASE - ISLAND_FALLOFF * distance);
+            }
+        }
+
+        heights
+    }
+
+    /// Sum of `NOISE_OCTAVES` layers of value noise. Returns a value between 0 and 1.
+    fn fractal_noise(&self, x: f32, y: f32) -> f32 {
+        let mut total = 0.0;
+        let mut weight = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        for octave in 0..NOISE_OCTAVES {
+            total += self.value_noise(x * frequency, y * frequency, octave) * amplitude;
+            weight += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+
+        total / weight
+    }
+
+    /// Interpolates random values placed on the corners of a grid. Returns a value between 0 and 1.
+    fn value_noise(&self, x: f32, y: f32, octave: u32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (ix, iy) = (x0 as i32, y0 as i32);
+        let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+        let (tx, ty) = (smooth(x - x0), smooth(y - y0));
+
+        let corner = |cx: i32, cy: i32| {
+            let value = hash(self.seed, octave, cx, cy);
+            (value as f32) / (u32::MAX as f32)
+        };
+
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let top = lerp(corner(ix, iy), corner(ix + 1, iy), tx);
+        let bottom = lerp(corner(ix, iy + 1), corner(ix + 1, iy + 1), tx);
+        lerp(top, bottom, ty)
+    }
+
+    /// Returns the cells of a river. The river starts on a high cell and flows to the lowest neighbor until it reaches the water.
+    fn river(&self, heights: &[f32], sequence: &mut SeedSequence) -> Vec<usize> {
+        let (width, height) = (self.width as usize, self.height as usize);
+
+        let source = (0..RIVER_SOURCE_CANDIDATES)
+            .map(|_| (sequence.next() as usize) % heights.len() )
+            .max_by(|&a, &b| heights[a].total_cmp(&heights[b]) );
+
+        let mut river = Vec::new();
+        let mut current = match source {
+            Some(source) if heights[source] >= SAND_LEVEL => source,
+            _ => { return river; }
+        };
+
+        // Rivers flow through the local minimums of the terrain, so the length of the river is bounded instead
+        let max_length = 4 * (width + height);
+        while heights[current] >= WATER_LEVEL && river.len() < max_length {
+            river.push(current);
+
+            let (x, y) = (current % width, current / width);
+            let neighbors = [
+                (x > 0).then(|| current - 1),
+                (x + 1 < width).then(|| current + 1),
+                (y > 0).then(|| current - width),
+                (y + 1 < height).then(|| current + width),
+            ];
+
+            let next = neighbors.into_iter()
+                .flatten()
+                .filter(|index| !river.contains(index) )
+                .min_by(|&a, &b| heights[a].total_cmp(&heights[b]) );
+
+            match next {
+                Some(next) => { current = next; },
+                None => { break; }
+            }
+        }
+
+        river
+    }
+
+    fn spawn_point(&self, cells: &[TerrainType], land: TerrainType) -> Option<[u32; 2]> {
+        let center = [(self.width as f32) / 2.0, (self.height as f32) / 2.0];
+        let distance = |index: usize| {
+            let dx = ((index as u32 % self.width) as f32) + 0.5 - center[0];
+            let dy = ((index as u32 / self.width) as f32) + 0.5 - center[1];
+            dx * dx + dy * dy
+        };
+
+        cells.iter()
+            .enumerate()
+            .filter(|(_, &cell)| cell == land )
+            .map(|(index, _)| index )
+            .min_by(|&a, &b| distance(a).total_cmp(&distance(b)) )
+            .map(|index| [index as u32 % self.width, index as u32 / self.width])
+    }
+
+}
+
+/// Seed derived from the current time, for maps that do not need to be reproduced. Print the seed to reproduce the map.
+pub fn time_seed() -> u64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64 )
+        .unwrap_or(0);
+    splitmix(nanos)
+}
+
+/// Deterministic sequence of random values derived from a seed. Owned by the generation that uses it.
+struct SeedSequence(u64);
+
+impl SeedSequence {
+    fn next(&mut self) -> u32 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        (splitmix(self.0) >> 32) as u32
+    }
+}
+
+/// Random value of the grid corner `x`, `y` of a noise octave
+fn hash(seed: u64, octave: u32, x: i32, y: i32) -> u32 {
+    let position = ((x as u32 as u64) << 32) | (y as u32 as u64);
+    let value = splitmix(seed ^ splitmix(position ^ splitmix(octave as u64)));
+    (value >> 32) as u32
+}
+
+/// Finalizer of the splitmix64 generator. Mixes the bits of `value`.
+fn splitmix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod generator_tests {
+    use super::*;
+
+    fn test_types() -> TerrainTypes {
+        TerrainTypes::from_json(r#"{ "types": [{ "name": "grass" }, { "name": "sand" }, { "name": "water" }] }"#).unwrap()
+    }
+
+    #[test]
+    fn deterministic() {
+        let types = test_types();
+        let generate = |seed: u64| TerrainGenerator { seed, width: 40, height: 30, rivers: 2 }.generate(&types).cells;
+
+        assert!(generate(1) == generate(1));
+        assert!(generate(1) != generate(2));
+    }
+
+    #[test]
+    fn island() {
+        let types = test_types();
+        let water = types.by_name("water").unwrap();
+        let generator = TerrainGenerator { seed: 7, width: 48, height: 48, rivers: 0 };
+        let terrain = generator.generate(&types);
+
+        assert!(terrain.cells.len() == 48 * 48);
+        for index in 0..48 {
+            assert!(terrain.cells[index] == water);
+            assert!(terrain.cells[48 * 47 + index] == water);
+        }
+
+        let [x, y] = terrain.spawn_point.unwrap();
+        assert!(terrain.cells[(y * 48 + x) as usize] == types.by_name("grass").unwrap());
+    }
+
+    #[test]
+    fn rivers_limit() {
+        // Rivers over the limit are ignored instead of walking the terrain billions of times
+        let types = test_types();
+        let generate = |rivers: u32| TerrainGenerator { seed: 3, width: 16, height: 16, rivers }.generate(&types).cells;
+        assert!(generate(u32::MAX) == generate(TerrainGenerator::max_rivers(16, 16)));
+    }
+}
@@ -66,12 +66,26 @@ impl DeviceMemoryAlloc {
         aligned_offset
     }
 
+    /// Returns `true` if an allocation of `info` fits in the remaining memory
+    pub fn can_allocate(&self, info: &vk::MemoryRequirements) -> bool {
+        let aligned_offset = crate::helpers::align_device(self.next_offset, info.alignment);
+        aligned_offset + info.size <= self.size
+    }
+
     pub fn free_memory(&mut self, offset: vk::DeviceSize) {
+        const FREED: vk::DeviceSize = 0b1 << 63;
+
         let index = self.allocations.iter().position(|alloc| alloc.offset == offset );
         match index {
-            Some(i) => { self.allocations[i].base |= 0b1 << 63; }
+            Some(i) => { self.allocations[i].base |= FREED; }
             None => { eprintln!("Allocation at offset {offset} was not found in device memory"); }
         }
+
+        // The memory of the freed allocations at the end of the memory can be allocated again
+        while let Some(last) = self.allocations.last().filter(|alloc| alloc.base & FREED != 0 ) {
+            self.next_offset = last.base & !FREED;
+            self.allocations.pop();
+        }
     }
 
 }
@@ -206,6 +220,11 @@ impl<V: Copy> VertexAlloc<V> {
         let buffer_req = device.get_buffer_memory_requirements(buffer);
         
         let vertex_alloc = &mut core.resources.vertex_alloc;
+        if !vertex_alloc.can_allocate(&buffer_req) {
+            device.destroy_buffer(buffer);
+            return Err(backend_err!("Not enough vertex memory to allocate a buffer of {} bytes", buffer_req.size));
+        }
+
         let offset = vertex_alloc.allocate_memory(&buffer_req);
         device.bind_buffer_memory(buffer, vertex_alloc.handle, offset)
             .map_err(|err| backend_err!("Failed to bind vertex buffer memory: {err}") )?;
@@ -270,6 +289,32 @@ impl<V: Copy> VertexAlloc<V> {
         core.staging.vertex_buffer_copy(self.buffer, vertex_copy);
     }
 
+    /// Overwrites the vertex starting at `first_vertex`, leaving the indices and the other vertex untouched.
+    /// Returns an error without uploading anything if the vertex do not fit in the buffer.
+    pub fn update_vertex(&self, core: &mut LoomzEngineCore, first_vertex: usize, vertex: &[V]) -> Result<(), CommonError> {
+        let last_vertex = first_vertex + vertex.len();
+        if (self.vertex_capacity as usize) < last_vertex {
+            return Err(backend_err!("Tried to update vertex {first_vertex}..{last_vertex} of a vertex buffer with a capacity of {}", self.vertex_capacity));
+        }
+
+        if vertex.is_empty() {
+            return Ok(());
+        }
+
+        let vertex_size = size_of::<V>() as vk::DeviceSize;
+        let src_vertex_offset = core.staging.copy_data(vertex);
+
+        let vertex_copy = vk::BufferCopy {
+            size: (vertex.len() as vk::DeviceSize) * vertex_size,
+            src_offset: src_vertex_offset,
+            dst_offset: self.vertex_offset()[0] + ((first_vertex as vk::DeviceSize) * vertex_size),
+        };
+
+        core.staging.vertex_buffer_copy(self.buffer, vertex_copy);
+
+        Ok(())
+    }
+
 }
 
 impl<V: Copy> Default for VertexAlloc<V> {
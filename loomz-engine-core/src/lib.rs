@@ -20,6 +20,10 @@ use loomz_shared::CommonError;
 use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
 use staging::VulkanStaging;
 
+/// Number of frames that can be recorded before the drawings of the oldest one must be completed.
+/// Resources written by the commands of a frame have one instance per frame in flight.
+pub const FRAMES_IN_FLIGHT: usize = 1;
+
 /// Regroup common engine information in one single place
 pub struct VulkanEngineInfo {
     pub graphics_queue_info: vk::wrapper::QueueInfo,
@@ -34,14 +38,17 @@ pub struct VulkanEngineInfo {
     pub depth_format: vk::Format,
 
     pub storage_min_align: u32,
+
+    /// Nanoseconds per timestamp query tick. `0.0` if timestamps are not supported by the graphics queue.
+    pub timestamp_period: f32,
 }
 
 /// Holder for the shared gpu resources used in the engine
 pub struct VulkanGlobalResources {
     pub linear_sampler: vk::Sampler,
     pub command_pool: vk::CommandPool,
-    pub drawing_command_buffers: [vk::CommandBuffer; 1],
-    pub upload_command_buffers: [vk::CommandBuffer; 1],
+    pub drawing_command_buffers: [vk::CommandBuffer; FRAMES_IN_FLIGHT],
+    pub upload_command_buffers: [vk::CommandBuffer; FRAMES_IN_FLIGHT],
     pub surface: vk::SurfaceKHR,
     pub vertex_alloc: alloc::DeviceMemoryAlloc,
     pub images_alloc: alloc::DeviceMemoryAlloc,
@@ -51,6 +58,8 @@ pub struct VulkanGlobalResources {
 
 /// Data used when recording GPU commands
 pub struct VulkanRecordingInfo {
+    /// Index of the frame in flight being recorded, in `0..FRAMES_IN_FLIGHT`
+    pub frame_index: usize,
     pub drawing_command_buffer: vk::CommandBuffer,
     pub output_image: vk::Image,
     pub extent: vk::Extent2D,
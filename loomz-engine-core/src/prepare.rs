@@ -1,5 +1,5 @@
 use loomz_shared::{synchronize_err, CommonError};
-use super::LoomzEngineCore;
+use super::{LoomzEngineCore, FRAMES_IN_FLIGHT};
 
 pub enum AcquireReturn {
     Invalid,
@@ -73,9 +73,15 @@ fn prepare_recording(engine: &mut LoomzEngineCore, image_index: u32) {
 
     assert!(i < att.output.len(), "Acquired image index must be in swapchain image range");
 
+    // Frames in flight are recorded in turn
+    let frame = match engine.recording.frame_index + 1 {
+        FRAMES_IN_FLIGHT => 0,
+        next => next,
+    };
+
     // Upload
     let staging = &mut engine.staging;
-    staging.upload_command_buffer = resources.upload_command_buffers[0];
+    staging.upload_command_buffer = resources.upload_command_buffers[frame];
 
     staging.start = staging.read;
     staging.read = staging.write;
@@ -87,7 +93,8 @@ fn prepare_recording(engine: &mut LoomzEngineCore, image_index: u32) {
 
     // Recording
     let recording = &mut engine.recording;
-    recording.drawing_command_buffer = resources.drawing_command_buffers[0];
+    recording.frame_index = frame;
+    recording.drawing_command_buffer = resources.drawing_command_buffers[frame];
     recording.output_image = att.output[i].image;
     recording.color_attachment.resolve_image_view = att.output[i].view;
     recording.color_attachment.image_view = att.color.view;
@@ -99,15 +106,16 @@ fn prepare_submit(engine: &mut LoomzEngineCore) {
     let resources = &engine.resources;
     let output = &mut engine.output;
     let submit = &mut engine.submit;
+    let frame = engine.recording.frame_index;
 
     // Upload
-    submit.upload_commands_submit.command_buffer = resources.upload_command_buffers[0];
+    submit.upload_commands_submit.command_buffer = resources.upload_command_buffers[frame];
 
     submit.upload_semaphore_signal[0].semaphore = output.drawings_sync.handle;
     submit.upload_semaphore_signal[0].value = output.drawings_sync.value + 1;
 
     // Render
-    submit.render_commands_submit.command_buffer = resources.drawing_command_buffers[0];
+    submit.render_commands_submit.command_buffer = resources.drawing_command_buffers[frame];
 
     submit.render_semaphore_wait[0].semaphore = output.output_attachment_ready;
     submit.render_semaphore_wait[0].stage_mask = vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT;
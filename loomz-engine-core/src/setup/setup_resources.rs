@@ -1,6 +1,6 @@
 use loomz_shared::{backend_init_err, CommonError};
 use vk::CommandBufferSubmitInfo;
-use crate::{context::VulkanContext, helpers, VulkanDescriptorSubmit, VulkanEngineInfo, VulkanGlobalResources, VulkanOutputInfo, VulkanRecordingInfo, VulkanStaging, VulkanSubmitInfo};
+use crate::{context::VulkanContext, helpers, VulkanDescriptorSubmit, VulkanEngineInfo, VulkanGlobalResources, VulkanOutputInfo, VulkanRecordingInfo, VulkanStaging, VulkanSubmitInfo, FRAMES_IN_FLIGHT};
 use super::VulkanEngineSetup;
 
 pub(crate) fn setup(setup: &mut VulkanEngineSetup) -> Result<(), CommonError> {
@@ -29,6 +29,10 @@ pub(crate) fn setup_info(setup: &mut VulkanEngineSetup) -> Result<Box<VulkanEngi
     let depth_format = depth_format(ctx)?;
 
     let device_info = ctx.instance.instance.get_physical_device_properties(ctx.device.physical_device);
+    let timestamp_period = match device_info.limits.timestamp_compute_and_graphics {
+        0 => 0.0,
+        _ => device_info.limits.timestamp_period,
+    };
 
     Ok(Box::new(VulkanEngineInfo {
         graphics_queue_info,
@@ -40,6 +44,7 @@ pub(crate) fn setup_info(setup: &mut VulkanEngineSetup) -> Result<Box<VulkanEngi
         depth_format,
         sample_count,
         storage_min_align: device_info.limits.min_storage_buffer_offset_alignment as u32,
+        timestamp_period,
     }))
 }
 
@@ -108,8 +113,8 @@ fn init_resources(setup: &mut VulkanEngineSetup) -> Result<Box<VulkanGlobalResou
     let mut resources = VulkanGlobalResources {
         command_pool: vk::CommandPool::null(),
         linear_sampler: vk::Sampler::null(),
-        upload_command_buffers: [vk::CommandBuffer::null(); 1],
-        drawing_command_buffers: [vk::CommandBuffer::null(); 1],
+        upload_command_buffers: [vk::CommandBuffer::null(); FRAMES_IN_FLIGHT],
+        drawing_command_buffers: [vk::CommandBuffer::null(); FRAMES_IN_FLIGHT],
         surface: vk::SurfaceKHR::null(),
         vertex_alloc: crate::alloc::DeviceMemoryAlloc::default(),
         images_alloc: crate::alloc::DeviceMemoryAlloc::default(),
@@ -139,7 +144,7 @@ fn setup_commands(setup: &mut VulkanEngineSetup, resources: &mut VulkanGlobalRes
     resources.command_pool = ctx.device.create_command_pool(&create_info)
         .map_err(|err| backend_init_err!("Failed to create main command pool: {err}") )?;
 
-    let mut command_buffers = [vk::CommandBuffer::null(); 2];
+    let mut command_buffers = [vk::CommandBuffer::null(); FRAMES_IN_FLIGHT * 2];
     let alloc_info = vk::CommandBufferAllocateInfo {
         level: vk::CommandBufferLevel::PRIMARY,
         command_pool: resources.command_pool,
@@ -150,14 +155,15 @@ fn setup_commands(setup: &mut VulkanEngineSetup, resources: &mut VulkanGlobalRes
     ctx.device.allocate_command_buffers(&alloc_info, &mut command_buffers)
         .map_err(|err| backend_init_err!("Failed to allocate command buffers: {err}") )?;
 
-    resources.upload_command_buffers[0] = command_buffers[0];
-    resources.drawing_command_buffers[0] = command_buffers[1];
+    let (upload, drawing) = command_buffers.split_at(FRAMES_IN_FLIGHT);
+    resources.upload_command_buffers.copy_from_slice(upload);
+    resources.drawing_command_buffers.copy_from_slice(drawing);
 
     Ok(())
 }
 
 fn setup_vertex_memory(setup: &mut VulkanEngineSetup, resources: &mut VulkanGlobalResources) -> Result<(), CommonError> {
-    use crate::alloc::{DeviceMemoryAlloc, KB};
+    use crate::alloc::{DeviceMemoryAlloc, MB};
 
     let ctx = setup.ctx.as_ref().unwrap();
     let instance = &ctx.instance.instance;
@@ -165,7 +171,8 @@ fn setup_vertex_memory(setup: &mut VulkanEngineSetup, resources: &mut VulkanGlob
     let device_type_index = crate::helpers::fetch_memory_index(instance, ctx.device.physical_device, flags, flags)
         .ok_or_else(|| backend_init_err!("Failed to find memory type suitable for vertex") )?;
     
-    let vertex_size = KB*100;
+    // The gui vertex buffer grows with the number of sprites
+    let vertex_size = MB*4;
     let default_alloc_capacity = 16;
     
     resources.vertex_alloc = DeviceMemoryAlloc::new(&ctx.device, vertex_size, default_alloc_capacity, device_type_index)
@@ -251,6 +258,7 @@ fn init_recording() -> Box<VulkanRecordingInfo> {
     };
 
     let recording_info = VulkanRecordingInfo {
+        frame_index: 0,
         drawing_command_buffer: vk::CommandBuffer::null(),
         output_image: vk::Image::null(),
         extent: vk::Extent2D::default(),
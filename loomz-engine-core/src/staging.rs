@@ -69,6 +69,11 @@ impl VulkanStaging {
         offset_bytes
     }
 
+    /// Number of bytes uploaded to the GPU in the current frame
+    pub fn frame_upload_size(&self) -> vk::DeviceSize {
+        self.read - self.start
+    }
+
     pub fn copy_data<T: Copy>(&mut self, data: &[T]) -> vk::DeviceSize {
         self.copy_data_with_align(data, align_of::<T>())
     }
@@ -4,6 +4,7 @@ mod batch;
 use fnv::FnvHashMap;
 use std::{slice, sync::Arc};
 use loomz_shared::api::{LoomzApi, GuiSprite};
+use loomz_shared::RectF32;
 use loomz_shared::assets::{LoomzAssetsBundle, AssetId, MsdfFontId, TextureId, ShaderId};
 use loomz_shared::{CommonError, CommonErrorType};
 use loomz_shared::{assets_err, backend_err, chain_err};
@@ -14,6 +15,9 @@ const LAYOUT_COUNT: usize = 1;
 const BATCH_LAYOUT_INDEX: u32 = 0;
 const BATCH_TEXTURE_BINDING: u32 = 0;
 
+/// Sprites the vertex buffer can hold before it is reallocated. The buffer grows with the number of visible sprites.
+const GUI_INITIAL_SPRITES_CAPACITY: usize = 1000;
+
 const PUSH_STAGE_FLAGS: vk::ShaderStageFlags = vk::ShaderStageFlags::VERTEX;
 const PUSH_SIZE: u32 = size_of::<GuiPushConstant>() as u32;
 
@@ -61,11 +65,23 @@ struct GuiViewSprite {
 struct GuiView {
     sprites: Vec<GuiViewSprite>,
     id: u32,
+    layer: i32,
     visible: bool,
+    /// Index of the first vertex of the gui in the vertex buffer. `u32::MAX` if the gui has no vertex.
+    first_vertex: u32,
+}
+
+/// Range of sprites of a gui whose vertex must be uploaded again
+#[derive(Copy, Clone)]
+struct GuiSpritesPatch {
+    gui_id: u32,
+    first_sprite: usize,
+    count: usize,
 }
 
 /// Generic gui data not yet formatted for rendering 
 struct GuiData {
+    /// Gui views sorted by layer. Batches are generated in this order.
     gui: Vec<GuiView>,
     indices: Vec<u32>,
     vertex: Vec<GuiVertex>,
@@ -76,6 +92,7 @@ struct GuiData {
 pub struct GuiBatch {
     pipeline: vk::Pipeline,
     set: vk::DescriptorSet,
+    clip: RectF32,
     index_count: u32,
     first_index: u32,
 }
@@ -95,6 +112,8 @@ pub(crate) struct GuiModule {
     data: Box<GuiData>,
     render: Box<GuiRender>,
     update_batches: bool,
+    /// Sprites updated since the last frame. Ignored if the batches are rebuilt.
+    patches: Vec<GuiSpritesPatch>,
 }
 
 impl GuiModule {
@@ -136,6 +155,7 @@ impl GuiModule {
             data: Box::new(data),
             render: Box::new(render),
             update_batches: false,
+            patches: Vec::with_capacity(16),
         };
 
         gui.setup_pipelines(api, core)?;
@@ -210,6 +230,7 @@ impl GuiModule {
         device.cmd_push_constants(cmd, render.pipeline_layout, PUSH_STAGE_FLAGS, 0, PUSH_SIZE, push_values(&render.push_constants));
 
         let mut last_pipeline = vk::Pipeline::null();
+        let mut last_clip = RectF32::default();
 
         for batch in render.batches.iter() {
             // improvement: Try to use one pipeline for all of the GUI rendering
@@ -217,10 +238,34 @@ impl GuiModule {
                 device.cmd_bind_pipeline(cmd, GRAPHICS, batch.pipeline);
                 last_pipeline = batch.pipeline;
             }
+
+            if last_clip != batch.clip {
+                device.cmd_set_scissor(cmd, 0, slice::from_ref(&self.scissor(batch.clip)));
+                last_clip = batch.clip;
+            }
             
             device.cmd_bind_descriptor_sets(cmd, GRAPHICS, render.pipeline_layout, BATCH_LAYOUT_INDEX, slice::from_ref(&batch.set), &[]);
             device.cmd_draw_indexed(cmd, batch.index_count, 1, batch.first_index, 0, 0);
         }
+
+        // Restore the full screen scissor for the next render passes
+        if last_clip.has_area() {
+            device.cmd_set_scissor(cmd, 0, slice::from_ref(&self.scissor(RectF32::default())));
+        }
+    }
+
+    /// Converts a sprite clipping rect into a scissor rect. A clip with no area covers the whole screen.
+    fn scissor(&self, clip: RectF32) -> vk::Rect2D {
+        let [screen_width, screen_height] = [self.render.push_constants[0].screen_width, self.render.push_constants[0].screen_height];
+        let clip = match clip.has_area() {
+            true => clip.intersection(&RectF32 { left: 0.0, top: 0.0, right: screen_width, bottom: screen_height }),
+            false => RectF32 { left: 0.0, top: 0.0, right: screen_width, bottom: screen_height },
+        };
+
+        vk::Rect2D {
+            offset: vk::Offset2D { x: clip.left as i32, y: clip.top as i32 },
+            extent: vk::Extent2D { width: clip.width() as u32, height: clip.height() as u32 },
+        }
     }
 
     //
@@ -228,11 +273,14 @@ impl GuiModule {
     //
 
     fn create_gui(&mut self, id: u32) -> usize {
-        let index = self.data.gui.len();
-        self.data.gui.push(GuiView {
+        // New guis are on layer 0, after the existing guis of the same layer
+        let index = self.data.gui.partition_point(|gui| gui.layer <= 0 );
+        self.data.gui.insert(index, GuiView {
             sprites: Vec::new(),
             id,
+            layer: 0,
             visible: true,
+            first_vertex: u32::MAX,
         });
 
         index
@@ -269,6 +317,48 @@ impl GuiModule {
         Ok(())
     }
 
+    /// Replaces the sprites starting at `first_sprite`. If the new sprites can be drawn by the same batches, only their vertex are uploaded.
+    /// A patch outside of the sprites of the gui is dropped and the client is asked to send all the sprites again.
+    fn patch_gui_sprites(&mut self, api: &LoomzApi, core: &mut LoomzEngineCore, index: usize, first_sprite: usize, sprites: &[GuiSprite]) -> Result<(), CommonError> {
+        let gui = match self.data.gui.get_mut(index) {
+            Some(gui) => gui,
+            None => {
+                return Err(backend_err!("Tried to fetch gui at index {}, but it does not exits", index));
+            } 
+        };
+
+        // The patch was made for sprites the engine does not have. Drop it and wait for the full sprites.
+        let last_sprite = first_sprite + sprites.len();
+        if last_sprite > gui.sprites.len() {
+            eprintln!("Gui {} patched sprites {}..{} but only has {} sprites. Requesting all the sprites", gui.id, first_sprite, last_sprite, gui.sprites.len());
+            api.gui().request_resend(gui.id);
+            return Ok(());
+        }
+
+        let mut same_batches = true;
+        for (view_sprite, &sprite) in gui.sprites[first_sprite..last_sprite].iter_mut().zip(sprites.iter()) {
+            let descriptor_set = match sprite.ty {
+                loomz_shared::GuiSpriteType::Image(texture_id) => Self::fetch_texture_descriptor_set(core, &mut self.resources, texture_id)?,
+                loomz_shared::GuiSpriteType::Font(font_id) => Self::fetch_font_texture_descriptor_set(core, &mut self.resources, font_id)?
+            };
+
+            let old = view_sprite.sprite;
+            same_batches &= old.ty == sprite.ty && old.clip == sprite.clip && view_sprite.descriptor_set == descriptor_set;
+
+            *view_sprite = GuiViewSprite {
+                descriptor_set,
+                sprite
+            };
+        }
+
+        match same_batches {
+            true => self.patches.push(GuiSpritesPatch { gui_id: gui.id, first_sprite, count: sprites.len() }),
+            false => { self.update_batches = true; }
+        }
+
+        Ok(())
+    }
+
     fn toggle_gui_visibility(&mut self, index: usize, visible: bool) -> Result<(), CommonError> {
         match self.data.gui.get_mut(index) {
             Some(gui) => {
@@ -282,6 +372,20 @@ impl GuiModule {
         }
     }
 
+    fn set_gui_layer(&mut self, index: usize, layer: i32) -> Result<(), CommonError> {
+        match self.data.gui.get_mut(index) {
+            Some(gui) => {
+                gui.layer = layer;
+                self.data.gui.sort_by_key(|gui| gui.layer );
+                self.update_batches = true;
+                Ok(())
+            },
+            None => {
+                Err(backend_err!("Tried to fetch gui at index {}, but it does not exits", index))
+            } 
+        }
+    }
+
     fn api_update(&mut self, api: &LoomzApi, core: &mut LoomzEngineCore) -> Result<(), CommonError> {
         use loomz_shared::GuiApiUpdate;
         
@@ -295,8 +399,14 @@ impl GuiModule {
                     GuiApiUpdate::ToggleGui(visible) => {
                         self.toggle_gui_visibility(index, visible)?;
                     },
+                    GuiApiUpdate::SetLayer(layer) => {
+                        self.set_gui_layer(index, layer)?;
+                    },
                     GuiApiUpdate::UpdateSprites(sprites) => {
                         self.update_gui_sprites(core, index, sprites)?;
+                    },
+                    GuiApiUpdate::PatchSprites(first_sprite, sprites) => {
+                        self.patch_gui_sprites(api, core, index, first_sprite as usize, sprites)?;
                     }
                 }
             }
@@ -311,8 +421,15 @@ impl GuiModule {
         if self.update_batches {
             batch::build(core, self)?;
             self.update_batches = false;
+        } else if !self.patches.is_empty() {
+            if let Err(err) = batch::patch(core, self) {
+                eprintln!("Failed to patch the gui vertex, rebuilding the gui batches: {err}");
+                batch::build(core, self)?;
+            }
         }
 
+        self.patches.clear();
+
         Ok(())
     }
 
@@ -320,6 +437,14 @@ impl GuiModule {
     // Data
     //
 
+    /// Number of sprites of the visible guis
+    pub fn sprites_count(&self) -> usize {
+        self.data.gui.iter()
+            .filter(|gui| gui.visible )
+            .map(|gui| gui.sprites.len() )
+            .sum()
+    }
+
     pub fn reload_assets(&mut self, api: &LoomzApi, core: &mut LoomzEngineCore, assets: &Vec<AssetId>) -> Result<(), CommonError> {
         for &assets_id in assets.iter() {
             match assets_id {
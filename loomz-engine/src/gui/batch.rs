@@ -1,6 +1,7 @@
-use loomz_shared::CommonError;
+use loomz_shared::{CommonError, RectF32};
 use loomz_shared::api::GuiSpriteType;
 use loomz_engine_core::{alloc::VertexAlloc, LoomzEngineCore};
+use loomz_shared::api::GuiSprite;
 use super::{GuiModule, GuiBatch, GuiView, GuiViewSprite, GuiVertex};
 
 struct NextBatch<'a> {
@@ -18,7 +19,7 @@ impl<'a> NextBatch<'a> {
         self.batches.clear();
     }
     
-    fn build_batch(&mut self, sprite_type: GuiSpriteType, set: vk::DescriptorSet, sprites_count: usize) -> Result<(), CommonError> {
+    fn build_batch(&mut self, sprite_type: GuiSpriteType, set: vk::DescriptorSet, clip: RectF32, sprites_count: usize) -> Result<(), CommonError> {
         let pipeline = match sprite_type {
             GuiSpriteType::Font(_) => self.text_pipeline,
             GuiSpriteType::Image(_) => self.image_pipeline,
@@ -27,6 +28,7 @@ impl<'a> NextBatch<'a> {
         self.batches.push(GuiBatch {
             pipeline,
             set,
+            clip,
             first_index: self.index_count as u32,
             index_count: (sprites_count * 6) as u32,
         });
@@ -41,7 +43,7 @@ impl<'a> NextBatch<'a> {
         // Safety, index buffer capacity will be greater than written range
         let mut i = self.index_count;
         let mut v = self.vertex_count;
-        assert!(i + index_count < self.indices.len() as isize);
+        assert!(i + index_count <= self.indices.len() as isize);
 
         unsafe {
             for _ in 0..sprite_count {
@@ -67,19 +69,16 @@ impl<'a> NextBatch<'a> {
 
         // Safety, vertex capacity will be greater than written range
         let mut v = self.vertex_count as isize;
-        assert!(v + vertex_count < self.vertex.len() as isize);
+        assert!(v + vertex_count <= self.vertex.len() as isize);
 
         unsafe {
             let vertex = self.vertex.as_mut_ptr();            
             for sprite_view in sprites {
-                let [x1, y1, x2, y2] = sprite_view.sprite.position.splat();
-                let [x3, y3, x4, y4] = sprite_view.sprite.texcoord.splat();
-                let color = sprite_view.sprite.color.splat();
-                
-                vertex.offset(v+0).write(GuiVertex { pos: [x1, y1], uv: [x3, y3], color });
-                vertex.offset(v+1).write(GuiVertex { pos: [x2, y1], uv: [x4, y3], color });
-                vertex.offset(v+2).write(GuiVertex { pos: [x1, y2], uv: [x3, y4], color });
-                vertex.offset(v+3).write(GuiVertex { pos: [x2, y2], uv: [x4, y4], color });
+                let [v0, v1, v2, v3] = sprite_vertex(&sprite_view.sprite);
+                vertex.offset(v+0).write(v0);
+                vertex.offset(v+1).write(v1);
+                vertex.offset(v+2).write(v2);
+                vertex.offset(v+3).write(v3);
                 v += 4;
             }
         }
@@ -97,34 +96,36 @@ impl<'a> NextBatch<'a> {
     }
 }
 
-fn find_first_sprite_type(gui: &[GuiView], gui_index_out: &mut usize) -> Option<GuiSpriteType> {
-    let mut gui_index = 0;
-    let mut current_sprite_type = None;
-
-    while current_sprite_type.is_none() && gui_index < gui.len() {
-        let gui = gui.get(gui_index);
+fn sprite_vertex(sprite: &GuiSprite) -> [GuiVertex; 4] {
+    let [x1, y1, x2, y2] = sprite.position.splat();
+    let [x3, y3, x4, y4] = sprite.texcoord.splat();
+    let color = sprite.color.splat();
+    [
+        GuiVertex { pos: [x1, y1], uv: [x3, y3], color },
+        GuiVertex { pos: [x2, y1], uv: [x4, y3], color },
+        GuiVertex { pos: [x1, y2], uv: [x3, y4], color },
+        GuiVertex { pos: [x2, y2], uv: [x4, y4], color },
+    ]
+}
 
-        if let Some(gui) = gui {
-            if gui.visible {
-                current_sprite_type = gui.sprites.first()
-                    .map(|sprite| sprite.sprite.ty );
-            }
+/// Vertex of the visible guis are written in the same order as the groups
+fn set_first_vertex(gui: &mut [GuiView]) {
+    let mut vertex_count = 0;
+    for gui in gui.iter_mut() {
+        if !gui.visible || gui.sprites.is_empty() {
+            gui.first_vertex = u32::MAX;
+            continue;
         }
 
-        if current_sprite_type.is_none() {
-            gui_index += 1;
-        }
+        gui.first_vertex = vertex_count;
+        vertex_count += (gui.sprites.len() * 4) as u32;
     }
-
-    *gui_index_out = gui_index;
-    current_sprite_type
 }
 
-fn groups<'a>(gui: &'a [GuiView]) -> impl Iterator<Item=(GuiSpriteType, vk::DescriptorSet, &'a [GuiViewSprite])> {
+fn groups<'a>(gui: &'a [GuiView]) -> impl Iterator<Item=(GuiSpriteType, vk::DescriptorSet, RectF32, &'a [GuiViewSprite])> {
     let mut gui_index = 0;
     let mut sprites_start = 0;
     let mut sprites_stop = 0;
-    let mut current_sprite_type = find_first_sprite_type(gui, &mut gui_index);
 
     ::std::iter::from_fn(move || {
         loop {
@@ -133,18 +134,20 @@ fn groups<'a>(gui: &'a [GuiView]) -> impl Iterator<Item=(GuiSpriteType, vk::Desc
                 None => { return None }
             };
 
-            if !gui.visible {
+            if !gui.visible || gui.sprites.is_empty() {
                 gui_index += 1;
                 continue;
             }
 
+            // Sprites are grouped while they share the same type and the same clipping rect
+            let first = gui.sprites[sprites_start].sprite;
             loop {
                 if sprites_stop == gui.sprites.len() {
                     break;
                 }
             
-                let sprite_view = &gui.sprites[sprites_stop];
-                if Some(sprite_view.sprite.ty) != current_sprite_type {
+                let sprite = &gui.sprites[sprites_stop].sprite;
+                if sprite.ty != first.ty || sprite.clip != first.clip {
                     break;
                 }
             
@@ -152,11 +155,9 @@ fn groups<'a>(gui: &'a [GuiView]) -> impl Iterator<Item=(GuiSpriteType, vk::Desc
             }
 
             let set = gui.sprites[sprites_start].descriptor_set;
-            let sprite_type = gui.sprites[sprites_start].sprite.ty;
             let sprites = &gui.sprites[sprites_start..sprites_stop];
-            let value = (sprite_type, set, sprites);
+            let value = (first.ty, set, first.clip, sprites);
 
-            current_sprite_type = Some(sprite_type);
             sprites_start = sprites_stop;
 
             if sprites_start == gui.sprites.len() {
@@ -171,6 +172,14 @@ fn groups<'a>(gui: &'a [GuiView]) -> impl Iterator<Item=(GuiSpriteType, vk::Desc
 }
 
 pub(super) fn build(core: &mut LoomzEngineCore, gui_module: &mut GuiModule) -> Result<(), CommonError> {
+    // The vertex buffer grows to the number of visible sprites
+    let sprites_count = gui_module.sprites_count();
+    let sprites_capacity = gui_module.data.vertex.len() / 4;
+    if sprites_count > sprites_capacity {
+        let capacity = usize::max(sprites_count, sprites_capacity * 2);
+        gui_module.allocate_vertex_buffers(core, capacity)?;
+    }
+
     let mut batcher = NextBatch {
         batches: &mut gui_module.render.batches,
         indices: &mut gui_module.data.indices,
@@ -182,9 +191,10 @@ pub(super) fn build(core: &mut LoomzEngineCore, gui_module: &mut GuiModule) -> R
     };
 
     batcher.prepare();
+    set_first_vertex(&mut gui_module.data.gui);
 
-    for (sprite_type, image_view, sprites) in groups(&gui_module.data.gui) {
-        batcher.build_batch(sprite_type, image_view, sprites.len())?;
+    for (sprite_type, image_view, clip, sprites) in groups(&gui_module.data.gui) {
+        batcher.build_batch(sprite_type, image_view, clip, sprites.len())?;
         batcher.generate_indices(sprites);
         batcher.generate_vertex(sprites);
     }
@@ -193,3 +203,30 @@ pub(super) fn build(core: &mut LoomzEngineCore, gui_module: &mut GuiModule) -> R
 
     Ok(())
 }
+
+/// Uploads the vertex of the patched sprites. Patched sprites are always drawn by the same batches as the sprites they replaced.
+/// On error, the batches must be rebuilt.
+pub(super) fn patch(core: &mut LoomzEngineCore, gui_module: &mut GuiModule) -> Result<(), CommonError> {
+    let data = &mut gui_module.data;
+    for patch in gui_module.patches.iter() {
+        let gui = match data.gui.iter().find(|gui| gui.id == patch.gui_id ) {
+            Some(gui) => gui,
+            None => { continue; }
+        };
+
+        if gui.first_vertex == u32::MAX {
+            continue;
+        }
+
+        let sprites = &gui.sprites[patch.first_sprite..(patch.first_sprite + patch.count)];
+        let first_vertex = (gui.first_vertex as usize) + (patch.first_sprite * 4);
+        let last_vertex = first_vertex + (patch.count * 4);
+        for (vertex, sprite_view) in data.vertex[first_vertex..last_vertex].chunks_exact_mut(4).zip(sprites.iter()) {
+            vertex.copy_from_slice(&sprite_vertex(&sprite_view.sprite));
+        }
+
+        data.vertex_alloc.update_vertex(core, first_vertex, &data.vertex[first_vertex..last_vertex])?;
+    }
+
+    Ok(())
+}
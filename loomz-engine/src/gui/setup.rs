@@ -1,6 +1,6 @@
 use loomz_engine_core::{LoomzEngineCore, alloc::VertexAlloc, descriptors::*, pipelines::*};
 use loomz_shared::{CommonError, CommonErrorType, LoomzApi};
-use loomz_shared::{backend_init_err, assets_err, chain_err};
+use loomz_shared::{backend_err, backend_init_err, assets_err, chain_err};
 use super::{GuiPushConstant, GuiVertex};
 
 impl super::GuiModule {
@@ -111,13 +111,27 @@ impl super::GuiModule {
     }
 
     pub(super) fn setup_vertex_buffers(&mut self, core: &mut LoomzEngineCore) -> Result<(), CommonError> {
-        let vertex_capacity = 500;
-        let index_capacity = 1000;
+        self.allocate_vertex_buffers(core, super::GUI_INITIAL_SPRITES_CAPACITY)
+            .map_err(|err| chain_err!(err, CommonErrorType::BackendInit, "Failed to create vertex alloc: {err}") )
+    }
+
+    /// Replaces the vertex buffer with a buffer that can hold `sprites_capacity` sprites. The content of the old buffer is lost.
+    pub(super) fn allocate_vertex_buffers(&mut self, core: &mut LoomzEngineCore, sprites_capacity: usize) -> Result<(), CommonError> {
+        let vertex_capacity = sprites_capacity * 4;
+        let index_capacity = sprites_capacity * 6;
+
+        if self.data.vertex_alloc.buffer != vk::Buffer::null() {
+            // The old buffer may still be used by the frames being rendered
+            core.ctx.device.device_wait_idle()
+                .map_err(|err| backend_err!("Failed to wait for the device: {err}") )?;
+            self.data.vertex_alloc.free(core);
+            self.data.vertex_alloc = VertexAlloc::default();
+        }
 
+        self.data.vertex_alloc = VertexAlloc::new(core, index_capacity as u32, vertex_capacity as u32)?;
         self.data.indices = vec![0; index_capacity];
         self.data.vertex = vec![GuiVertex::default(); vertex_capacity];
-        self.data.vertex_alloc = VertexAlloc::new(core, index_capacity as u32, vertex_capacity as u32)
-            .map_err(|err| chain_err!(err, CommonErrorType::BackendInit, "Failed to create vertex alloc: {err}") )?;
+        self.setup_render_data();
 
         Ok(())
     }
@@ -2,8 +2,10 @@ mod pipeline_compiler;
 mod world;
 mod gui;
 mod record;
+mod perf;
 
 use std::path::PathBuf;
+use std::time::Instant;
 use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
 use loomz_engine_core::LoomzEngineCore;
 use loomz_shared::{backend_init_err, CommonError, api::LoomzApi};
@@ -13,6 +15,7 @@ pub struct LoomzEngine {
     core: LoomzEngineCore,
     world: world::WorldModule,
     gui: gui::GuiModule,
+    perf: perf::PerfModule,
     pipeline_cache: vk::PipelineCache,
 }
 
@@ -22,12 +25,14 @@ impl LoomzEngine {
         let mut core = LoomzEngineCore::init()?;
         let world = world::WorldModule::init(&mut core, api)?;
         let gui = gui::GuiModule::init(&mut core, api)?;
+        let perf = perf::PerfModule::init(&core)?;
         let pipeline_cache = Self::load_pipeline_cache(&core)?;
         let mut engine = LoomzEngine {
             api: api.clone(),
             core,
             world,
             gui,
+            perf,
             pipeline_cache,
         };
 
@@ -44,6 +49,7 @@ impl LoomzEngine {
         self.core.ctx.device.destroy_pipeline_cache(self.pipeline_cache);
         self.world.destroy(&mut self.core);
         self.gui.destroy(&mut self.core);
+        self.perf.destroy(&mut self.core);
         self.core.destroy();
     }
 
@@ -65,15 +71,19 @@ impl LoomzEngine {
     }
 
     pub fn update(&mut self) -> Result<(), CommonError> {
+        let start = Instant::now();
         self.reload_assets()?;
         self.world.update(&self.api, &mut self.core)?;
         self.gui.update(&self.api, &mut self.core)?;
+        self.perf.stats.update_ms = perf::elapsed_ms(start, Instant::now());
         Ok(())
     }
 
     pub fn render(&mut self) -> Result<(), CommonError> {
         use loomz_engine_core::AcquireReturn;
 
+        self.perf.begin_frame();
+
         match self.core.acquire_frame()? {
             AcquireReturn::Invalid => {},
             AcquireReturn::Rebuild => {
@@ -81,14 +91,29 @@ impl LoomzEngine {
                 self.gui.rebuild(&self.core);
             },
             AcquireReturn::Render => {
+                self.perf.read_gpu_time(&self.core);
+
+                let record_start = Instant::now();
                 record::record_commands(self)?;
+                self.perf.stats.record_ms = perf::elapsed_ms(record_start, Instant::now());
+
                 self.core.submit_frame()?;
+                self.push_perf_stats();
             }
         }
 
         Ok(())
     }
 
+    fn push_perf_stats(&mut self) {
+        let stats = &mut self.perf.stats;
+        stats.actors = self.world.actors_count() as u32;
+        stats.terrain_batches = self.world.terrain_batches_count() as u32;
+        stats.gui_sprites = self.gui.sprites_count() as u32;
+        stats.staging_bytes = self.core.staging.frame_upload_size();
+        self.api.perf().push_engine_stats(*stats);
+    }
+
     fn compile_pipelines(&mut self) -> Result<(), CommonError> {
         let mut compiler = pipeline_compiler::PipelineCompiler::new();
         self.world.write_pipeline_create_infos(&mut compiler);
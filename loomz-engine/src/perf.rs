@@ -0,0 +1,111 @@
+use std::time::Instant;
+use loomz_shared::api::PerfEngineStats;
+use loomz_shared::{backend_init_err, CommonError};
+use loomz_engine_core::{LoomzEngineCore, VulkanContext, VulkanRecordingInfo, FRAMES_IN_FLIGHT};
+
+/// Timestamps written per frame: before and after the drawing commands
+const TIMESTAMP_COUNT: u32 = 2;
+
+/// Measures the engine timings and the GPU time of the drawing commands
+pub(crate) struct PerfModule {
+    /// Timestamp queries of each frame in flight. Null if the graphics queue does not support timestamps.
+    query_pools: [vk::QueryPool; FRAMES_IN_FLIGHT],
+    timestamp_period: f32,
+    /// If the timestamps of the frame in flight are waiting to be read
+    timestamps_written: [bool; FRAMES_IN_FLIGHT],
+    last_frame: Option<Instant>,
+    pub stats: PerfEngineStats,
+}
+
+impl PerfModule {
+
+    pub fn init(core: &LoomzEngineCore) -> Result<Self, CommonError> {
+        let timestamp_period = core.info.timestamp_period;
+        let mut query_pools = [vk::QueryPool::null(); FRAMES_IN_FLIGHT];
+        if timestamp_period > 0.0 {
+            let create_info = vk::QueryPoolCreateInfo {
+                query_type: vk::QueryType::TIMESTAMP,
+                query_count: TIMESTAMP_COUNT,
+                ..Default::default()
+            };
+
+            for query_pool in query_pools.iter_mut() {
+                *query_pool = core.ctx.device.create_query_pool(&create_info)
+                    .map_err(|err| backend_init_err!("Failed to create timestamp query pool: {}", err) )?;
+            }
+        }
+
+        let perf = PerfModule {
+            query_pools,
+            timestamp_period,
+            timestamps_written: [false; FRAMES_IN_FLIGHT],
+            last_frame: None,
+            stats: PerfEngineStats::default(),
+        };
+
+        Ok(perf)
+    }
+
+    pub fn destroy(self, core: &mut LoomzEngineCore) {
+        for query_pool in self.query_pools {
+            if !query_pool.is_null() {
+                core.ctx.device.destroy_query_pool(query_pool);
+            }
+        }
+    }
+
+    /// Updates the frame time with the time elapsed since the last call
+    pub fn begin_frame(&mut self) {
+        let now = Instant::now();
+        if let Some(last_frame) = self.last_frame {
+            self.stats.frame_ms = elapsed_ms(last_frame, now);
+        }
+
+        self.last_frame = Some(now);
+    }
+
+    /// Reads the GPU time of the last use of the frame in flight being recorded.
+    /// Must be called after `acquire_frame`, once the drawing commands of that frame completed.
+    pub fn read_gpu_time(&mut self, core: &LoomzEngineCore) {
+        let frame = core.recording.frame_index;
+        if !self.timestamps_written[frame] {
+            return;
+        }
+
+        let mut timestamps = [0u64; TIMESTAMP_COUNT as usize];
+        if core.ctx.device.get_query_pool_results(self.query_pools[frame], 0, &mut timestamps, vk::QueryResultFlags::default()).is_ok() {
+            let ticks = timestamps[1].wrapping_sub(timestamps[0]);
+            self.stats.gpu_ms = ((ticks as f64) * (self.timestamp_period as f64) / 1_000_000.0) as f32;
+        }
+
+        self.timestamps_written[frame] = false;
+    }
+
+    /// Writes the first timestamp. Must be recorded outside of a render pass.
+    pub fn write_begin_timestamp(&self, ctx: &VulkanContext, recording: &VulkanRecordingInfo) {
+        let query_pool = self.query_pools[recording.frame_index];
+        if query_pool.is_null() {
+            return;
+        }
+
+        let cmd = recording.drawing_command_buffer;
+        ctx.device.cmd_reset_query_pool(cmd, query_pool, 0, TIMESTAMP_COUNT);
+        ctx.device.cmd_write_timestamp(cmd, vk::PipelineStageFlags::TOP_OF_PIPE, query_pool, 0);
+    }
+
+    pub fn write_end_timestamp(&mut self, ctx: &VulkanContext, recording: &VulkanRecordingInfo) {
+        let query_pool = self.query_pools[recording.frame_index];
+        if query_pool.is_null() {
+            return;
+        }
+
+        let cmd = recording.drawing_command_buffer;
+        ctx.device.cmd_write_timestamp(cmd, vk::PipelineStageFlags::BOTTOM_OF_PIPE, query_pool, 1);
+        self.timestamps_written[recording.frame_index] = true;
+    }
+
+}
+
+pub(crate) fn elapsed_ms(start: Instant, end: Instant) -> f32 {
+    (end - start).as_secs_f32() * 1000.0
+}
@@ -11,14 +11,17 @@ pub(crate) fn record_commands(engine: &mut LoomzEngine) -> Result<(), CommonErro
     debug_assert!(!cmd.is_null(), "Drawing command buffer was not set during render prepare phase");
 
     begin_record(&ctx.device, cmd)?;
+    engine.perf.write_begin_timestamp(ctx, recording);
     prepare_attachments(ctx, cmd, recording.output_image);
     begin_render_main(ctx, cmd, recording);
 
     engine.world.render(ctx, cmd);
     engine.gui.render(ctx, cmd);
+    engine.world.render_perf_graph(ctx, cmd);
 
     end_render_main(ctx, cmd);
     finalize_attachments(ctx, cmd, recording.output_image);
+    engine.perf.write_end_timestamp(ctx, recording);
     end_record(&ctx.device, cmd)?;
 
     Ok(())
@@ -8,7 +8,7 @@ use bitflags::bitflags;
 use std::{slice, sync::Arc, u32, usize};
 use loomz_shared::api::{LoomzApi, WorldUpdate, WorldDebugFlags, TERRAIN_CHUNK_SIZE};
 use loomz_shared::assets::{LoomzAssetsBundle, TextureId, ShaderId, AssetId};
-use loomz_shared::{CommonError, RgbaU8, SizeF32, size};
+use loomz_shared::{CommonError, RectF32, RgbaU8, SizeF32, size};
 use loomz_engine_core::{LoomzEngineCore, VulkanContext, Texture, alloc::VertexAlloc, descriptors::*, pipelines::*};
 use super::pipeline_compiler::PipelineCompiler;
 
@@ -33,6 +33,9 @@ const ACTOR_SPRITE_BUFFER_BINDING_INDEX: u32 = 0;
 const ACTOR_BATCH_LAYOUT_INDEX: u32 = 1;
 const ACTOR_SAMPLER_BINDING_INDEX: u32 = 0;
 
+/// Number of autotile sprites of a terrain type. One per combination of `TerrainNeighbors`.
+const TERRAIN_AUTOTILE_COUNT: usize = 256;
+
 bitflags! {
     #[derive(Copy, Clone, Default)]
     pub struct WorldFlags: u8 {
@@ -108,6 +111,7 @@ struct WorldResources {
 
     vertex: VertexAlloc<WorldVertex>,
     debug_vertex: VertexAlloc<WorldDebugVertex>,
+    perf_vertex: VertexAlloc<WorldDebugVertex>,
 
     descriptors: DescriptorsAllocator<LAYOUT_COUNT>,
 
@@ -155,6 +159,8 @@ struct WorldRender {
     terrain: WorldTerrainRender,
     actors: WorldActorRender,
     debug: WorldDebugRender,
+    /// Frame time graph of the performance overlay. Drawn with the debug pipeline.
+    perf: WorldDebugRender,
     push_constants: [WorldPushConstant; 1],
 }
 
@@ -164,6 +170,10 @@ pub(crate) struct WorldModule {
     render: Box<WorldRender>,
     debug: WorldDebugFlags,
     flags: WorldFlags,
+    /// Screen area of the frame time graph. `None` if the graph is hidden.
+    perf_graph: Option<RectF32>,
+    /// Value of `PerfStats::frame_count` when the frame time graph was last uploaded
+    perf_graph_frame: u64,
 }
 
 impl WorldModule {
@@ -176,6 +186,7 @@ impl WorldModule {
 
             vertex: VertexAlloc::default(),
             debug_vertex: VertexAlloc::default(),
+            perf_vertex: VertexAlloc::default(),
 
             descriptors: DescriptorsAllocator::default(),
 
@@ -195,6 +206,8 @@ impl WorldModule {
             render: Box::default(),
             debug: WorldDebugFlags::empty(),
             flags: WorldFlags::SHOW_WORLD,
+            perf_graph: None,
+            perf_graph_frame: 0,
         };
 
         world.setup_pipelines(core, api)?;
@@ -210,6 +223,7 @@ impl WorldModule {
     pub fn destroy(self, core: &mut LoomzEngineCore) {
         self.resources.vertex.free(core);
         self.resources.debug_vertex.free(core);
+        self.resources.perf_vertex.free(core);
         self.resources.descriptors.destroy(core);
 
         if let Some(texture) = self.resources.terrain_texture.as_ref() {
@@ -275,6 +289,7 @@ impl WorldModule {
         handle = compiler.get_pipeline("world_debug");
         self.resources.pipelines.debug.pipeline.set_handle(handle);
         self.render.debug.pipeline_handle = handle;
+        self.render.perf.pipeline_handle = handle;
     }
 
     //
@@ -295,7 +310,14 @@ impl WorldModule {
         self.render_actors(ctx, cmd);
 
         if !self.debug.is_empty() {
-            self.render_debug_info(ctx, cmd);
+            self.render_debug_info(ctx, cmd, &self.render.debug);
+        }
+    }
+
+    /// Renders the frame time graph of the performance overlay. Drawn over the gui, even if the world is hidden.
+    pub fn render_perf_graph(&self, ctx: &VulkanContext, cmd: vk::CommandBuffer) {
+        if self.perf_graph.is_some() {
+            self.render_debug_info(ctx, cmd, &self.render.perf);
         }
     }
 
@@ -337,11 +359,10 @@ impl WorldModule {
         }
     }
 
-    fn render_debug_info(&self, ctx: &VulkanContext, cmd: vk::CommandBuffer) {
+    fn render_debug_info(&self, ctx: &VulkanContext, cmd: vk::CommandBuffer, render: &WorldDebugRender) {
         const GRAPHICS: vk::PipelineBindPoint = vk::PipelineBindPoint::GRAPHICS;
         let device = &ctx.device;
         let push = Self::push_values(&self.render.push_constants);
-        let render = self.render.debug;
 
         if render.index_count == 0 {
             return;
@@ -417,6 +438,7 @@ impl WorldModule {
         const ANIMATION_INTERVAL: f32 = 1.0 / 16.0; // 16fps
 
         self.api_update(api, core)?;
+        self.update_perf_graph(api, core);
 
         if self.data.last_animation_tick.elapsed().as_secs_f32() > ANIMATION_INTERVAL {
             self.animation_update();
@@ -435,6 +457,14 @@ impl WorldModule {
         Ok(())
     }
 
+    pub fn actors_count(&self) -> usize {
+        self.data.actors_data.len()
+    }
+
+    pub fn terrain_batches_count(&self) -> usize {
+        self.render.terrain.batches.len()
+    }
+
     pub fn reload_assets(&mut self, api: &LoomzApi, core: &mut LoomzEngineCore, assets: &Vec<AssetId>) -> Result<(), CommonError> {
         for &assets_id in assets.iter() {
             match assets_id {
@@ -476,20 +506,27 @@ impl Default for WorldRender {
                 sprites_set: vk::DescriptorSet::null(),
                 batches: Vec::with_capacity(16),
             },
-            debug: WorldDebugRender {
-                pipeline_handle: vk::Pipeline::null(),
-                pipeline_layout: vk::PipelineLayout::null(),
-                vertex_buffer: [vk::Buffer::null()],
-                vertex_offset: [0],
-                index_offset: 0,
-                index_count: 0,
-            },
+            debug: WorldDebugRender::default(),
+            perf: WorldDebugRender::default(),
 
             push_constants: [WorldPushConstant::default(); 1],
         }
     }
 }
 
+impl Default for WorldDebugRender {
+    fn default() -> Self {
+        WorldDebugRender {
+            pipeline_handle: vk::Pipeline::null(),
+            pipeline_layout: vk::PipelineLayout::null(),
+            vertex_buffer: [vk::Buffer::null()],
+            vertex_offset: [0],
+            index_offset: 0,
+            index_count: 0,
+        }
+    }
+}
+
 impl Default for WorldPipeline {
     fn default() -> Self {
         WorldPipeline {
@@ -1,7 +1,7 @@
 use std::time::Instant;
 use loomz_shared::api::{
     WorldActorId, WorldAnimationId, WorldAnimation, WorldActorUpdate, WorldTerrainChunk,
-    TerrainChunk, TerrainType, TERRAIN_CHUNK_STRIDE, TERRAIN_CELL_SIZE_PX
+    TerrainChunk, TerrainType, TerrainNeighbors, TERRAIN_CHUNK_STRIDE, TERRAIN_CELL_SIZE_PX
 };
 use loomz_shared::{CommonError, CommonErrorType, TextureId, PositionF32, SizeU32, RectF32, rect, backend_err, assets_err, chain_err};
 use loomz_engine_core::{LoomzEngineCore, alloc::DeviceSlice};
@@ -64,6 +64,7 @@ pub(super) struct WorldData {
     pub actors_data: Vec<WorldActorData>,
     pub actors_sprites: DeviceSlice<ActorSpriteData>,
 
+    /// Autotile sprites of the terrain types. Each terrain type has one sprite per combination of `TerrainNeighbors`.
     pub terrain_tilemap: Vec<TerrainSpriteData>,
     pub terrain_size: SizeU32,
     pub terrain_chunks: Vec<WorldTerrainChunkData>,
@@ -253,30 +254,32 @@ impl super::WorldModule {
         }
 
         let tiles = &data.terrain_tilemap;
-        let get_tile = |ty: TerrainType| -> TerrainSpriteData {
-            // Safety: ty will always fall in the range of tiles. This is checked in `load_terrain_tilemap`s
-            unsafe { tiles.get(ty as usize).copied().unwrap_unchecked() }
+        let get_tile = |ty: TerrainType, neighbors: TerrainNeighbors| -> TerrainSpriteData {
+            // Unknown terrain types use the first sprite of the tilemap
+            let index = ((ty.0 as usize) * super::TERRAIN_AUTOTILE_COUNT) + (neighbors.bits() as usize);
+            tiles.get(index).copied().unwrap_or_default()
         };
 
         for row in 0..TERRAIN_CHUNK_STRIDE {
             let row_type = &chunk.cells[row];
+            let row_neighbors = &chunk.neighbors[row];
             let row_data = &mut chunk_data.cells[row];
-            row_data[0] = get_tile(row_type[0]);
-            row_data[1] = get_tile(row_type[1]);
-            row_data[2] = get_tile(row_type[2]);
-            row_data[3] = get_tile(row_type[3]);
-            row_data[4] = get_tile(row_type[4]);
-            row_data[5] = get_tile(row_type[5]);
-            row_data[6] = get_tile(row_type[6]);
-            row_data[7] = get_tile(row_type[7]);
-            row_data[8] = get_tile(row_type[8]);
-            row_data[9] = get_tile(row_type[9]);
-            row_data[10] = get_tile(row_type[10]);
-            row_data[11] = get_tile(row_type[11]);
-            row_data[12] = get_tile(row_type[12]);
-            row_data[13] = get_tile(row_type[13]);
-            row_data[14] = get_tile(row_type[14]);
-            row_data[15] = get_tile(row_type[15]);
+            row_data[0] = get_tile(row_type[0], row_neighbors[0]);
+            row_data[1] = get_tile(row_type[1], row_neighbors[1]);
+            row_data[2] = get_tile(row_type[2], row_neighbors[2]);
+            row_data[3] = get_tile(row_type[3], row_neighbors[3]);
+            row_data[4] = get_tile(row_type[4], row_neighbors[4]);
+            row_data[5] = get_tile(row_type[5], row_neighbors[5]);
+            row_data[6] = get_tile(row_type[6], row_neighbors[6]);
+            row_data[7] = get_tile(row_type[7], row_neighbors[7]);
+            row_data[8] = get_tile(row_type[8], row_neighbors[8]);
+            row_data[9] = get_tile(row_type[9], row_neighbors[9]);
+            row_data[10] = get_tile(row_type[10], row_neighbors[10]);
+            row_data[11] = get_tile(row_type[11], row_neighbors[11]);
+            row_data[12] = get_tile(row_type[12], row_neighbors[12]);
+            row_data[13] = get_tile(row_type[13], row_neighbors[13]);
+            row_data[14] = get_tile(row_type[14], row_neighbors[14]);
+            row_data[15] = get_tile(row_type[15], row_neighbors[15]);
         }
 
         Ok(())
@@ -319,7 +322,7 @@ impl Default for WorldData {
             actors_data: Vec::with_capacity(16),
             actors_sprites: DeviceSlice::default(),
 
-            terrain_tilemap: Vec::with_capacity(4),
+            terrain_tilemap: Vec::new(),
             terrain_size: SizeU32::default(),
             terrain_chunks: Vec::with_capacity(16),
             terrain_sprites: DeviceSlice::default(),
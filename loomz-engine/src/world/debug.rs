@@ -1,4 +1,4 @@
-use loomz_shared::api::WorldDebugFlags;
+use loomz_shared::api::{LoomzApi, WorldDebugFlags, PERF_HISTORY_SIZE};
 use loomz_shared::{RectF32, RgbaU8, rgb, rect};
 use loomz_engine_core::LoomzEngineCore;
 use super::{WorldModule, WorldDebugVertex};
@@ -88,6 +88,75 @@ impl WorldModule {
         self.build_debug_data(core);
     }
 
+    /// Rebuilds the frame time graph of the performance overlay with the last frame times.
+    /// The graph is only uploaded if a frame time was added or if the graph area changed.
+    pub(super) fn update_perf_graph(&mut self, api: &LoomzApi, core: &mut LoomzEngineCore) {
+        // Frame time at the top of the graph
+        const GRAPH_MAX_MS: f32 = 50.0;
+        const TARGET_60_MS: f32 = 1000.0 / 60.0;
+        const TARGET_30_MS: f32 = 1000.0 / 30.0;
+
+        let graph = api.perf().graph_area();
+        let area = match graph {
+            Some(area) => area,
+            None => {
+                self.perf_graph = None;
+                return;
+            }
+        };
+
+        let stats = api.perf().stats();
+        if self.perf_graph == graph && self.perf_graph_frame == stats.frame_count {
+            return;
+        }
+
+        self.perf_graph = graph;
+        self.perf_graph_frame = stats.frame_count;
+
+        let background = rgb(20, 20, 24);
+        let green = rgb(60, 190, 80);
+        let yellow = rgb(220, 190, 40);
+        let red = rgb(200, 40, 40);
+        let grey = rgb(110, 110, 120);
+
+        let mut index = Vec::with_capacity(6 * (PERF_HISTORY_SIZE + 3));
+        let mut vertex = Vec::with_capacity(4 * (PERF_HISTORY_SIZE + 3));
+        let mut vertex_count = 0;
+
+        let mut push_rect = |rect: RectF32, color: RgbaU8| {
+            write_indices(&mut index, vertex_count);
+            write_vertex(&mut vertex, rect, color, &mut vertex_count);
+        };
+
+        push_rect(area, background);
+
+        let height = area.height();
+        let bar_width = area.width() / (PERF_HISTORY_SIZE as f32);
+        for (i, frame_ms) in stats.frame_times().enumerate() {
+            if frame_ms <= 0.0 {
+                continue;
+            }
+
+            let color = match frame_ms {
+                ms if ms <= TARGET_60_MS => green,
+                ms if ms <= TARGET_30_MS => yellow,
+                _ => red,
+            };
+
+            let bar_height = f32::min(frame_ms / GRAPH_MAX_MS, 1.0) * height;
+            let x = area.left + (i as f32) * bar_width;
+            push_rect(rect(x, area.bottom - bar_height, x + bar_width, area.bottom), color);
+        }
+
+        for target_ms in [TARGET_60_MS, TARGET_30_MS] {
+            let y = area.bottom - (target_ms / GRAPH_MAX_MS) * height;
+            push_rect(rect(area.left, y-0.5, area.right, y+0.5), grey);
+        }
+
+        self.resources.perf_vertex.set_data(core, &index, &vertex);
+        self.render.perf.index_count = index.len() as u32;
+    }
+
 }
 
 fn write_indices(index: &mut Vec<u32>, i: u32) {
@@ -1,7 +1,8 @@
 use loomz_engine_core::{LoomzEngineCore, alloc::{VertexAlloc, DeviceSlice}, descriptors::*, pipelines::*};
 use loomz_engine_core::VulkanContext;
 use loomz_shared::api::{TERRAIN_CHUNK_SIZE, TERRAIN_CHUNK_STRIDE};
-use loomz_shared::{CommonError, CommonErrorType, LoomzApi, TerrainType};
+use loomz_shared::{CommonError, CommonErrorType, LoomzApi, TerrainNeighbors};
+use loomz_shared::assets::terrain::{TerrainTypes, TERRAIN_TYPES_ASSET};
 use loomz_shared::{backend_init_err, assets_err, chain_err};
 use super::{WorldPushConstant, WorldVertex, WorldDebugVertex, data::TerrainSpriteData};
 
@@ -390,6 +391,9 @@ impl super::WorldModule {
         self.resources.debug_vertex = VertexAlloc::new(core, index_capacity, vertex_capacity)
             .map_err(|err| chain_err!(err, CommonErrorType::BackendInit, "Failed to create debug vertex alloc: {err}") )?;
 
+        self.resources.perf_vertex = VertexAlloc::new(core, index_capacity, vertex_capacity)
+            .map_err(|err| chain_err!(err, CommonErrorType::BackendInit, "Failed to create perf graph vertex alloc: {err}") )?;
+
         Ok(())
     }
 
@@ -400,38 +404,23 @@ impl super::WorldModule {
         Ok(())
     }
 
+    /// Computes the sprite of every terrain type for every combination of neighbors. See `assets::terrain` for the rules.
     fn load_terrain_tilemap(&mut self) -> Result<(), CommonError> {
         let tilemap = &mut self.data.terrain_tilemap;
 
-        let terrain_json_source = self.resources.assets.json_by_name("terrain_sprites")
+        let terrain_json_source = self.resources.assets.json_by_name(TERRAIN_TYPES_ASSET)
             .ok_or_else(|| assets_err!("Failed to find terrain json asset") )?;
 
-        let terrain_json: serde_json::Value = serde_json::from_str(&terrain_json_source)
-            .map_err(|err| assets_err!("Failed to parse json: {err:?}") )?;
-
-        let obj = terrain_json.as_object()
-            .ok_or_else(|| assets_err!("Failed to parse json. terrain_json is not an object") )?;
-
-        for &key in TerrainType::names() {
-            let tile_info = obj.get(key)
-                .and_then(|v| v.as_object() )
-                .ok_or_else(|| assets_err!("Failed to find tilemap key \"{key}\"") )?;
-
-            let uv_x = tile_info.get("uv_x")
-                .and_then(|v| v.as_f64() )
-                .unwrap_or(0.0) as f32;
-
-            let uv_y = tile_info.get("uv_y")
-                .and_then(|v| v.as_f64() )
-                .unwrap_or(0.0) as f32;
+        let types = TerrainTypes::from_json(&terrain_json_source)?;
 
-            tilemap.push(TerrainSpriteData {
-                uv_offset: [uv_x, uv_y]
-            });
+        tilemap.clear();
+        for (_, info) in types.iter() {
+            for bits in 0..super::TERRAIN_AUTOTILE_COUNT {
+                let neighbors = TerrainNeighbors::from_bits_retain(bits as u8);
+                tilemap.push(TerrainSpriteData { uv_offset: info.sprite(neighbors) });
+            }
         }
 
-        assert!(tilemap.len() == TerrainType::Max as usize, "Tilemap length must fall in range for some optimization");
-
         Ok(())
     }
 
@@ -496,6 +485,12 @@ impl super::WorldModule {
         render.vertex_buffer = [res.debug_vertex.buffer];
         render.index_offset = res.debug_vertex.index_offset();
         render.vertex_offset = res.debug_vertex.vertex_offset();
+
+        let render = &mut self.render.perf;
+        render.pipeline_layout = res.pipelines.debug.layout;
+        render.vertex_buffer = [res.perf_vertex.buffer];
+        render.index_offset = res.perf_vertex.index_offset();
+        render.vertex_offset = res.perf_vertex.vertex_offset();
     }
 
     pub(super) fn setup_default_data(&mut self, core: &mut LoomzEngineCore) -> Result<(), CommonError> {
@@ -593,6 +588,7 @@ impl super::WorldModule {
         else if shader_id == debug_id  { 
             ::std::mem::swap(&mut self.resources.pipelines.debug.pipeline, &mut new_pipeline);
             self.render.debug.pipeline_handle = pipeline_handle[0];
+            self.render.perf.pipeline_handle = pipeline_handle[0];
         }
 
         new_pipeline.destroy(&core.ctx);
@@ -8,6 +8,9 @@ pub use world::*;
 mod gui;
 pub use gui::*;
 
+mod perf;
+pub use perf::*;
+
 use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
 use crate::assets::LoomzAssetsBundle;
 use crate::inputs::{SharedInputBuffer, SharedKeysState};
@@ -19,6 +22,7 @@ struct ApiInner {
     keys: SharedKeysState,
     world: WorldApi,
     gui: GuiApi,
+    perf: PerfApi,
     exit: AtomicBool,
 }
 
@@ -36,6 +40,7 @@ impl LoomzApi {
             keys: SharedKeysState::new(),
             world: WorldApi::init(),
             gui: GuiApi::init(),
+            perf: PerfApi::init(),
             exit: AtomicBool::new(false),
         };
 
@@ -78,6 +83,10 @@ impl LoomzApi {
         &self.inner.gui
     }
 
+    pub fn perf(&self) -> &PerfApi {
+        &self.inner.perf
+    }
+
     pub fn exit(&self) {
         self.inner.exit.store(true, Ordering::SeqCst);
     }
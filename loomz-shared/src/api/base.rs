@@ -128,31 +128,65 @@ struct InnerBufferEx<ID, T> {
     length_data: u32,
     buffer: Box<[Option<(ID, T)>]>,
     data: &'static mut [u8],
+    /// Data buffers replaced by a larger buffer. They are freed once the messages referencing them are read.
+    retired_data: Vec<&'static mut [u8]>,
 }
 
-/// A message queue extended to support data of varying size
+impl<ID, T> InnerBufferEx<ID, T> {
+
+    /// Doubles the number of messages the queue can hold
+    fn grow_messages(&mut self) {
+        let capacity = usize::max(self.buffer.len() * 2, 1);
+        let mut buffer = Vec::with_capacity(capacity);
+        buffer.extend(self.buffer.iter_mut().map(|message| message.take() ));
+        buffer.resize_with(capacity, || None);
+        self.buffer = buffer.into_boxed_slice();
+    }
+
+    /// Replaces the data buffer with a buffer of at least `min_size` bytes. The messages not yet read keep referencing the old buffer.
+    fn grow_data(&mut self, min_size: usize) {
+        let size = usize::max(self.data.len() * 2, min_size);
+        let data = Box::leak(vec![0u8; size].into_boxed_slice());
+        let old_data = ::std::mem::replace(&mut self.data, data);
+        self.retired_data.push(old_data);
+        self.length_data = 0;
+    }
+
+    /// Frees the retired data buffers. Must only be called when the queue holds no message.
+    fn free_retired_data(&mut self) {
+        for data in self.retired_data.drain(..) {
+            // Safety: the buffers were leaked from a boxed slice and no message references them anymore
+            unsafe { drop(Box::from_raw(data as *mut [u8])); }
+        }
+    }
+
+}
+
+/// A message queue extended to support data of varying size.
+/// The queue grows when a message or its data does not fit, so messages are never dropped.
 pub struct MessageQueueEx<ID, T> {
     inner: Mutex<InnerBufferEx<ID, T>>,
 }
 
 impl<ID: Clone, T> MessageQueueEx<ID, T> {
 
-    /// Creates a new extended queue with a capacity of `cap_message` size
-    /// and a capacity of `cap_data_bytes` of extra data
+    /// Creates a new extended queue with an initial capacity of `cap_message` size
+    /// and an initial capacity of `cap_data_bytes` of extra data
     pub fn with_capacity(cap_message: usize, cap_data_bytes: usize) -> Self {
         let mut buffer = Vec::with_capacity(cap_message);
         for _ in 0..cap_message {
             buffer.push(None);
         }
 
-        let data = vec![0u8; cap_data_bytes];
+        let data = vec![0u8; cap_data_bytes].into_boxed_slice();
 
         MessageQueueEx {
             inner: Mutex::new(InnerBufferEx {
                 length_message: 0,
                 length_data: 0,
                 buffer: buffer.into_boxed_slice(),
-                data: data.leak(),
+                data: Box::leak(data),
+                retired_data: Vec::new(),
             }),
         }
     }
@@ -160,13 +194,12 @@ impl<ID: Clone, T> MessageQueueEx<ID, T> {
     pub fn push(&self, id: &ID, message: T) {
         let mut inner = self.inner.lock();
         let next_index = inner.length_message as usize;
-        let max_index = inner.buffer.len();
-        if next_index >= max_index {
-            println!("ERROR: Not enough capacity to hold more than {} messages. Increase the message queue capacity", max_index);
-        } else {
-            inner.length_message += 1;
-            inner.buffer[next_index] = Some((id.clone(), message));
+        if next_index >= inner.buffer.len() {
+            inner.grow_messages();
         }
+
+        inner.length_message += 1;
+        inner.buffer[next_index] = Some((id.clone(), message));
     }
 
     pub fn push_with_data<D: Copy+'static, F0>(&self, id: &ID, src_data: &[D], generate_message: F0) where
@@ -175,16 +208,19 @@ impl<ID: Clone, T> MessageQueueEx<ID, T> {
         let mut inner = self.inner.lock();
 
         let next_index = inner.length_message as usize;
+        if next_index == 0 && !inner.retired_data.is_empty() {
+            inner.free_retired_data();
+        }
+
         if next_index >= inner.buffer.len() {
-            println!("ERROR: Not enough capacity to hold more than {} messages. Increase the message queue capacity", inner.buffer.len());
-            return;
+            inner.grow_messages();
         }
 
-        let next_data_offset = align(inner.length_data as usize, align_of::<D>());
+        let mut next_data_offset = align(inner.length_data as usize, align_of::<D>());
         let data_size = src_data.len() * size_of::<D>();
         if next_data_offset + data_size > inner.data.len() {
-            println!("ERROR: Not enough capacity to hold more than {} bytes of extra data. Increase the buffer capacity", inner.data.len());
-            return;
+            inner.grow_data(data_size);
+            next_data_offset = 0;
         }
 
         // Copy data into the queue buffer then run the callback
@@ -212,14 +248,16 @@ impl<ID: Clone, T> MessageQueueEx<ID, T> {
             return None;
         }
 
+        // Messages left over by an iterator that was not consumed are never read again
+        let length = inner.length_message as usize;
         inner.length_message = 0;
         inner.length_data = 0;
 
         let mut index = 0;
         let it = ::std::iter::from_fn(move || {
-            let next_value = match inner.buffer.get_mut(index) {
-                Some(op) => op.take(),
-                None => None,
+            let next_value = match index < length {
+                true => inner.buffer[index].take(),
+                false => None,
             };
             index += 1;
             next_value
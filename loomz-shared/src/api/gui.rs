@@ -1,3 +1,4 @@
+use parking_lot::Mutex;
 use crate::base_types::{RectF32, RgbaU8};
 use crate::assets::{MsdfFontId, TextureId};
 use super::{Id, MessageQueueEx};
@@ -17,22 +18,30 @@ pub struct GuiSprite {
     pub ty: GuiSpriteType,
     pub position: RectF32,
     pub texcoord: RectF32,
-    pub color: RgbaU8
+    pub color: RgbaU8,
+    /// Clipping rectangle of the sprite in screen coordinates. A rect with no area means the sprite is not clipped.
+    pub clip: RectF32,
 }
 
 pub enum GuiApiUpdate {
     ToggleGui(bool),
+    SetLayer(i32),
     UpdateSprites(&'static [GuiSprite]),
+    /// Replaces the sprites of the gui starting at the index. The number of sprites of the gui does not change.
+    PatchSprites(u32, &'static [GuiSprite]),
 }
 
 pub struct GuiApi {
-    gui: MessageQueueEx<GuiId, GuiApiUpdate>
+    gui: MessageQueueEx<GuiId, GuiApiUpdate>,
+    /// Values of the gui ids whose sprites must be sent again with `update_gui`
+    resend: Mutex<Vec<u32>>,
 }
 
 impl GuiApi {
     pub fn init() -> Self {
         GuiApi {
-            gui: MessageQueueEx::with_capacity(8, 5120),
+            gui: MessageQueueEx::with_capacity(64, 32768),
+            resend: Mutex::new(Vec::new()),
         }
     }
 
@@ -40,10 +49,40 @@ impl GuiApi {
         self.gui.push(id, GuiApiUpdate::ToggleGui(visible));
     }
 
+    /// Guis with a higher layer are drawn over the guis with a lower layer. Guis on the same layer are drawn in creation order.
+    pub fn set_gui_layer(&self, id: &GuiId, layer: i32) {
+        self.gui.push(id, GuiApiUpdate::SetLayer(layer));
+    }
+
     pub fn update_gui(&self, id: &GuiId, sprites: &[GuiSprite]) {
         self.gui.push_with_data(id, sprites, |sprites| GuiApiUpdate::UpdateSprites(sprites) );
     }
 
+    /// Replaces the sprites of the gui starting at `first_sprite`. Cheaper than `update_gui` when only a few components changed.
+    pub fn patch_gui(&self, id: &GuiId, first_sprite: u32, sprites: &[GuiSprite]) {
+        self.gui.push_with_data(id, sprites, |sprites| GuiApiUpdate::PatchSprites(first_sprite, sprites) );
+    }
+
+    /// Asks the client to send all the sprites of the gui again. Used by the engine when it cannot apply a patch.
+    pub fn request_resend(&self, id_value: u32) {
+        let mut resend = self.resend.lock();
+        if !resend.contains(&id_value) {
+            resend.push(id_value);
+        }
+    }
+
+    /// Returns `true` if the engine asked for all the sprites of the gui since the last call
+    pub fn take_resend_request(&self, id: &GuiId) -> bool {
+        let mut resend = self.resend.lock();
+        match resend.iter().position(|&value| value == id.value() ) {
+            Some(index) => {
+                resend.swap_remove(index);
+                true
+            },
+            None => false
+        }
+    }
+
     pub fn gui_updates<'a>(&'a self) -> Option<impl Iterator<Item = (GuiId, GuiApiUpdate)> + 'a> {
         self.gui.read_values()
     }
@@ -0,0 +1,131 @@
+use parking_lot::Mutex;
+use crate::RectF32;
+
+/// Number of frames kept in the frame time history
+pub const PERF_HISTORY_SIZE: usize = 120;
+
+/// Performance counters of the last frames. Times are in milliseconds.
+#[derive(Copy, Clone)]
+pub struct PerfStats {
+    /// Rolling history of the frame times. `frame_index` is the index of the next frame to write.
+    pub frame_times: [f32; PERF_HISTORY_SIZE],
+    pub frame_index: usize,
+    /// Number of frame times pushed since the start. Changes every time a frame time is added to the history.
+    pub frame_count: u64,
+    pub client_update_ms: f32,
+    pub engine_update_ms: f32,
+    pub engine_record_ms: f32,
+    pub gpu_ms: f32,
+    pub actors: u32,
+    pub terrain_batches: u32,
+    pub gui_sprites: u32,
+    /// Bytes of staging memory uploaded in the last frame
+    pub staging_bytes: u64,
+}
+
+impl PerfStats {
+
+    /// Iterates over the frame times from the oldest to the newest
+    pub fn frame_times(&self) -> impl Iterator<Item = f32> + '_ {
+        let (newest, oldest) = self.frame_times.split_at(self.frame_index);
+        oldest.iter().chain(newest.iter()).copied()
+    }
+
+    /// Average frame time of the history. Frames not yet recorded are ignored.
+    pub fn average_frame_time(&self) -> f32 {
+        let (total, count) = self.frame_times.iter()
+            .filter(|&&time| time > 0.0)
+            .fold((0.0, 0), |(total, count), &time| (total + time, count + 1) );
+
+        match count {
+            0 => 0.0,
+            count => total / (count as f32)
+        }
+    }
+
+    pub fn fps(&self) -> f32 {
+        let time = self.average_frame_time();
+        if time > 0.0 { 1000.0 / time } else { 0.0 }
+    }
+}
+
+impl Default for PerfStats {
+    fn default() -> Self {
+        PerfStats {
+            frame_times: [0.0; PERF_HISTORY_SIZE],
+            frame_index: 0,
+            frame_count: 0,
+            client_update_ms: 0.0,
+            engine_update_ms: 0.0,
+            engine_record_ms: 0.0,
+            gpu_ms: 0.0,
+            actors: 0,
+            terrain_batches: 0,
+            gui_sprites: 0,
+            staging_bytes: 0,
+        }
+    }
+}
+
+/// Counters gathered by the engine each frame
+#[derive(Copy, Clone, Default)]
+pub struct PerfEngineStats {
+    pub frame_ms: f32,
+    pub update_ms: f32,
+    pub record_ms: f32,
+    pub gpu_ms: f32,
+    pub actors: u32,
+    pub terrain_batches: u32,
+    pub gui_sprites: u32,
+    pub staging_bytes: u64,
+}
+
+pub struct PerfApi {
+    stats: Mutex<PerfStats>,
+    /// Screen area of the frame time graph. `None` if the graph is hidden.
+    graph: Mutex<Option<RectF32>>,
+}
+
+impl PerfApi {
+
+    pub fn init() -> Self {
+        PerfApi {
+            stats: Mutex::new(PerfStats::default()),
+            graph: Mutex::new(None),
+        }
+    }
+
+    /// Returns a copy of the current stats
+    pub fn stats(&self) -> PerfStats {
+        *self.stats.lock()
+    }
+
+    pub fn set_client_update_time(&self, update_ms: f32) {
+        self.stats.lock().client_update_ms = update_ms;
+    }
+
+    pub fn push_engine_stats(&self, engine: PerfEngineStats) {
+        let mut stats = self.stats.lock();
+        let index = stats.frame_index;
+        stats.frame_times[index] = engine.frame_ms;
+        stats.frame_index = (index + 1) % PERF_HISTORY_SIZE;
+        stats.frame_count += 1;
+        stats.engine_update_ms = engine.update_ms;
+        stats.engine_record_ms = engine.record_ms;
+        stats.gpu_ms = engine.gpu_ms;
+        stats.actors = engine.actors;
+        stats.terrain_batches = engine.terrain_batches;
+        stats.gui_sprites = engine.gui_sprites;
+        stats.staging_bytes = engine.staging_bytes;
+    }
+
+    /// Shows the frame time graph in `area` (in pixels), or hides it if `area` is `None`
+    pub fn set_graph_area(&self, area: Option<RectF32>) {
+        *self.graph.lock() = area;
+    }
+
+    pub fn graph_area(&self) -> Option<RectF32> {
+        *self.graph.lock()
+    }
+
+}
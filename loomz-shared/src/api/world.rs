@@ -28,22 +28,63 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Neighbors of a terrain cell that have the same terrain type as the cell. Used to select the autotile sprite
+    /// of the cell. Neighbors outside of the terrain count as the same type.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct TerrainNeighbors: u8 {
+        const N  = 0b0000_0001;
+        const NE = 0b0000_0010;
+        const E  = 0b0000_0100;
+        const SE = 0b0000_1000;
+        const S  = 0b0001_0000;
+        const SW = 0b0010_0000;
+        const W  = 0b0100_0000;
+        const NW = 0b1000_0000;
+    }
+}
+
+impl TerrainNeighbors {
+    /// Offsets of the neighbors, in the order of the bits
+    pub const OFFSETS: [[i32; 2]; 8] = [[0, -1], [1, -1], [1, 0], [1, 1], [0, 1], [-1, 1], [-1, 0], [-1, -1]];
+
+    /// Parses a direction of the terrain json. Ex: `"ne"`
+    pub fn from_direction(name: &str) -> Option<Self> {
+        match name {
+            "n" => Some(Self::N),
+            "ne" => Some(Self::NE),
+            "e" => Some(Self::E),
+            "se" => Some(Self::SE),
+            "s" => Some(Self::S),
+            "sw" => Some(Self::SW),
+            "w" => Some(Self::W),
+            "nw" => Some(Self::NW),
+            _ => None
+        }
+    }
+}
+
+impl Default for TerrainNeighbors {
+    fn default() -> Self {
+        TerrainNeighbors::all()
+    }
+}
+
+/// Index of a terrain type in the terrain json. See `assets::terrain::TerrainTypes`.
+/// The first terrain type is the default terrain.
+#[repr(transparent)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
-pub enum TerrainType {
-    #[default]
-    Grass,
-    Sand,
-    Water,
-    Max,
+pub struct TerrainType(pub u8);
+
+impl From<u32> for TerrainType {
+    fn from(value: u32) -> Self {
+        TerrainType(value as u8)
+    }
 }
 
-impl TerrainType {
-    pub const fn names() -> &'static [&'static str] {
-        &[
-            "grass",
-            "sand",
-            "water",
-        ]
+impl From<TerrainType> for u32 {
+    fn from(value: TerrainType) -> Self {
+        value.0 as u32
     }
 }
 
@@ -53,6 +94,7 @@ pub struct WorldTerrainChunk {
     pub position: PositionU32,
     pub view: RectF32,
     pub cells: TerrainChunk<TerrainType>,
+    pub neighbors: TerrainChunk<TerrainNeighbors>,
 }
 
 impl WorldTerrainChunk {
@@ -62,7 +104,8 @@ impl WorldTerrainChunk {
         WorldTerrainChunk {
             position: PositionU32 { x: chunk_x as u32, y: chunk_y as u32 },
             view: rect(x, y, x+stride_px, y+stride_px),
-            cells: Default::default()
+            cells: Default::default(),
+            neighbors: Default::default(),
         }
     }
 }
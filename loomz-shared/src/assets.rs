@@ -1,5 +1,6 @@
 pub mod ktx;
 pub mod msdf_font;
+pub mod terrain;
 
 mod static_bundle;
 
@@ -30,7 +30,7 @@ fn init_asset_reload() -> Result<AssetReloadState, CommonError> {
                 let id = AssetId::Texture(TextureId(state.textures.len() as u32));
                 let src = args[2].to_string();
                 state.paths_to_id.push((src.clone(), id));
-                state.json.push(src);
+                state.textures.push(src);
             },
             "JSON" => {
                 let id = AssetId::Json(JsonId(state.json.len() as u32));
@@ -87,7 +87,17 @@ fn filter_asset_change(state: &AssetReloadState, dedup: &mut fnv::FnvHashSet<Ass
 fn reload_asset(state: &AssetReloadState, assets: &mut super::static_bundle::LoomzAssetsBundle, id: AssetId) {
     match id {
         AssetId::Texture(_) => { /* TODO */ },
-        AssetId::Json(_) => { /* TODO */ }
+        AssetId::Json(JsonId(id)) => {
+            let src = match state.json.get(id as usize) {
+                Some(src) => src,
+                None => { return; }
+            };
+
+            let path = format!("./assets/{}", src);
+            if let (Ok(data), Some(json)) = (::std::fs::read_to_string(&path), assets.json.get_mut(id as usize)) {
+                *json = data;
+            }
+        }
         AssetId::MsdfFont(_) => { /* TODO */ }
         AssetId::ShaderId(ShaderId(id)) => {
             let shader = match assets.shaders.get_mut(id as usize) {
@@ -149,9 +159,14 @@ fn start_assets_watcher(bundle: Arc<LoomzAssetsBundle>) -> Result<(), CommonErro
             if dedup.len() > 0 {
                 let mut assets = bundle.bundle.lock();
                 let mut changed = bundle.changed.lock();
+                let mut json_versions = bundle.json_versions.lock();
                 for &id in dedup.iter() {
                     changed.insert(id);
                     reload_asset(&state, &mut assets, id);
+
+                    if let AssetId::Json(JsonId(json_id)) = id {
+                        *json_versions.entry(json_id).or_insert(0) += 1;
+                    }
                 }
 
                 dedup.clear();
@@ -169,6 +184,7 @@ fn start_assets_watcher(bundle: Arc<LoomzAssetsBundle>) -> Result<(), CommonErro
 pub struct LoomzAssetsBundle {
     bundle: Mutex<super::static_bundle::LoomzAssetsBundle>,
     changed: Mutex<fnv::FnvHashSet<AssetId>>,
+    json_versions: Mutex<fnv::FnvHashMap<u32, u32>>,
 }
 
 impl LoomzAssetsBundle {
@@ -178,7 +194,8 @@ impl LoomzAssetsBundle {
 
         let bundle = Arc::new(LoomzAssetsBundle {
             bundle: Mutex::new(inner_bundle),
-            changed: Mutex::new(fnv::FnvHashSet::default())
+            changed: Mutex::new(fnv::FnvHashSet::default()),
+            json_versions: Mutex::new(fnv::FnvHashMap::default()),
         });
 
         let watcher_bundle = Arc::clone(&bundle);
@@ -211,6 +228,10 @@ impl LoomzAssetsBundle {
         self.bundle.lock().font(id).cloned()
     }
 
+    pub fn fallback_font_id(&self, font: MsdfFontId, c: &str) -> Option<MsdfFontId> {
+        self.bundle.lock().fallback_font_id(font, c)
+    }
+
     pub fn default_font_id(&self) -> Option<MsdfFontId> {
         self.bundle.lock().default_font_id()
     }
@@ -223,6 +244,11 @@ impl LoomzAssetsBundle {
         self.bundle.lock().shader(id).cloned()
     }
 
+    /// Number of times the json asset was reloaded
+    pub fn json_version(&self, id: JsonId) -> u32 {
+        self.json_versions.lock().get(&id.0).copied().unwrap_or(0)
+    }
+
     pub fn changed_assets(&self) -> Option<Vec<AssetId>> {
         let mut changed = self.changed.lock();
         if changed.len() > 0 {
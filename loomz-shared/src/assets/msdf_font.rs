@@ -57,19 +57,23 @@ impl MsdfFontData {
         Ok(data)
     }
 
-    pub fn compute_glyph(&self, c: &str, scale: f32, glyph: &mut ComputedGlyph) -> f32 {
-        // Multi characters glyph not supported
-        let chr = match c.len() == 1 {
-            true => c.chars().next().unwrap_or('?'),
-            false => '?'
-        };
+    /// Returns `true` if the atlas has a glyph for the grapheme `c`. Multi characters graphemes are never in the atlas.
+    pub fn has_glyph(&self, c: &str) -> bool {
+        self.atlas_glyph(c).is_some()
+    }
 
+    /// Computes the glyph of the grapheme `c`. Graphemes missing from the atlas are drawn as '?'.
+    /// See `LoomzAssetsBundle::fallback_font_id` to find a font that has the glyph instead.
+    pub fn compute_glyph(&self, c: &str, scale: f32, glyph: &mut ComputedGlyph) -> f32 {
         let atlas_height = self.info.height;
-        let atlas_glyph = self.glyphs.get(chr as usize).copied().unwrap_or_default();
+        let atlas_glyph = match self.atlas_glyph(c) {
+            Some(glyph) => glyph,
+            None => self.glyphs.get('?' as usize).copied().unwrap_or_default(),
+        };
 
-        let line_height = self.info.line_height * 0.75;
-        let top = line_height - atlas_glyph.plane_bound[1];
-        let bottom = line_height - atlas_glyph.plane_bound[3];
+        let baseline = self.baseline(1.0);
+        let top = baseline - atlas_glyph.plane_bound[1];
+        let bottom = baseline - atlas_glyph.plane_bound[3];
 
         glyph.position.left = scale * atlas_glyph.plane_bound[0];
         glyph.position.top = scale * top;
@@ -83,4 +87,23 @@ impl MsdfFontData {
 
         atlas_glyph.advance * scale
     }
+
+    fn atlas_glyph(&self, c: &str) -> Option<AtlasGlyph> {
+        // Multi characters glyph not supported
+        let mut chars = c.chars();
+        let chr = match (chars.next(), chars.next()) {
+            (Some(chr), None) => chr,
+            _ => { return None; }
+        };
+
+        match self.glyphs.get(chr as usize) {
+            Some(glyph) if glyph.unicode == chr as u32 => Some(*glyph),
+            _ => None,
+        }
+    }
+
+    /// Distance between the top of the computed glyphs and the baseline of the text
+    pub fn baseline(&self, scale: f32) -> f32 {
+        self.info.line_height * 0.75 * scale
+    }
 }
@@ -55,6 +55,15 @@ impl LoomzAssetsBundle {
         self.msdf_fonts.get(id.0 as usize)
     }
 
+    /// Returns the first font, in the order of the assets metadata, that has a glyph for `c`, skipping `font`.
+    /// Text drawn with `font` uses it for the graphemes that `font` is missing.
+    pub fn fallback_font_id(&self, font: MsdfFontId, c: &str) -> Option<MsdfFontId> {
+        self.msdf_fonts.iter()
+            .enumerate()
+            .find(|(index, asset)| *index != font.0 as usize && asset.font_data.has_glyph(c) )
+            .map(|(index, _)| MsdfFontId(index as u32) )
+    }
+
     pub fn default_font_id(&self) -> Option<MsdfFontId> {
         if self.msdf_fonts.len() == 0 {
             None
@@ -79,6 +88,12 @@ impl LoomzAssetsBundle {
         None
     }
 
+    /// Number of times the json asset was reloaded. Json assets are never reloaded in the static bundle.
+    #[allow(dead_code)]
+    pub fn json_version(&self, _id: JsonId) -> u32 {
+        0
+    }
+
     pub(super) fn load_base_bundle() -> Result<Self, CommonError> {
         let mut bundle = LoomzAssetsBundle::default();
         let meta_csv = Self::load_asset_metadata()?;
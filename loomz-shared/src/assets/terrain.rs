@@ -0,0 +1,264 @@
+//! Terrain types, loaded from the `terrain_sprites` json asset. Format:
+//!
+//! ```json
+//! {
+//!   "types": [
+//!     { "name": "grass", "uv_x": 0, "uv_y": 0, "walkable": true, "movement_cost": 1.0 },
+//!     {
+//!       "name": "sand", "uv_x": 64, "uv_y": 0, "walkable": true, "movement_cost": 1.5,
+//!       "autotile": [
+//!         { "same": ["e", "s"], "ignore": ["ne", "nw", "sw"], "uv_x": 0, "uv_y": 64 }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Terrain cells store the index of their type in `types`. New types must be added at the end of the list,
+//! so that the terrains saved before keep their types. The first type is the default terrain.
+//!
+//! The first autotile rule matching the neighbors of a cell selects its sprite. `same` lists the neighbors that must have
+//! the same type as the cell, `ignore` the neighbors that are not checked. The other neighbors must have a different type.
+//! Cells that match no rule use the base sprite (`uv_x`, `uv_y`).
+//!
+//! The older format, an object keyed by type name (`{ "grass": { "uv_x": 0, "uv_y": 0 }, ... }`), is still accepted.
+//! Its types are read in the order of `LEGACY_TYPE_NAMES`, the order of the types before they were loaded from the json.
+use crate::api::{TerrainType, TerrainNeighbors};
+use crate::{assets_err, CommonError};
+
+/// Name of the json asset holding the terrain types
+pub const TERRAIN_TYPES_ASSET: &str = "terrain_sprites";
+
+/// Terrain types of the older terrain json format, in the order stored by the terrain cells
+const LEGACY_TYPE_NAMES: [&str; 3] = ["grass", "sand", "water"];
+
+#[derive(Copy, Clone, Debug)]
+pub struct TerrainAutotileRule {
+    pub same: TerrainNeighbors,
+    pub ignore: TerrainNeighbors,
+    pub uv_offset: [f32; 2],
+}
+
+impl TerrainAutotileRule {
+    pub fn matches(&self, neighbors: TerrainNeighbors) -> bool {
+        (neighbors - self.ignore) == self.same
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TerrainTypeInfo {
+    pub name: String,
+    pub uv_offset: [f32; 2],
+    pub walkable: bool,
+    /// Cost of moving through a cell of this type. `1.0` is the cost of a plain cell.
+    pub movement_cost: f32,
+    pub autotile: Vec<TerrainAutotileRule>,
+}
+
+impl TerrainTypeInfo {
+    /// Returns the sprite of a cell of this type with `neighbors`
+    pub fn sprite(&self, neighbors: TerrainNeighbors) -> [f32; 2] {
+        self.autotile.iter()
+            .find(|rule| rule.matches(neighbors) )
+            .map(|rule| rule.uv_offset )
+            .unwrap_or(self.uv_offset)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct TerrainTypes {
+    types: Vec<TerrainTypeInfo>,
+}
+
+impl TerrainTypes {
+
+    pub fn from_json(source: &str) -> Result<Self, CommonError> {
+        let json: serde_json::Value = serde_json::from_str(source)
+            .map_err(|err| assets_err!("Failed to parse terrain json: {err:?}") )?;
+
+        let types_json = match json.get("types") {
+            Some(types_json) => types_json.as_array()
+                .ok_or_else(|| assets_err!("Failed to parse terrain json. \"types\" is not an array") )?,
+            None => { return Self::from_legacy_json(&json); }
+        };
+
+        if types_json.is_empty() || types_json.len() > (u8::MAX as usize) + 1 {
+            return Err(assets_err!("Terrain json must define between 1 and 256 terrain types"));
+        }
+
+        let mut types = Vec::with_capacity(types_json.len());
+        for type_json in types_json {
+            let name = type_json.get("name")
+                .and_then(|v| v.as_str() )
+                .ok_or_else(|| assets_err!("Terrain type without a name in terrain json") )?;
+
+            types.push(Self::parse_type(name, type_json)?);
+        }
+
+        Ok(TerrainTypes { types })
+    }
+
+    /// Parses the older terrain json format, an object keyed by type name
+    fn from_legacy_json(json: &serde_json::Value) -> Result<Self, CommonError> {
+        let obj = json.as_object()
+            .ok_or_else(|| assets_err!("Failed to parse terrain json. The json is not an object") )?;
+
+        let mut types = Vec::with_capacity(LEGACY_TYPE_NAMES.len());
+        for name in LEGACY_TYPE_NAMES {
+            let type_json = obj.get(name)
+                .ok_or_else(|| assets_err!("Failed to parse terrain json. Missing \"types\" or terrain type {name:?}") )?;
+
+            types.push(Self::parse_type(name, type_json)?);
+        }
+
+        Ok(TerrainTypes { types })
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    pub fn get(&self, ty: TerrainType) -> Option<&TerrainTypeInfo> {
+        self.types.get(ty.0 as usize)
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<TerrainType> {
+        self.types.iter()
+            .position(|info| info.name == name )
+            .map(|index| TerrainType(index as u8) )
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (TerrainType, &TerrainTypeInfo)> {
+        self.types.iter()
+            .enumerate()
+            .map(|(index, info)| (TerrainType(index as u8), info) )
+    }
+
+    fn parse_type(name: &str, type_json: &serde_json::Value) -> Result<TerrainTypeInfo, CommonError> {
+        let autotile = match type_json.get("autotile") {
+            Some(rules) => Self::parse_autotile_rules(name, rules)?,
+            None => Vec::new(),
+        };
+
+        let info = TerrainTypeInfo {
+            name: name.to_string(),
+            uv_offset: parse_uv(type_json),
+            walkable: type_json.get("walkable").and_then(|v| v.as_bool() ).unwrap_or(true),
+            movement_cost: type_json.get("movement_cost").and_then(|v| v.as_f64() ).unwrap_or(1.0) as f32,
+            autotile,
+        };
+
+        Ok(info)
+    }
+
+    fn parse_autotile_rules(name: &str, rules: &serde_json::Value) -> Result<Vec<TerrainAutotileRule>, CommonError> {
+        let rules = rules.as_array()
+            .ok_or_else(|| assets_err!("Autotile rules of terrain type {name:?} must be an array") )?;
+
+        let parse_neighbors = |rule: &serde_json::Value, field: &str| -> Result<TerrainNeighbors, CommonError> {
+            let mut neighbors = TerrainNeighbors::empty();
+            let directions = rule.get(field)
+                .and_then(|v| v.as_array() )
+                .map(|v| v.as_slice() )
+                .unwrap_or(&[]);
+
+            for direction in directions {
+                neighbors |= direction.as_str()
+                    .and_then(TerrainNeighbors::from_direction)
+                    .ok_or_else(|| assets_err!("Invalid direction {direction} in autotile rules of terrain type {name:?}") )?;
+            }
+
+            Ok(neighbors)
+        };
+
+        let mut parsed = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let ignore = parse_neighbors(rule, "ignore")?;
+            let same = parse_neighbors(rule, "same")? - ignore;
+            parsed.push(TerrainAutotileRule { same, ignore, uv_offset: parse_uv(rule) });
+        }
+
+        Ok(parsed)
+    }
+
+}
+
+fn parse_uv(value: &serde_json::Value) -> [f32; 2] {
+    let uv_x = value.get("uv_x").and_then(|v| v.as_f64() ).unwrap_or(0.0) as f32;
+    let uv_y = value.get("uv_y").and_then(|v| v.as_f64() ).unwrap_or(0.0) as f32;
+    [uv_x, uv_y]
+}
+
+#[cfg(test)]
+mod terrain_types_tests {
+    use super::*;
+
+    fn types_json(count: usize) -> String {
+        let types: Vec<String> = (0..count).map(|index| format!("{{ \"name\": \"type{index}\" }}") ).collect();
+        format!("{{ \"types\": [{}] }}", types.join(","))
+    }
+
+    #[test]
+    fn types_count() {
+        assert!(TerrainTypes::from_json(&types_json(0)).is_err());
+        assert!(TerrainTypes::from_json(&types_json(1)).unwrap().len() == 1);
+        assert!(TerrainTypes::from_json(&types_json(256)).unwrap().len() == 256);
+        assert!(TerrainTypes::from_json(&types_json(257)).is_err());
+    }
+
+    #[test]
+    fn by_name() {
+        let types = TerrainTypes::from_json(&types_json(3)).unwrap();
+        assert!(types.by_name("type0") == Some(TerrainType(0)));
+        assert!(types.by_name("type2") == Some(TerrainType(2)));
+        assert!(types.by_name("type3").is_none());
+        assert!(types.get(TerrainType(1)).unwrap().name == "type1");
+    }
+
+    #[test]
+    fn autotile() {
+        let source = r#"{ "types": [{
+            "name": "sand", "uv_x": 64, "uv_y": 0,
+            "autotile": [
+                { "same": ["e", "s", "ne"], "ignore": ["ne", "nw", "sw"], "uv_x": 0, "uv_y": 64 }
+            ]
+        }] }"#;
+
+        let types = TerrainTypes::from_json(source).unwrap();
+        let sand = types.get(TerrainType(0)).unwrap();
+        let rule = sand.autotile[0];
+
+        // Ignored neighbors are removed from the `same` neighbors
+        assert!(rule.same == TerrainNeighbors::E | TerrainNeighbors::S);
+        assert!(rule.ignore == TerrainNeighbors::NE | TerrainNeighbors::NW | TerrainNeighbors::SW);
+
+        assert!(sand.sprite(TerrainNeighbors::E | TerrainNeighbors::S | TerrainNeighbors::NW) == [0.0, 64.0]);
+        assert!(sand.sprite(TerrainNeighbors::E | TerrainNeighbors::S | TerrainNeighbors::N) == [64.0, 0.0]);
+
+        let invalid = r#"{ "types": [{ "name": "sand", "autotile": [{ "same": ["up"] }] }] }"#;
+        assert!(TerrainTypes::from_json(invalid).is_err());
+    }
+
+    #[test]
+    fn legacy_format() {
+        let source = r#"{
+            "water": { "uv_x": 128, "uv_y": 0 },
+            "grass": { "uv_x": 0, "uv_y": 0 },
+            "sand": { "uv_x": 64, "uv_y": 0, "autotile": [{ "same": ["e"], "uv_x": 0, "uv_y": 64 }] }
+        }"#;
+
+        let types = TerrainTypes::from_json(source).unwrap();
+        assert!(types.len() == 3);
+        assert!(types.by_name("grass") == Some(TerrainType(0)));
+        assert!(types.by_name("sand") == Some(TerrainType(1)));
+        assert!(types.by_name("water") == Some(TerrainType(2)));
+        assert!(types.get(TerrainType(2)).unwrap().uv_offset == [128.0, 0.0]);
+        assert!(types.get(TerrainType(1)).unwrap().autotile.len() == 1);
+
+        assert!(TerrainTypes::from_json(r#"{ "grass": {} }"#).is_err());
+    }
+}
@@ -70,6 +70,24 @@ impl RectF32 {
         x >= self.left && y >= self.top && x < self.right && y < self.bottom
     }
 
+    /// Returns the area shared by both rects. If the rects do not intersect, the returned rect has no area.
+    #[inline]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let left = f32::max(self.left, other.left);
+        let top = f32::max(self.top, other.top);
+        RectF32 {
+            left,
+            top,
+            right: f32::max(left, f32::min(self.right, other.right)),
+            bottom: f32::max(top, f32::min(self.bottom, other.bottom)),
+        }
+    }
+
+    #[inline]
+    pub fn has_area(&self) -> bool {
+        self.right > self.left && self.bottom > self.top
+    }
+
     #[inline]
     pub fn intersects(&self, other: &Self) -> bool {
         if self.right < other.left || other.right < self.left {
@@ -85,6 +103,18 @@ impl RectF32 {
 
 }
 
+impl Mul<f32> for RectF32 {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        RectF32 {
+            left: self.left * rhs,
+            top: self.top * rhs,
+            right: self.right * rhs,
+            bottom: self.bottom * rhs,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Default, Debug)]
 pub struct RgbaU8 {
     pub r: u8,
@@ -148,6 +178,16 @@ impl PositionF32 {
     }
 }
 
+impl Mul<f32> for PositionF32 {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self::Output {
+        PositionF32 {
+            x: self.x * rhs,
+            y: self.y * rhs
+        }
+    }
+}
+
 impl AddAssign<PositionF64> for PositionF32 {
     fn add_assign(&mut self, rhs: PositionF64) {
         self.x += rhs.x as f32;
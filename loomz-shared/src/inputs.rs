@@ -6,7 +6,7 @@ use parking_lot::{Mutex, MutexGuard};
 use std::sync::atomic::{AtomicU8, Ordering};
 
 use std::sync::Arc;
-use crate::base_types::{PositionF64, SizeF32};
+use crate::base_types::{PositionF32, PositionF64, SizeF32};
 
 bitflags! {
     #[derive(Copy, Clone)]
@@ -15,6 +15,8 @@ bitflags! {
         const MOUSE_MOVE       = 0b00000010;
         const MOUSE_BTN        = 0b00000100;
         const UPDATED_KEYSTATE = 0b00001000;
+        const MOUSE_WHEEL      = 0b00010000;
+        const SCALE_FACTOR     = 0b00100000;
     }
 }
 
@@ -43,7 +45,10 @@ pub struct InputBuffer {
     pub cursor_position: PositionF64,
     pub mouse_buttons_old: MouseButtonState,
     pub mouse_buttons: MouseButtonState,
+    pub mouse_wheel: PositionF32,
     pub screen_size: SizeF32,
+    /// Ratio between the physical pixels and the logical pixels of the window (ex: `2.0` on most 4K laptop screens)
+    pub scale_factor: f32,
 }
 
 impl InputBuffer {
@@ -54,7 +59,9 @@ impl InputBuffer {
             cursor_position: PositionF64 { x: 0.0, y: 0.0 },
             mouse_buttons_old: MouseButtonState::empty(),
             mouse_buttons: MouseButtonState::empty(),
+            mouse_wheel: PositionF32 { x: 0.0, y: 0.0 },
             screen_size,
+            scale_factor: 1.0,
         }
     }
 
@@ -89,6 +96,10 @@ impl SharedInputBuffer {
         }
     }
 
+    pub fn cursor_position_value(&self) -> PositionF64 {
+        self.lock().cursor_position
+    }
+
     pub fn cursor_position_delta(&self) -> PositionF64 {
         let inputs = self.lock();
         inputs.cursor_position - inputs.cursor_position_old
@@ -133,6 +144,21 @@ impl SharedInputBuffer {
         self.set_flags(InputUpdateFlags::MOUSE_BTN);
     }
 
+    /// Returns the mouse wheel movement in pixels since the last update. Positive `y` means the wheel was scrolled up.
+    pub fn mouse_wheel(&self) -> Option<PositionF32> {
+        match self.flags().contains(InputUpdateFlags::MOUSE_WHEEL) {
+            true => Some(self.lock().mouse_wheel),
+            false => None
+        }
+    }
+
+    pub fn update_mouse_wheel(&self, x: f32, y: f32) {
+        let mut buffer = self.lock();
+        buffer.mouse_wheel.x += x;
+        buffer.mouse_wheel.y += y;
+        self.set_flags(InputUpdateFlags::MOUSE_WHEEL);
+    }
+
     pub fn screen_size(&self) -> Option<SizeF32> {
         match self.flags().contains(InputUpdateFlags::SCREEN_RESIZED) {
             true => Some(self.lock().screen_size),
@@ -149,6 +175,22 @@ impl SharedInputBuffer {
         self.set_flags(InputUpdateFlags::SCREEN_RESIZED);
     }
 
+    pub fn scale_factor(&self) -> Option<f32> {
+        match self.flags().contains(InputUpdateFlags::SCALE_FACTOR) {
+            true => Some(self.lock().scale_factor),
+            false => None
+        }
+    }
+
+    pub fn scale_factor_value(&self) -> f32 {
+        self.lock().scale_factor
+    }
+
+    pub fn update_scale_factor(&self, scale_factor: f32) {
+        self.lock().scale_factor = scale_factor;
+        self.set_flags(InputUpdateFlags::SCALE_FACTOR);
+    }
+
     pub fn clear_update_flags(&self) {
         let flags = self.flags();
 
@@ -157,6 +199,10 @@ impl SharedInputBuffer {
             buffer.cursor_position_old = buffer.cursor_position;
         }
 
+        if flags.contains(InputUpdateFlags::MOUSE_WHEEL) {
+            self.lock().mouse_wheel = PositionF32 { x: 0.0, y: 0.0 };
+        }
+
         self.inner.flags.store(0, Ordering::Relaxed);
     }
 
@@ -3,10 +3,37 @@ use parking_lot::{Mutex, MutexGuard};
 use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
 
 pub mod keys {
+    pub const BACKQUOTE: u32 = 0;
     pub const _1: u32 = 6;
     pub const _2: u32 = 7;
     pub const _3: u32 = 8;
+    pub const _4: u32 = 9;
+    pub const A: u32 = 19;
+    pub const L: u32 = 30;
+    pub const S: u32 = 37;
+    pub const Y: u32 = 43;
+    pub const Z: u32 = 44;
+    pub const BACKSPACE: u32 = 52;
+    pub const CTRL_LEFT: u32 = 55;
+    pub const CTRL_RIGHT: u32 = 56;
+    pub const ENTER: u32 = 57;
+    pub const SHIFT_LEFT: u32 = 60;
+    pub const SHIFT_RIGHT: u32 = 61;
+    pub const SPACE: u32 = 62;
+    pub const TAB: u32 = 63;
+    pub const DELETE: u32 = 72;
+    pub const END: u32 = 73;
+    pub const HOME: u32 = 75;
+    pub const PAGE_DOWN: u32 = 77;
+    pub const PAGE_UP: u32 = 78;
+    pub const ARROW_DOWN: u32 = 79;
+    pub const ARROW_LEFT: u32 = 80;
+    pub const ARROW_RIGHT: u32 = 81;
+    pub const ARROW_UP: u32 = 82;
     pub const ESC: u32 = 114;
+
+    /// Editing keys that repeat while held down. See `SharedKeysState::write_repeat`.
+    pub const REPEATABLE: [u32; 8] = [BACKSPACE, DELETE, HOME, END, ARROW_DOWN, ARROW_LEFT, ARROW_RIGHT, ARROW_UP];
 }
 
 type KeyStateCollection = FnvHashMap<u32, SingleKeyState>;
@@ -22,6 +49,8 @@ pub enum SingleKeyState {
 #[derive(Default)]
 struct InnerKeyState {
     collection: Mutex<KeyStateCollection>,
+    text: Mutex<String>,
+    repeats: Mutex<Vec<u32>>,
     flags: AtomicBool,
 }
 
@@ -42,6 +71,21 @@ impl<'a> KeyStateGuard<'a> {
             .unwrap_or(false)
     }
 
+    /// Returns true if the key is down, whether it was pressed this update or before
+    pub fn pressed(&self, key_code: u32) -> bool {
+        self.inner.get(&key_code)
+            .map(|key| matches!(*key, SingleKeyState::Pressed | SingleKeyState::JustPressed) )
+            .unwrap_or(false)
+    }
+
+    pub fn shift(&self) -> bool {
+        self.pressed(keys::SHIFT_LEFT) || self.pressed(keys::SHIFT_RIGHT)
+    }
+
+    pub fn ctrl(&self) -> bool {
+        self.pressed(keys::CTRL_LEFT) || self.pressed(keys::CTRL_RIGHT)
+    }
+
     pub fn set_key(&mut self, key_code: u32, pressed: bool) {
         self.inner.insert(key_code, match pressed {
             true => SingleKeyState::JustPressed,
@@ -69,13 +113,49 @@ impl SharedKeysState {
         }
     }
 
+    /// Returns the state of the keys, even if no key changed since the last update. Use it to read the held modifiers.
+    pub fn read<'a>(&'a self) -> KeyStateGuard<'a> {
+        KeyStateGuard { inner: self.inner.collection.lock() }
+    }
+
     pub fn write<'a>(&'a self) -> KeyStateGuard<'a> {
         let guard = KeyStateGuard { inner: self.inner.collection.lock() };
         self.inner.flags.store(true, Ordering::SeqCst);
         guard
     }
 
+    /// Text typed by the user since the last update. Control characters are not included.
+    pub fn text(&self) -> Option<String> {
+        let text = self.inner.text.lock();
+        match text.is_empty() {
+            true => None,
+            false => Some(text.clone())
+        }
+    }
+
+    pub fn write_text(&self, value: &str) {
+        let mut text = self.inner.text.lock();
+        text.extend(value.chars().filter(|c| !c.is_control()));
+        self.inner.flags.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true if `key_code` was repeated by the system since the last update because it is held down.
+    /// `just_pressed` is only true for the first press, so only the text editing should check this.
+    pub fn repeated(&self, key_code: u32) -> bool {
+        self.inner.repeats.lock().contains(&key_code)
+    }
+
+    /// Records a repeat of a held key. Only the keys in `keys::REPEATABLE` are recorded.
+    pub fn write_repeat(&self, key_code: u32) {
+        if keys::REPEATABLE.contains(&key_code) {
+            self.inner.repeats.lock().push(key_code);
+            self.inner.flags.store(true, Ordering::SeqCst);
+        }
+    }
+
     pub fn clear_update_flags(&self) {
+        self.inner.text.lock().clear();
+        self.inner.repeats.lock().clear();
         if self.inner.flags.fetch_and(false, Ordering::Relaxed) {
             let mut collection = self.inner.collection.lock();
             for v in collection.values_mut() {
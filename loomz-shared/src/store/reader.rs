@@ -1,8 +1,11 @@
 use super::{ALIGN, StoreAndLoad};
 
+/// Reads the values written by `SaveFileWriterBase`. Reads never go past the end of `data`:
+/// values past the end read as zero or empty, and `overflowed` returns `true`.
 pub struct SaveFileReaderBase<'a> {
     pub current_offset: usize,
-    pub data: &'a [u32]
+    pub data: &'a [u32],
+    overflow: bool,
 }
 
 impl<'a> SaveFileReaderBase<'a> {
@@ -11,20 +14,24 @@ impl<'a> SaveFileReaderBase<'a> {
         SaveFileReaderBase {
             current_offset: 0,
             data,
+            overflow: false,
         }
     }
   
     pub fn read_str(&mut self) -> &str {
-        let length = self.read_u32();
-        let length_padded = self.read_u32();
+        let length = self.read_u32() as usize;
+        let length_padded = self.read_u32() as usize;
+        if length > length_padded || !self.expect(length_padded / ALIGN) {
+            return "";
+        }
 
         let str = unsafe {
-            let str_ptr = self.data.as_ptr().offset(self.current_offset as isize) as *const u8;
-            let str_bytes = ::std::slice::from_raw_parts(str_ptr, length as usize);
+            let str_ptr = self.data.as_ptr().add(self.current_offset) as *const u8;
+            let str_bytes = ::std::slice::from_raw_parts(str_ptr, length);
             ::std::str::from_utf8(str_bytes).unwrap_or("UTF8 DECODING ERROR")
         };
 
-        self.current_offset += (length_padded / 4) as usize;
+        self.current_offset += length_padded / ALIGN;
 
         str
     }
@@ -33,27 +40,31 @@ impl<'a> SaveFileReaderBase<'a> {
         let align = align_of::<T>();
         assert!(align >= ALIGN, "Alignment of T must be at least 4 bytes");
 
-        let length = self.read_u32();
+        let length = self.read_u32() as usize;
 
         // Skip padding
         while (self.current_offset*ALIGN) % align != 0 {
             self.current_offset += 1;
         }
 
+        let u32_count = length.saturating_mul(size_of::<T>()) / ALIGN;
+        if !self.expect(u32_count) {
+            return &[];
+        }
+
         let data = unsafe {
-            let data_ptr = self.data.as_ptr().offset(self.current_offset as isize) as *const T;
-            ::std::slice::from_raw_parts(data_ptr, length as usize)
+            let data_ptr = self.data.as_ptr().add(self.current_offset) as *const T;
+            ::std::slice::from_raw_parts(data_ptr, length)
         };
 
-        let u32_count = (length as usize) * size_of::<T>();
-        self.current_offset += u32_count / ALIGN;
+        self.current_offset += u32_count;
 
         data
     }
 
     pub fn read_bool_vec(&mut self) -> Vec<bool> {
-        let length = self.read_u32();
-        let mut values = Vec::with_capacity(length as usize);
+        let length = self.read_len(1);
+        let mut values = Vec::with_capacity(length);
 
         for _ in 0..length {
             values.push(self.read_u32() == 1);
@@ -63,24 +74,30 @@ impl<'a> SaveFileReaderBase<'a> {
     }
 
     pub fn read_u32(&mut self) -> u32 {
+        if !self.expect(1) {
+            return 0;
+        }
+
         let value = self.data[self.current_offset];
         self.current_offset += 1;
         value
     }
 
     pub fn read_f32(&mut self) -> f32 {
-        let value = self.data[self.current_offset];
-        self.current_offset += 1;
-        f32::from_bits(value)
+        f32::from_bits(self.read_u32())
     }
 
     pub fn read<T: Copy>(&mut self) -> T {
         assert!(align_of::<T>() == ALIGN, "Alignment of T must be at least 4 bytes");
         let u32_count = size_of::<T>() / ALIGN;
         
-        let data = unsafe {
-            let data_ptr = self.data.as_ptr().offset(self.current_offset as isize) as *const T;
-            *data_ptr
+        // Past the end of the data, the value is zeroed. The caller must check `overflowed` before using it.
+        let data = match self.expect(u32_count) {
+            true => unsafe {
+                let data_ptr = self.data.as_ptr().add(self.current_offset) as *const T;
+                *data_ptr
+            },
+            false => unsafe { ::std::mem::MaybeUninit::<T>::zeroed().assume_init() }
         };
 
         self.current_offset += u32_count;
@@ -96,12 +113,45 @@ impl<'a> SaveFileReaderBase<'a> {
         self.read_u32() == 1
     }
 
+    /// Reads the length of a list whose items are at least `item_size` u32 values.
+    /// Returns 0 if the list cannot fit in the remaining data.
+    pub fn read_len(&mut self, item_size: usize) -> usize {
+        let length = self.read_u32() as usize;
+        match self.expect(length.saturating_mul(item_size)) {
+            true => length,
+            false => 0
+        }
+    }
+
     pub fn load<T: StoreAndLoad>(&mut self) -> T {
         T::load(self)
     }
 
+    /// Number of u32 values left to read
+    pub fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.current_offset)
+    }
+
+    /// Returns `true` if a read went past the end of the data. The values read since then are not valid.
+    pub fn overflowed(&self) -> bool {
+        self.overflow
+    }
+
     pub fn skip(&mut self, count: usize) {
-        self.current_offset += count;
+        if self.expect(count) {
+            self.current_offset += count;
+        }
+    }
+
+    /// Checks that `count` u32 values are left to read. Marks the reader as overflowed if they are not.
+    fn expect(&mut self, count: usize) -> bool {
+        if self.remaining() < count {
+            self.overflow = true;
+            self.current_offset = self.data.len();
+            return false;
+        }
+
+        true
     }
 
 }
@@ -2,7 +2,7 @@ use loomz_shared::{system_err, LoomzApi, CommonError};
 
 use winit::application::ApplicationHandler;
 use winit::event_loop::{ActiveEventLoop, EventLoop, ControlFlow};
-use winit::event::{WindowEvent, ElementState, MouseButton};
+use winit::event::{WindowEvent, ElementState, MouseButton, MouseScrollDelta};
 use winit::window::{Window, WindowId};
 use super::LoomzApplication;
 
@@ -18,6 +18,8 @@ impl<'a> ApplicationHandler for LoomzApplication {
             }
         };
 
+        self.api().inputs_ref().update_scale_factor(window.scale_factor() as f32);
+
         if let Err(e) = self.set_window(window) {
             self.set_last_error(e);
             event_loop.exit();
@@ -50,16 +52,25 @@ impl<'a> ApplicationHandler for LoomzApplication {
                     event_loop.exit();
                 }
             },
+            WindowEvent::ScaleFactorChanged { scale_factor, inner_size_writer: _ } => {
+                self.api().inputs_ref().update_scale_factor(scale_factor as f32);
+            },
             WindowEvent::CursorMoved { device_id: _, position } => {
                 self.api().inputs_ref().update_cursor_position(position.x, position.y);
             },
             WindowEvent::MouseInput { device_id: _, state, button } => {
                 parse_mouse_input(self.api(), state, button);
             },
+            WindowEvent::MouseWheel { device_id: _, delta, phase: _ } => {
+                parse_mouse_wheel(self.api(), delta);
+            },
             WindowEvent::KeyboardInput { device_id: _, is_synthetic: _, event } => {
-                if !event.repeat {
-                    parse_keyboard_input(self.api(), &event);
+                match event.repeat {
+                    true => parse_keyboard_repeat(self.api(), &event),
+                    false => parse_keyboard_input(self.api(), &event),
                 }
+
+                parse_text_input(self.api(), &event);
             },
             WindowEvent::CloseRequested => {
                 event_loop.exit();
@@ -111,6 +122,18 @@ fn parse_mouse_input(api: &LoomzApi, state: ElementState, btn: MouseButton) {
     }
 }
 
+fn parse_mouse_wheel(api: &LoomzApi, delta: MouseScrollDelta) {
+    // Number of pixels scrolled for one line of a mouse wheel
+    const LINE_HEIGHT: f32 = 40.0;
+
+    let [x, y] = match delta {
+        MouseScrollDelta::LineDelta(x, y) => [x * LINE_HEIGHT, y * LINE_HEIGHT],
+        MouseScrollDelta::PixelDelta(position) => [position.x as f32, position.y as f32],
+    };
+
+    api.inputs_ref().update_mouse_wheel(x, y);
+}
+
 fn parse_keyboard_input(api: &LoomzApi, key: &winit::event::KeyEvent) {
     let key_code = match key.physical_key {
         winit::keyboard::PhysicalKey::Code(code) => code as u32,
@@ -122,6 +145,24 @@ fn parse_keyboard_input(api: &LoomzApi, key: &winit::event::KeyEvent) {
     }
 }
 
+fn parse_keyboard_repeat(api: &LoomzApi, key: &winit::event::KeyEvent) {
+    if let winit::keyboard::PhysicalKey::Code(code) = key.physical_key {
+        if key.state.is_pressed() {
+            api.keys_ref().write_repeat(code as u32);
+        }
+    }
+}
+
+fn parse_text_input(api: &LoomzApi, key: &winit::event::KeyEvent) {
+    if !key.state.is_pressed() {
+        return;
+    }
+
+    if let Some(text) = key.text.as_ref() {
+        api.keys_ref().write_text(text.as_str());
+    }
+}
+
 pub fn run(app: &mut LoomzApplication) {
     let event_loop = EventLoop::new().unwrap();
 
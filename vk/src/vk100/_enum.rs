@@ -24,6 +24,7 @@ vk_enum!(PipelineDynamicStateCreateFlags);
 vk_enum!(PipelineLayoutCreateFlags);
 vk_enum!(SamplerCreateFlags);
 vk_enum!(BufferViewCreateFlags);
+vk_enum!(QueryPoolCreateFlags);
 
 vk_enum!(InstanceCreateFlags);
 vk_bitflags!(InstanceCreateFlags);
@@ -486,6 +487,22 @@ impl QueryControlFlags {
     pub const PRECISE: Self = Self(0b1);
 }
 
+vk_enum!(QueryType);
+impl QueryType {
+    pub const OCCLUSION: Self = Self(0);
+    pub const PIPELINE_STATISTICS: Self = Self(1);
+    pub const TIMESTAMP: Self = Self(2);
+}
+
+vk_enum!(QueryResultFlags);
+vk_bitflags!(QueryResultFlags);
+impl QueryResultFlags {
+    pub const TYPE_64: Self = Self(0b1);
+    pub const WAIT: Self = Self(0b10);
+    pub const WITH_AVAILABILITY: Self = Self(0b100);
+    pub const PARTIAL: Self = Self(0b1000);
+}
+
 vk_enum!(QueryPipelineStatisticFlags);
 vk_bitflags!(QueryPipelineStatisticFlags);
 impl QueryPipelineStatisticFlags  {
@@ -26,6 +26,7 @@ define_nondispatchable_handle!(DescriptorSetLayout, DESCRIPTOR_SET_LAYOUT);
 define_nondispatchable_handle!(DescriptorPool, DESCRIPTOR_POOL);
 define_nondispatchable_handle!(Sampler, SAMPLER);
 define_nondispatchable_handle!(BufferView, BUFFER_VIEW);
+define_nondispatchable_handle!(QueryPool, QUERY_POOL);
 
 
 pub type Bool32 = u32;
@@ -1831,3 +1832,25 @@ pub struct DrawIndexedIndirectCommand {
     pub vertex_offset: i32,
     pub first_instance: u32,
 }
+
+#[repr(C)]
+pub struct QueryPoolCreateInfo {
+    pub s_type: StructureType,
+    pub p_next: *const c_void,
+    pub flags: QueryPoolCreateFlags,
+    pub query_type: QueryType,
+    pub query_count: u32,
+    pub pipeline_statistics: QueryPipelineStatisticFlags,
+}
+impl Default for QueryPoolCreateInfo {
+    fn default() -> Self {
+        Self {
+            s_type: StructureType::QUERY_POOL_CREATE_INFO,
+            p_next: ptr::null(),
+            flags: QueryPoolCreateFlags::default(),
+            query_type: QueryType::default(),
+            query_count: 0,
+            pipeline_statistics: QueryPipelineStatisticFlags::default(),
+        }
+    }
+}
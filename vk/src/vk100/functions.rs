@@ -152,6 +152,10 @@ pub struct DeviceFnV1_0 {
     pub create_buffer_view: PFN_vkCreateBufferView,
     pub destroy_buffer_view: PFN_vkDestroyBufferView,
 
+    pub create_query_pool: PFN_vkCreateQueryPool,
+    pub destroy_query_pool: PFN_vkDestroyQueryPool,
+    pub get_query_pool_results: PFN_vkGetQueryPoolResults,
+
     pub cmd_begin_render_pass: PFN_vkCmdBeginRenderPass,
     pub cmd_end_render_pass: PFN_vkCmdEndRenderPass,
     pub cmd_copy_buffer: PFN_vkCmdCopyBuffer,
@@ -168,6 +172,8 @@ pub struct DeviceFnV1_0 {
     pub cmd_copy_image_to_buffer: PFN_vkCmdCopyImageToBuffer,
     pub cmd_push_constants: PFN_vkCmdPushConstants,
     pub cmd_dispatch: PFN_vkCmdDispatch,
+    pub cmd_reset_query_pool: PFN_vkCmdResetQueryPool,
+    pub cmd_write_timestamp: PFN_vkCmdWriteTimestamp,
 }
 
 impl DeviceFnV1_0 {
@@ -252,6 +258,10 @@ impl DeviceFnV1_0 {
                 create_buffer_view: transmute(cb(cstr(b"vkCreateBufferView\0"))),
                 destroy_buffer_view: transmute(cb(cstr(b"vkDestroyBufferView\0"))),
 
+                create_query_pool: transmute(cb(cstr(b"vkCreateQueryPool\0"))),
+                destroy_query_pool: transmute(cb(cstr(b"vkDestroyQueryPool\0"))),
+                get_query_pool_results: transmute(cb(cstr(b"vkGetQueryPoolResults\0"))),
+
                 cmd_begin_render_pass: transmute(cb(cstr(b"vkCmdBeginRenderPass\0"))),
                 cmd_end_render_pass: transmute(cb(cstr(b"vkCmdEndRenderPass\0"))),
                 cmd_copy_buffer: transmute(cb(cstr(b"vkCmdCopyBuffer\0"))),
@@ -268,6 +278,8 @@ impl DeviceFnV1_0 {
                 cmd_copy_image_to_buffer: transmute(cb(cstr(b"vkCmdCopyImageToBuffer\0"))),
                 cmd_push_constants: transmute(cb(cstr(b"vkCmdPushConstants\0"))),
                 cmd_dispatch: transmute(cb(cstr(b"vkCmdDispatch\0"))),
+                cmd_reset_query_pool: transmute(cb(cstr(b"vkCmdResetQueryPool\0"))),
+                cmd_write_timestamp: transmute(cb(cstr(b"vkCmdWriteTimestamp\0"))),
             }
         }
     }
@@ -791,6 +803,44 @@ pub type PFN_vkCmdDispatch = unsafe extern "system" fn(
     group_count_z: u32,
 );
 
+pub type PFN_vkCreateQueryPool = unsafe extern "system" fn(
+    device: Device,
+    p_create_info: *const QueryPoolCreateInfo,
+    p_allocator: *const c_void,
+    p_query_pool: *mut QueryPool,
+) -> VkResult;
+
+pub type PFN_vkDestroyQueryPool = unsafe extern "system" fn(
+    device: Device,
+    query_pool: QueryPool,
+    p_allocator: *const c_void,
+);
+
+pub type PFN_vkGetQueryPoolResults = unsafe extern "system" fn(
+    device: Device,
+    query_pool: QueryPool,
+    first_query: u32,
+    query_count: u32,
+    data_size: usize,
+    p_data: *mut c_void,
+    stride: DeviceSize,
+    flags: QueryResultFlags,
+) -> VkResult;
+
+pub type PFN_vkCmdResetQueryPool = unsafe extern "system" fn(
+    command_buffer: CommandBuffer,
+    query_pool: QueryPool,
+    first_query: u32,
+    query_count: u32,
+);
+
+pub type PFN_vkCmdWriteTimestamp = unsafe extern "system" fn(
+    command_buffer: CommandBuffer,
+    pipeline_stage: PipelineStageFlags,
+    query_pool: QueryPool,
+    query: u32,
+);
+
 pub type PFN_vkCreateComputePipelines = unsafe extern "system" fn(
     device: Device,
     pipeline_cache: PipelineCache,
@@ -510,6 +510,32 @@ impl Device {
         }
     }
 
+    pub fn create_query_pool(&self, create_info: &QueryPoolCreateInfo) -> Result<QueryPool, VkResult> {
+        unsafe {
+            let mut query_pool = QueryPool::null();
+            (self.device_fn_1_0.create_query_pool)(self.handle, create_info, ptr::null(), &mut query_pool)
+                .as_result()
+                .map(|_| query_pool)
+        }
+    }
+
+    pub fn destroy_query_pool(&self, query_pool: QueryPool) {
+        unsafe {
+            (self.device_fn_1_0.destroy_query_pool)(self.handle, query_pool, ptr::null());
+        }
+    }
+
+    /// Reads one 64 bits result per query in `results`. Returns `Err(VkResult::NOT_READY)` if the results are not available yet.
+    pub fn get_query_pool_results(&self, query_pool: QueryPool, first_query: u32, results: &mut [u64], flags: QueryResultFlags) -> Result<(), VkResult> {
+        unsafe {
+            let query_count = results.len() as u32;
+            let data_size = size_of_val(results);
+            let flags = flags | QueryResultFlags::TYPE_64;
+            (self.device_fn_1_0.get_query_pool_results)(self.handle, query_pool, first_query, query_count, data_size, results.as_mut_ptr() as *mut _, size_of::<u64>() as DeviceSize, flags)
+                .as_result()
+        }
+    }
+
 
     pub fn cmd_begin_render_pass(&self, cmd: CommandBuffer, begin_info: &RenderPassBeginInfo, contents: SubpassContents) {
         unsafe {
@@ -652,6 +678,18 @@ impl Device {
         }
     }
 
+    pub fn cmd_reset_query_pool(&self, cmd: CommandBuffer, query_pool: QueryPool, first_query: u32, query_count: u32) {
+        unsafe {
+            (self.device_fn_1_0.cmd_reset_query_pool)(cmd, query_pool, first_query, query_count)
+        }
+    }
+
+    pub fn cmd_write_timestamp(&self, cmd: CommandBuffer, stage: PipelineStageFlags, query_pool: QueryPool, query: u32) {
+        unsafe {
+            (self.device_fn_1_0.cmd_write_timestamp)(cmd, stage, query_pool, query)
+        }
+    }
+
     //
     // VK 1.2
     //